@@ -0,0 +1,422 @@
+use crate::{
+    Address, AddressError, AddressResult,
+    chains::tron::{b58decode_check, b58encode_check},
+};
+use bech32::{Hrp, hrp, segwit};
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField, sec1::ToEncodedPoint},
+};
+use ripemd::Ripemd160;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+
+/// The BIP341 tag of the hash tweaking the internal key of a Taproot output.
+const TAP_TWEAK_TAG: &[u8] = b"TapTweak";
+
+/// A Bitcoin address.
+///
+/// A P2SH address only commits to the hash of a script, so a parsed one is assumed to wrap
+/// a P2WPKH script, the only kind this wallet creates.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitcoinAddress {
+    network: BitcoinNetwork,
+    address_type: BitcoinAddressType,
+    /// The key or script hash, or the Taproot output key.
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitcoinPublicKey(k256::PublicKey);
+
+impl From<k256::PublicKey> for BitcoinPublicKey {
+    fn from(public_key: k256::PublicKey) -> Self {
+        BitcoinPublicKey(public_key)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BitcoinNetwork {
+    Mainnet,
+    Testnet,
+}
+
+impl BitcoinNetwork {
+    fn p2pkh_version(&self) -> u8 {
+        match self {
+            BitcoinNetwork::Mainnet => 0x00,
+            BitcoinNetwork::Testnet => 0x6f,
+        }
+    }
+
+    fn p2sh_version(&self) -> u8 {
+        match self {
+            BitcoinNetwork::Mainnet => 0x05,
+            BitcoinNetwork::Testnet => 0xc4,
+        }
+    }
+
+    fn hrp(&self) -> Hrp {
+        match self {
+            BitcoinNetwork::Mainnet => hrp::BC,
+            BitcoinNetwork::Testnet => hrp::TB,
+        }
+    }
+}
+
+impl fmt::Display for BitcoinNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BitcoinNetwork::Mainnet => write!(f, "mainnet"),
+            BitcoinNetwork::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BitcoinAddressType {
+    /// Legacy pay-to-public-key-hash, `1...`.
+    P2pkh,
+    /// Nested SegWit, a P2WPKH script wrapped in pay-to-script-hash, `3...`.
+    P2shP2wpkh,
+    /// Native SegWit v0, bech32 `bc1q...`.
+    P2wpkh,
+    /// Taproot, SegWit v1, bech32m `bc1p...`.
+    P2tr,
+}
+
+impl fmt::Display for BitcoinAddressType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            BitcoinAddressType::P2pkh => "P2PKH",
+            BitcoinAddressType::P2shP2wpkh => "P2SH-P2WPKH",
+            BitcoinAddressType::P2wpkh => "P2WPKH",
+            BitcoinAddressType::P2tr => "P2TR",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Represents the format of a Bitcoin address
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitcoinFormat {
+    pub address_type: BitcoinAddressType,
+    pub network: BitcoinNetwork,
+}
+
+impl BitcoinFormat {
+    pub fn new(address_type: BitcoinAddressType, network: BitcoinNetwork) -> Self {
+        Self {
+            address_type,
+            network,
+        }
+    }
+}
+
+impl fmt::Display for BitcoinFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.address_type, self.network)
+    }
+}
+
+impl Address for BitcoinAddress {
+    type Format = BitcoinFormat;
+    type PublicKey = BitcoinPublicKey;
+
+    fn from_public_key(public_key: &Self::PublicKey, format: &Self::Format) -> AddressResult<Self> {
+        let compressed = public_key.0.to_encoded_point(true);
+        let payload = match format.address_type {
+            BitcoinAddressType::P2pkh | BitcoinAddressType::P2wpkh => {
+                hash160(compressed.as_bytes()).to_vec()
+            }
+            BitcoinAddressType::P2shP2wpkh => {
+                let mut redeem_script = vec![0x00, 0x14];
+                redeem_script.extend(hash160(compressed.as_bytes()));
+                hash160(&redeem_script).to_vec()
+            }
+            BitcoinAddressType::P2tr => taproot_output_key(&public_key.0)?.to_vec(),
+        };
+
+        Ok(BitcoinAddress {
+            network: format.network,
+            address_type: format.address_type,
+            payload,
+        })
+    }
+}
+
+impl BitcoinAddress {
+    pub fn network(&self) -> BitcoinNetwork {
+        self.network
+    }
+
+    pub fn address_type(&self) -> BitcoinAddressType {
+        self.address_type
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.payload
+    }
+
+    fn from_base58(s: &str) -> AddressResult<Self> {
+        let raw = b58decode_check(s)?;
+        if raw.len() != 21 {
+            return Err(AddressError::InvalidAddress("Invalid length".to_string()));
+        }
+        let (network, address_type) = [BitcoinNetwork::Mainnet, BitcoinNetwork::Testnet]
+            .into_iter()
+            .find_map(|network| {
+                if raw[0] == network.p2pkh_version() {
+                    Some((network, BitcoinAddressType::P2pkh))
+                } else if raw[0] == network.p2sh_version() {
+                    Some((network, BitcoinAddressType::P2shP2wpkh))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| AddressError::Message(format!("Invalid version byte {}", raw[0])))?;
+
+        Ok(BitcoinAddress {
+            network,
+            address_type,
+            payload: raw[1..].to_vec(),
+        })
+    }
+
+    fn from_bech32(s: &str) -> AddressResult<Self> {
+        let (hrp, version, program) =
+            segwit::decode(s).map_err(|e| AddressError::InvalidAddress(e.to_string()))?;
+        let network = [BitcoinNetwork::Mainnet, BitcoinNetwork::Testnet]
+            .into_iter()
+            .find(|network| network.hrp() == hrp)
+            .ok_or_else(|| AddressError::Message(format!("Unknown prefix {hrp}")))?;
+        let address_type = match (version, program.len()) {
+            (segwit::VERSION_0, 20) => BitcoinAddressType::P2wpkh,
+            (segwit::VERSION_1, 32) => BitcoinAddressType::P2tr,
+            _ => {
+                return Err(AddressError::Message(format!(
+                    "Unsupported witness program: version {}, {} bytes",
+                    version.to_u8(),
+                    program.len()
+                )));
+            }
+        };
+
+        Ok(BitcoinAddress {
+            network,
+            address_type,
+            payload: program,
+        })
+    }
+}
+
+impl fmt::Display for BitcoinAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let version = match self.address_type {
+            BitcoinAddressType::P2pkh => self.network.p2pkh_version(),
+            BitcoinAddressType::P2shP2wpkh => self.network.p2sh_version(),
+            BitcoinAddressType::P2wpkh => {
+                let address =
+                    segwit::encode_v0(self.network.hrp(), &self.payload).map_err(|_| fmt::Error)?;
+                return address.fmt(f);
+            }
+            BitcoinAddressType::P2tr => {
+                let address =
+                    segwit::encode_v1(self.network.hrp(), &self.payload).map_err(|_| fmt::Error)?;
+                return address.fmt(f);
+            }
+        };
+        let mut raw = vec![version];
+        raw.extend(&self.payload);
+        b58encode_check(raw).fmt(f)
+    }
+}
+
+impl fmt::Debug for BitcoinAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Address").field(&self.to_string()).finish()
+    }
+}
+
+impl FromStr for BitcoinAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> AddressResult<Self> {
+        let prefix = s.get(..3).map(str::to_ascii_lowercase);
+        match prefix.as_deref() {
+            Some("bc1") | Some("tb1") => Self::from_bech32(s),
+            _ => Self::from_base58(s),
+        }
+    }
+}
+
+impl Serialize for BitcoinAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for BitcoinAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+/// The x-only output key of a Taproot key path spend without a script tree (BIP86).
+fn taproot_output_key(public_key: &k256::PublicKey) -> AddressResult<[u8; 32]> {
+    let compressed = public_key.to_encoded_point(true);
+    let x_only = &compressed.as_bytes()[1..];
+    // BIP340 keys have an even y coordinate.
+    let internal_key = match compressed.as_bytes()[0] {
+        0x03 => -public_key.to_projective(),
+        _ => public_key.to_projective(),
+    };
+
+    let tag = Sha256::digest(TAP_TWEAK_TAG);
+    let tweak = Sha256::new()
+        .chain_update(tag)
+        .chain_update(tag)
+        .chain_update(x_only)
+        .finalize();
+    let tweak = Option::<Scalar>::from(Scalar::from_repr(tweak))
+        .ok_or_else(|| AddressError::Message("Invalid Taproot tweak".to_string()))?;
+
+    let output_key = (internal_key + ProjectivePoint::GENERATOR * tweak).to_affine();
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&output_key.to_encoded_point(true).as_bytes()[1..]);
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The generator point, used by the BIP173 examples.
+    const PUBLIC_KEY: &str = "0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798";
+
+    fn public_key(hex: &str) -> BitcoinPublicKey {
+        k256::PublicKey::from_sec1_bytes(&hex::decode(hex).unwrap())
+            .unwrap()
+            .into()
+    }
+
+    fn address(public_key: &BitcoinPublicKey, address_type: BitcoinAddressType) -> String {
+        let format = BitcoinFormat::new(address_type, BitcoinNetwork::Mainnet);
+        BitcoinAddress::from_public_key(public_key, &format)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_from_public_key() {
+        let public_key = public_key(PUBLIC_KEY);
+        assert_eq!(
+            address(&public_key, BitcoinAddressType::P2pkh),
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+        );
+        assert_eq!(
+            address(&public_key, BitcoinAddressType::P2wpkh),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        let testnet = BitcoinFormat::new(BitcoinAddressType::P2wpkh, BitcoinNetwork::Testnet);
+        assert_eq!(
+            BitcoinAddress::from_public_key(&public_key, &testnet)
+                .unwrap()
+                .to_string(),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+        );
+    }
+
+    #[test]
+    fn test_taproot_output_key() {
+        // BIP86, m/86'/0'/0'/0/0 of "abandon abandon ... about".
+        let public_key =
+            public_key("03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115");
+        assert_eq!(
+            address(&public_key, BitcoinAddressType::P2tr),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        for (s, network, address_type) in [
+            (
+                "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+                BitcoinNetwork::Mainnet,
+                BitcoinAddressType::P2pkh,
+            ),
+            (
+                "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf",
+                BitcoinNetwork::Mainnet,
+                BitcoinAddressType::P2shP2wpkh,
+            ),
+            (
+                "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2",
+                BitcoinNetwork::Testnet,
+                BitcoinAddressType::P2shP2wpkh,
+            ),
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                BitcoinNetwork::Mainnet,
+                BitcoinAddressType::P2wpkh,
+            ),
+            (
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+                BitcoinNetwork::Mainnet,
+                BitcoinAddressType::P2tr,
+            ),
+        ] {
+            let address: BitcoinAddress = s.parse().unwrap();
+            assert_eq!(address.network(), network, "{s}");
+            assert_eq!(address.address_type(), address_type, "{s}");
+            assert_eq!(address.to_string(), s);
+        }
+
+        // Upper case bech32 is valid, and written back in lower case.
+        let address: BitcoinAddress = "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            address.to_string(),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "",
+            "1",
+            // Bad checksum.
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMh",
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+            // A v1 program encoded with bech32 instead of bech32m (BIP350).
+            "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7k7grplx",
+            // A Tron address.
+            "TPhiVyQZ5xyvVK2KS2LTke8YvXJU5wxnbN",
+        ] {
+            assert!(s.parse::<BitcoinAddress>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_serde() {
+        let address: BitcoinAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, "\"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\"");
+        assert_eq!(
+            serde_json::from_str::<BitcoinAddress>(&json).unwrap(),
+            address
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{Address, AddressError, AddressResult, TronAddress};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Hash)]
+pub struct EthereumAddress([u8; 20]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthereumPublicKey(k256::PublicKey);
+
+impl From<k256::PublicKey> for EthereumPublicKey {
+    fn from(public_key: k256::PublicKey) -> Self {
+        EthereumPublicKey(public_key)
+    }
+}
+
+/// Represents the format of an Ethereum address
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EthereumFormat {
+    Standard,
+}
+
+impl fmt::Display for EthereumFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EthereumFormat")
+    }
+}
+
+impl Address for EthereumAddress {
+    type Format = EthereumFormat;
+    type PublicKey = EthereumPublicKey;
+
+    fn from_public_key(
+        public_key: &Self::PublicKey,
+        _format: &Self::Format,
+    ) -> AddressResult<Self> {
+        let digest = Keccak256::digest(&public_key.0.to_encoded_point(false).as_bytes()[1..]);
+        let mut raw = [0u8; 20];
+        raw.copy_from_slice(&digest[digest.len() - 20..]);
+
+        Ok(EthereumAddress(raw))
+    }
+}
+
+impl EthereumAddress {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn from_bytes(raw: &[u8]) -> AddressResult<Self> {
+        if raw.len() != 20 {
+            return Err(AddressError::InvalidAddress("Invalid length".to_string()));
+        }
+
+        let mut address = [0u8; 20];
+        address.copy_from_slice(raw);
+        Ok(EthereumAddress(address))
+    }
+
+    /// Parses an address without verifying its EIP-55 checksum, with or without `0x`.
+    ///
+    /// Prefer [`FromStr`], which rejects mixed-case addresses with a wrong checksum.
+    pub fn from_str_lenient(s: &str) -> AddressResult<Self> {
+        let hex = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if hex.len() != 40 {
+            return Err(AddressError::InvalidAddress("Invalid length".to_string()));
+        }
+        hex::decode(hex)
+            .map_err(|_| AddressError::InvalidAddress("Invalid hex".to_string()))
+            .and_then(|raw| EthereumAddress::from_bytes(&raw))
+    }
+
+    /// The EIP-55 mixed-case checksum encoding, with the `0x` prefix.
+    pub fn to_checksum(&self) -> String {
+        let hex = hex::encode(self.0);
+        let hash = Keccak256::digest(hex.as_bytes());
+
+        let mut checksummed = String::with_capacity(42);
+        checksummed.push_str("0x");
+        for (i, c) in hex.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                checksummed.push(c.to_ascii_uppercase());
+            } else {
+                checksummed.push(c);
+            }
+        }
+        checksummed
+    }
+}
+
+impl fmt::Display for EthereumAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.to_checksum().fmt(f)
+    }
+}
+
+impl fmt::Debug for EthereumAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Address").field(&self.to_string()).finish()
+    }
+}
+
+impl TryFrom<&[u8]> for EthereumAddress {
+    type Error = AddressError;
+
+    fn try_from(value: &[u8]) -> AddressResult<Self> {
+        EthereumAddress::from_bytes(value)
+    }
+}
+
+impl FromStr for EthereumAddress {
+    type Err = AddressError;
+
+    /// Parses a `0x` prefixed address.
+    ///
+    /// All-lowercase and all-uppercase addresses carry no checksum and are accepted as is,
+    /// mixed-case addresses must have a valid EIP-55 checksum.
+    fn from_str(s: &str) -> AddressResult<Self> {
+        let Some(hex) = s.strip_prefix("0x") else {
+            return Err(AddressError::InvalidAddress(
+                "Missing 0x prefix".to_string(),
+            ));
+        };
+        let address = EthereumAddress::from_str_lenient(s)?;
+
+        let has_lowercase = hex.chars().any(|c| c.is_ascii_lowercase());
+        let has_uppercase = hex.chars().any(|c| c.is_ascii_uppercase());
+        if has_lowercase && has_uppercase && address.to_checksum() != s {
+            return Err(AddressError::InvalidAddress("Invalid checksum".to_string()));
+        }
+        Ok(address)
+    }
+}
+
+/// Tron and Ethereum addresses of the same key share the key hash, Tron adds a prefix.
+impl From<&TronAddress> for EthereumAddress {
+    fn from(address: &TronAddress) -> Self {
+        let mut raw = [0u8; 20];
+        raw.copy_from_slice(&address.as_bytes()[1..]);
+        EthereumAddress(raw)
+    }
+}
+
+impl From<TronAddress> for EthereumAddress {
+    fn from(address: TronAddress) -> Self {
+        EthereumAddress::from(&address)
+    }
+}
+
+impl From<&EthereumAddress> for TronAddress {
+    fn from(address: &EthereumAddress) -> Self {
+        TronAddress::from_key_hash(address.0)
+    }
+}
+
+impl From<EthereumAddress> for TronAddress {
+    fn from(address: EthereumAddress) -> Self {
+        TronAddress::from(&address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chains::tron::TronFormat;
+    use k256::{EncodedPoint, PublicKey, SecretKey};
+
+    /// The examples of EIP-55.
+    const EIP55_VECTORS: [&str; 8] = [
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_eip55_checksum() {
+        for vector in &EIP55_VECTORS[4..] {
+            let address: EthereumAddress = vector.parse().expect("parse error");
+            assert_eq!(address.to_string(), *vector);
+
+            let lowercase: EthereumAddress = vector
+                .to_lowercase()
+                .replacen("0X", "0x", 1)
+                .parse()
+                .unwrap();
+            assert_eq!(lowercase, address);
+        }
+        // All-caps and all-lowercase addresses have no checksum.
+        for vector in &EIP55_VECTORS[..4] {
+            assert!(vector.parse::<EthereumAddress>().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_strict_and_lenient_parsing() {
+        // One letter of a valid checksum in the wrong case.
+        let bad_checksum = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        assert!(bad_checksum.parse::<EthereumAddress>().is_err());
+        assert!(EthereumAddress::from_str_lenient(bad_checksum).is_ok());
+
+        let without_prefix = "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(without_prefix.parse::<EthereumAddress>().is_err());
+        assert_eq!(
+            EthereumAddress::from_str_lenient(without_prefix).unwrap(),
+            EIP55_VECTORS[4].parse().unwrap()
+        );
+
+        assert!(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"
+                .parse::<EthereumAddress>()
+                .is_err()
+        );
+        assert!(
+            EthereumAddress::from_str_lenient("0xzzAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_from_public_key() {
+        // The key of private key 1 is the generator point.
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        let public_key = SecretKey::from_slice(&secret).unwrap().public_key();
+
+        let address =
+            EthereumAddress::from_public_key(&public_key.into(), &EthereumFormat::Standard)
+                .unwrap();
+        assert_eq!(
+            address.to_string(),
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+        );
+    }
+
+    #[test]
+    fn test_tron_conversion() {
+        let public_key_hex = "040738c1aa72b07ff1a894198374a34c760913db0e6a5679d48477873b8f1fa865a5923ae7346c3c717579006f9f853adcb7bb5563022775591895253a0457f0df";
+        let encoded_point = EncodedPoint::from_bytes(hex::decode(public_key_hex).unwrap()).unwrap();
+        let public_key = PublicKey::try_from(encoded_point).unwrap();
+
+        let ethereum =
+            EthereumAddress::from_public_key(&public_key.into(), &EthereumFormat::Standard)
+                .unwrap();
+        let tron = TronAddress::from_public_key(&public_key.into(), &TronFormat::Standard).unwrap();
+
+        assert_eq!(TronAddress::from(&ethereum), tron);
+        assert_eq!(EthereumAddress::from(&tron), ethereum);
+        assert_eq!(tron.to_string(), "TWer2Ygk5TEheHp3TPuYeqxmB6SsGZmaL6");
+    }
+}
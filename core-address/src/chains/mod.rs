@@ -1,2 +1,11 @@
+mod bitcoin;
+mod ethereum;
 mod tron;
-pub use tron::TronAddress;
+pub use bitcoin::{
+    BitcoinAddress, BitcoinAddressType, BitcoinFormat, BitcoinNetwork, BitcoinPublicKey,
+};
+pub use ethereum::{EthereumAddress, EthereumFormat, EthereumPublicKey};
+pub use tron::{
+    TronAddress, TronFormat, TronPublicKey, b58decode_check, b58encode_check, tron_hex,
+    tron_zero_x_hex,
+};
@@ -1,28 +1,71 @@
 use crate::{Address, AddressError, AddressResult};
 use hex::FromHex;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
 use sha2::{Digest, Sha256};
 use sha3::Keccak256;
 use std::{fmt, str::FromStr};
 
 const ADDRESS_TYPE_PREFIX: u8 = 0x41;
 
-#[derive(Clone, PartialEq, Eq, Serialize, Hash)]
+/// A Tron address.
+///
+/// It serializes as a base58 string, and deserializes from any of the [`TronFormat`]s. Use
+/// [`tron_hex`] or [`tron_zero_x_hex`] to serialize as hex instead.
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct TronAddress([u8; 21]);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TronPublicKey(k256::PublicKey);
 
+impl TronPublicKey {
+    /// A key in the 33 bytes compressed SEC1 encoding.
+    pub fn from_compressed(bytes: &[u8]) -> AddressResult<Self> {
+        Self::from_sec1(bytes, 33, &[0x02, 0x03])
+    }
+
+    /// A key in the 65 bytes uncompressed SEC1 encoding.
+    pub fn from_uncompressed(bytes: &[u8]) -> AddressResult<Self> {
+        Self::from_sec1(bytes, 65, &[0x04])
+    }
+
+    fn from_sec1(bytes: &[u8], len: usize, tags: &[u8]) -> AddressResult<Self> {
+        if bytes.len() != len || !tags.contains(&bytes[0]) {
+            return Err(AddressError::Message(format!(
+                "Expected a {len} bytes SEC1 public key"
+            )));
+        }
+        k256::PublicKey::from_sec1_bytes(bytes)
+            .map(TronPublicKey)
+            .map_err(|_| AddressError::Message("Invalid public key".to_string()))
+    }
+}
+
+impl From<k256::PublicKey> for TronPublicKey {
+    fn from(public_key: k256::PublicKey) -> Self {
+        TronPublicKey(public_key)
+    }
+}
+
 /// Represents the format of a Tron address
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TronFormat {
+    /// Base58check, `T...`.
     Standard,
+    /// `41...`
+    Hex,
+    /// `0x41...`
+    ZeroXHex,
 }
 
 impl fmt::Display for TronFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "TronFormat")
+        let s = match self {
+            TronFormat::Standard => "base58",
+            TronFormat::Hex => "hex",
+            TronFormat::ZeroXHex => "0x hex",
+        };
+        f.write_str(s)
     }
 }
 
@@ -50,6 +93,26 @@ impl TronAddress {
         &self.0
     }
 
+    /// The address written in the given format, `Display` uses [`TronFormat::Standard`].
+    pub fn format(&self, format: &TronFormat) -> String {
+        match format {
+            TronFormat::Standard => b58encode_check(self.0),
+            TronFormat::Hex => hex::encode(self.0),
+            TronFormat::ZeroXHex => format!("0x{}", hex::encode(self.0)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 21] {
+        self.0
+    }
+
+    /// The address of a 20-byte key hash, as shared with Ethereum.
+    pub(crate) fn from_key_hash(key_hash: [u8; 20]) -> Self {
+        let mut raw = [ADDRESS_TYPE_PREFIX; 21];
+        raw[1..].copy_from_slice(&key_hash);
+        TronAddress(raw)
+    }
+
     pub fn from_bytes(raw: &[u8]) -> AddressResult<Self> {
         if raw.len() != 21 {
             return Err(AddressError::InvalidAddress("Invalid length".to_string()));
@@ -125,6 +188,59 @@ impl FromStr for TronAddress {
     }
 }
 
+impl Serialize for TronAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.format(&TronFormat::Standard))
+    }
+}
+
+impl<'de> Deserialize<'de> for TronAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serializes a [`TronAddress`] as `41...` hex: `#[serde(with = "cwu_core_address::tron_hex")]`.
+pub mod tron_hex {
+    use super::{TronAddress, TronFormat};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        address: &TronAddress,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&address.format(&TronFormat::Hex))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TronAddress, D::Error> {
+        TronAddress::deserialize(deserializer)
+    }
+}
+
+/// Serializes a [`TronAddress`] as `0x41...` hex:
+/// `#[serde(with = "cwu_core_address::tron_zero_x_hex")]`.
+pub mod tron_zero_x_hex {
+    use super::{TronAddress, TronFormat};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        address: &TronAddress,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&address.format(&TronFormat::ZeroXHex))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TronAddress, D::Error> {
+        TronAddress::deserialize(deserializer)
+    }
+}
+
 /// Base58check encode.
 pub fn b58encode_check<T: AsRef<[u8]>>(raw: T) -> String {
     let mut hasher = Sha256::new();
@@ -145,6 +261,9 @@ pub fn b58decode_check(s: &str) -> AddressResult<Vec<u8>> {
     let mut result = bs58::decode(s)
         .into_vec()
         .map_err(|_| AddressError::InvalidAddress("".to_string()))?;
+    if result.len() < 4 {
+        return Err(AddressError::InvalidAddress("".to_string()));
+    }
 
     let check = result.split_off(result.len() - 4);
 
@@ -232,4 +351,82 @@ mod tests {
             "Derived Tron Address does not match expected address."
         );
     }
+
+    #[test]
+    fn test_public_key_from_sec1_bytes() {
+        let uncompressed = hex::decode("040738c1aa72b07ff1a894198374a34c760913db0e6a5679d48477873b8f1fa865a5923ae7346c3c717579006f9f853adcb7bb5563022775591895253a0457f0df").unwrap();
+        let compressed =
+            hex::decode("030738c1aa72b07ff1a894198374a34c760913db0e6a5679d48477873b8f1fa865")
+                .unwrap();
+
+        let public_key = TronPublicKey::from_uncompressed(&uncompressed).unwrap();
+        assert_eq!(
+            TronPublicKey::from_compressed(&compressed).unwrap(),
+            public_key
+        );
+        assert_eq!(
+            TronAddress::from_public_key(&public_key, &TronFormat::Standard)
+                .unwrap()
+                .to_string(),
+            "TWer2Ygk5TEheHp3TPuYeqxmB6SsGZmaL6"
+        );
+
+        assert!(TronPublicKey::from_compressed(&uncompressed).is_err());
+        assert!(TronPublicKey::from_uncompressed(&compressed).is_err());
+        // The x coordinate is larger than the field modulus.
+        let mut off_curve = [0xff; 33];
+        off_curve[0] = 0x02;
+        assert!(TronPublicKey::from_compressed(&off_curve).is_err());
+    }
+
+    #[test]
+    fn test_formats_and_serde() {
+        let addr: TronAddress = "TPhiVyQZ5xyvVK2KS2LTke8YvXJU5wxnbN".parse().unwrap();
+        assert_eq!(
+            addr.format(&TronFormat::Standard),
+            "TPhiVyQZ5xyvVK2KS2LTke8YvXJU5wxnbN"
+        );
+        assert_eq!(
+            addr.format(&TronFormat::Hex),
+            "4196a3bace5adacf637eb7cc79d5787f4247da4bbe"
+        );
+        assert_eq!(
+            addr.format(&TronFormat::ZeroXHex),
+            "0x4196a3bace5adacf637eb7cc79d5787f4247da4bbe"
+        );
+        for format in [TronFormat::Standard, TronFormat::Hex, TronFormat::ZeroXHex] {
+            assert_eq!(addr.format(&format).parse::<TronAddress>().unwrap(), addr);
+        }
+
+        let json = serde_json::to_string(&addr).unwrap();
+        assert_eq!(json, "\"TPhiVyQZ5xyvVK2KS2LTke8YvXJU5wxnbN\"");
+        assert_eq!(serde_json::from_str::<TronAddress>(&json).unwrap(), addr);
+        assert_eq!(
+            serde_json::from_str::<TronAddress>("\"0x4196a3bace5adacf637eb7cc79d5787f4247da4bbe\"")
+                .unwrap(),
+            addr
+        );
+        assert!(
+            serde_json::from_str::<TronAddress>("\"TPhiVyQZ5xyvVK2KS2LTke8YvXJU5wxnbn\"").is_err()
+        );
+
+        #[derive(Serialize, Deserialize)]
+        struct Hex {
+            #[serde(with = "tron_hex")]
+            hex: TronAddress,
+            #[serde(with = "tron_zero_x_hex")]
+            zero_x: TronAddress,
+        }
+        let json = serde_json::to_string(&Hex {
+            hex: addr.clone(),
+            zero_x: addr.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"hex":"4196a3bace5adacf637eb7cc79d5787f4247da4bbe","zero_x":"0x4196a3bace5adacf637eb7cc79d5787f4247da4bbe"}"#
+        );
+        let parsed: Hex = serde_json::from_str(&json).unwrap();
+        assert_eq!((parsed.hex, parsed.zero_x), (addr.clone(), addr));
+    }
 }
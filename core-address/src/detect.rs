@@ -0,0 +1,424 @@
+use crate::{
+    AddressError, AddressResult, BitcoinAddress, EthereumAddress, NetworkAddress, TronAddress,
+};
+use cwu_model::Network;
+use std::fmt;
+
+const TRON_BASE58_LENGTH: usize = 34;
+const TRON_HEX_LENGTH: usize = 42;
+const ETHEREUM_HEX_LENGTH: usize = 40;
+const BITCOIN_BASE58_PREFIXES: &[char] = &['1', '3', 'm', 'n', '2'];
+const BITCOIN_BASE58_MAX_LENGTH: usize = 35;
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Why an input is not (exactly) a valid address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressDiagnostic {
+    Empty,
+    /// The input doesn't look like an address of any supported network.
+    UnknownFormat,
+    InvalidLength {
+        network: Network,
+        expected: usize,
+        found: usize,
+    },
+    /// A character that can't appear in the address, `position` counts from 1.
+    InvalidCharacter {
+        network: Network,
+        character: char,
+        position: usize,
+    },
+    /// The address is well-formed, but a character was mistyped.
+    ChecksumMismatch {
+        network: Network,
+    },
+    /// An Ethereum address without its `0x` prefix: accepted, but worth a second look.
+    MissingPrefix,
+}
+
+impl fmt::Display for AddressDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressDiagnostic::Empty => write!(f, "The address is empty"),
+            AddressDiagnostic::UnknownFormat => {
+                write!(
+                    f,
+                    "Not a Tron (T..., 41...), Ethereum (0x...) or Bitcoin (1..., 3..., bc1...) address"
+                )
+            }
+            AddressDiagnostic::InvalidLength {
+                network,
+                expected,
+                found,
+            } => write!(
+                f,
+                "A {network} address has {expected} characters, this one has {found}"
+            ),
+            AddressDiagnostic::InvalidCharacter {
+                network,
+                character,
+                position,
+            } => write!(
+                f,
+                "'{character}' at position {position} can't appear in a {network} address"
+            ),
+            AddressDiagnostic::ChecksumMismatch { network } => write!(
+                f,
+                "The {network} address checksum doesn't match, a character is probably mistyped"
+            ),
+            AddressDiagnostic::MissingPrefix => {
+                write!(f, "The Ethereum address is missing its 0x prefix")
+            }
+        }
+    }
+}
+
+/// What [`classify_address`] found out about an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressClassification {
+    candidates: Vec<Network>,
+    address: Option<NetworkAddress>,
+    diagnostics: Vec<AddressDiagnostic>,
+}
+
+impl AddressClassification {
+    /// The networks the input looks like an address of.
+    pub fn candidates(&self) -> &[Network] {
+        self.candidates.as_slice()
+    }
+
+    /// The network of the input, when it can only be one.
+    pub fn network(&self) -> Option<Network> {
+        match self.candidates.as_slice() {
+            [network] => Some(*network),
+            _ => None,
+        }
+    }
+
+    /// The parsed address, if the input is a valid one.
+    pub fn address(&self) -> Option<&NetworkAddress> {
+        self.address.as_ref()
+    }
+
+    pub fn diagnostics(&self) -> &[AddressDiagnostic] {
+        self.diagnostics.as_slice()
+    }
+
+    /// The valid address, or an error explaining what is wrong with the input.
+    pub fn into_address(self) -> AddressResult<NetworkAddress> {
+        match self.address {
+            Some(address) => Ok(address),
+            None => Err(AddressError::InvalidAddress(
+                self.diagnostics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            )),
+        }
+    }
+
+    fn new(network: Network) -> Self {
+        Self {
+            candidates: vec![network],
+            address: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn unknown(diagnostic: AddressDiagnostic) -> Self {
+        Self {
+            candidates: Vec::new(),
+            address: None,
+            diagnostics: vec![diagnostic],
+        }
+    }
+
+    fn with_diagnostic(mut self, diagnostic: AddressDiagnostic) -> Self {
+        self.diagnostics.push(diagnostic);
+        self
+    }
+}
+
+/// Tells which network an address, typically pasted by a user, belongs to.
+///
+/// Recognized inputs are Tron base58 (`T...`), Tron hex (`41...`, `0x41...`), Ethereum
+/// hex (`0x...`, or without the prefix) and Bitcoin base58 (`1...`, `3...`, testnet `m...`,
+/// `n...`, `2...`) or bech32 (`bc1...`, testnet `tb1...`). Surrounding whitespace is ignored. The input is
+/// classified even if it is invalid, with diagnostics that explain the likely typo.
+pub fn classify_address(input: &str) -> AddressClassification {
+    let input = input.trim();
+    let hex_digits = |s: &str| s.chars().all(|c| c.is_ascii_hexdigit());
+
+    if input.is_empty() {
+        AddressClassification::unknown(AddressDiagnostic::Empty)
+    } else if input.starts_with('T') {
+        classify_tron_base58(input)
+    } else if let Some(hex) = input.strip_prefix("0x").or(input.strip_prefix("0X")) {
+        if hex.len() == TRON_HEX_LENGTH && hex.starts_with("41") && hex_digits(hex) {
+            classify_tron_hex(input)
+        } else {
+            classify_ethereum(input, hex)
+        }
+    } else if input.len() == TRON_HEX_LENGTH && input.starts_with("41") && hex_digits(input) {
+        classify_tron_hex(input)
+    } else if input.len() == ETHEREUM_HEX_LENGTH && hex_digits(input) {
+        classify_ethereum(input, input).with_diagnostic(AddressDiagnostic::MissingPrefix)
+    } else if input.get(..3).is_some_and(|prefix| {
+        prefix.eq_ignore_ascii_case("bc1") || prefix.eq_ignore_ascii_case("tb1")
+    }) {
+        classify_bitcoin_bech32(input)
+    } else if input.starts_with(BITCOIN_BASE58_PREFIXES) && input.len() <= BITCOIN_BASE58_MAX_LENGTH
+    {
+        classify_bitcoin_base58(input)
+    } else {
+        AddressClassification::unknown(AddressDiagnostic::UnknownFormat)
+    }
+}
+
+fn classify_bitcoin_base58(input: &str) -> AddressClassification {
+    let classification = AddressClassification::new(Network::Bitcoin);
+    if let Err(bs58::decode::Error::InvalidCharacter { character, index }) =
+        bs58::decode(input).into_vec()
+    {
+        return classification.with_diagnostic(AddressDiagnostic::InvalidCharacter {
+            network: Network::Bitcoin,
+            character,
+            position: index + 1,
+        });
+    }
+    match input.parse::<BitcoinAddress>() {
+        Ok(address) => AddressClassification {
+            address: Some(address.into()),
+            ..classification
+        },
+        Err(_) => classification.with_diagnostic(AddressDiagnostic::ChecksumMismatch {
+            network: Network::Bitcoin,
+        }),
+    }
+}
+
+fn classify_bitcoin_bech32(input: &str) -> AddressClassification {
+    let classification = AddressClassification::new(Network::Bitcoin);
+    // Skip the human readable part and its separator.
+    if let Some((index, character)) = input
+        .char_indices()
+        .skip(3)
+        .find(|(_, c)| !BECH32_CHARSET.contains(c.to_ascii_lowercase()))
+    {
+        return classification.with_diagnostic(AddressDiagnostic::InvalidCharacter {
+            network: Network::Bitcoin,
+            character,
+            position: index + 1,
+        });
+    }
+    match input.parse::<BitcoinAddress>() {
+        Ok(address) => AddressClassification {
+            address: Some(address.into()),
+            ..classification
+        },
+        Err(_) => classification.with_diagnostic(AddressDiagnostic::ChecksumMismatch {
+            network: Network::Bitcoin,
+        }),
+    }
+}
+
+fn classify_tron_base58(input: &str) -> AddressClassification {
+    let classification = AddressClassification::new(Network::Tron);
+    if let Err(bs58::decode::Error::InvalidCharacter { character, index }) =
+        bs58::decode(input).into_vec()
+    {
+        return classification.with_diagnostic(AddressDiagnostic::InvalidCharacter {
+            network: Network::Tron,
+            character,
+            position: index + 1,
+        });
+    }
+    if input.len() != TRON_BASE58_LENGTH {
+        return classification.with_diagnostic(AddressDiagnostic::InvalidLength {
+            network: Network::Tron,
+            expected: TRON_BASE58_LENGTH,
+            found: input.len(),
+        });
+    }
+    match input.parse::<TronAddress>() {
+        Ok(address) => AddressClassification {
+            address: Some(address.into()),
+            ..classification
+        },
+        Err(_) => classification.with_diagnostic(AddressDiagnostic::ChecksumMismatch {
+            network: Network::Tron,
+        }),
+    }
+}
+
+fn classify_tron_hex(input: &str) -> AddressClassification {
+    let classification = AddressClassification::new(Network::Tron);
+    match input.parse::<TronAddress>() {
+        Ok(address) => AddressClassification {
+            address: Some(address.into()),
+            ..classification
+        },
+        Err(_) => classification.with_diagnostic(AddressDiagnostic::UnknownFormat),
+    }
+}
+
+/// Classifies an Ethereum address, `hex` is the input without its `0x` prefix.
+fn classify_ethereum(input: &str, hex: &str) -> AddressClassification {
+    let classification = AddressClassification::new(Network::Ethereum);
+    let prefix_len = input.len() - hex.len();
+    if let Some((index, character)) = hex.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return classification.with_diagnostic(AddressDiagnostic::InvalidCharacter {
+            network: Network::Ethereum,
+            character,
+            position: prefix_len + index + 1,
+        });
+    }
+    if hex.len() != ETHEREUM_HEX_LENGTH {
+        return classification.with_diagnostic(AddressDiagnostic::InvalidLength {
+            network: Network::Ethereum,
+            expected: prefix_len + ETHEREUM_HEX_LENGTH,
+            found: input.len(),
+        });
+    }
+    // The strict parser checks the EIP-55 checksum of mixed-case addresses.
+    match format!("0x{hex}").parse::<EthereumAddress>() {
+        Ok(address) => AddressClassification {
+            address: Some(address.into()),
+            ..classification
+        },
+        Err(_) => classification.with_diagnostic(AddressDiagnostic::ChecksumMismatch {
+            network: Network::Ethereum,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_valid_addresses() {
+        for (input, network) in [
+            ("TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX", Network::Tron),
+            ("  TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX\n", Network::Tron),
+            ("4196a3bace5adacf637eb7cc79d5787f4247da4bbe", Network::Tron),
+            (
+                "0x4196a3bace5adacf637eb7cc79d5787f4247da4bbe",
+                Network::Tron,
+            ),
+            (
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+                Network::Ethereum,
+            ),
+            (
+                "0xde709f2102306220921060314715629080e2fb77",
+                Network::Ethereum,
+            ),
+            ("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", Network::Bitcoin),
+            ("37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf", Network::Bitcoin),
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                Network::Bitcoin,
+            ),
+            (
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+                Network::Bitcoin,
+            ),
+        ] {
+            let classification = classify_address(input);
+            assert_eq!(classification.network(), Some(network), "{input}");
+            assert_eq!(classification.address().unwrap().network(), network);
+            assert!(classification.diagnostics().is_empty(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_classify_explains_typos() {
+        let classification = classify_address("TMTpzDaQrCVsE1efSyCnsENcbBj2oUThyX");
+        assert_eq!(classification.network(), Some(Network::Tron));
+        assert!(classification.address().is_none());
+        assert_eq!(
+            classification.diagnostics(),
+            [AddressDiagnostic::ChecksumMismatch {
+                network: Network::Tron
+            }]
+        );
+
+        // '0' is not in the base58 alphabet.
+        assert_eq!(
+            classify_address("TMTpzDaQrCVsE1efSyCnsENcbBj2oUTj0X").diagnostics(),
+            [AddressDiagnostic::InvalidCharacter {
+                network: Network::Tron,
+                character: '0',
+                position: 33
+            }]
+        );
+        assert_eq!(
+            classify_address("TMTpzDaQrCVsE1efSyCnsENcbBj2oUTj").diagnostics(),
+            [AddressDiagnostic::InvalidLength {
+                network: Network::Tron,
+                expected: 34,
+                found: 32
+            }]
+        );
+        assert_eq!(
+            classify_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").diagnostics(),
+            [AddressDiagnostic::ChecksumMismatch {
+                network: Network::Ethereum
+            }]
+        );
+        assert_eq!(
+            classify_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg").diagnostics(),
+            [AddressDiagnostic::InvalidCharacter {
+                network: Network::Ethereum,
+                character: 'g',
+                position: 42
+            }]
+        );
+    }
+
+    #[test]
+    fn test_classify_bitcoin_typos() {
+        assert_eq!(
+            classify_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5").diagnostics(),
+            [AddressDiagnostic::ChecksumMismatch {
+                network: Network::Bitcoin
+            }]
+        );
+        // 'b' is not in the bech32 alphabet.
+        assert_eq!(
+            classify_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3tb").diagnostics(),
+            [AddressDiagnostic::InvalidCharacter {
+                network: Network::Bitcoin,
+                character: 'b',
+                position: 42
+            }]
+        );
+        assert_eq!(
+            classify_address("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMh").diagnostics(),
+            [AddressDiagnostic::ChecksumMismatch {
+                network: Network::Bitcoin
+            }]
+        );
+    }
+
+    #[test]
+    fn test_classify_unknown_and_lenient_inputs() {
+        let classification = classify_address("hello");
+        assert!(classification.candidates().is_empty());
+        assert!(classification.into_address().is_err());
+        assert_eq!(
+            classify_address("  ").diagnostics(),
+            [AddressDiagnostic::Empty]
+        );
+
+        let classification = classify_address("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(classification.network(), Some(Network::Ethereum));
+        assert_eq!(
+            classification.diagnostics(),
+            [AddressDiagnostic::MissingPrefix]
+        );
+        assert!(classification.into_address().is_ok());
+    }
+}
@@ -1,7 +1,15 @@
 mod address;
 mod chains;
+mod detect;
+mod network_address;
 mod result;
 
 pub use address::Address;
-pub use chains::TronAddress;
+pub use chains::{
+    BitcoinAddress, BitcoinAddressType, BitcoinFormat, BitcoinNetwork, BitcoinPublicKey,
+    EthereumAddress, EthereumFormat, EthereumPublicKey, TronAddress, TronFormat, TronPublicKey,
+    b58decode_check, b58encode_check, tron_hex, tron_zero_x_hex,
+};
+pub use detect::{AddressClassification, AddressDiagnostic, classify_address};
+pub use network_address::{NetworkAddress, network_addresses};
 pub use result::{AddressError, AddressResult};
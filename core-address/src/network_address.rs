@@ -0,0 +1,136 @@
+use crate::{AddressResult, BitcoinAddress, EthereumAddress, TronAddress};
+use cwu_model::Network;
+use std::fmt;
+
+/// An address on one of the supported networks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NetworkAddress {
+    Ethereum(EthereumAddress),
+    Tron(TronAddress),
+    Bitcoin(BitcoinAddress),
+}
+
+impl NetworkAddress {
+    /// Parses an address of the given network.
+    pub fn parse(network: Network, address: &str) -> AddressResult<Self> {
+        match network {
+            Network::Ethereum => address.parse().map(NetworkAddress::Ethereum),
+            Network::Tron => address.parse().map(NetworkAddress::Tron),
+            Network::Bitcoin => address.parse().map(NetworkAddress::Bitcoin),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        match self {
+            NetworkAddress::Ethereum(_) => Network::Ethereum,
+            NetworkAddress::Tron(_) => Network::Tron,
+            NetworkAddress::Bitcoin(_) => Network::Bitcoin,
+        }
+    }
+}
+
+impl fmt::Display for NetworkAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkAddress::Ethereum(address) => address.fmt(f),
+            NetworkAddress::Tron(address) => address.fmt(f),
+            NetworkAddress::Bitcoin(address) => address.fmt(f),
+        }
+    }
+}
+
+impl From<EthereumAddress> for NetworkAddress {
+    fn from(address: EthereumAddress) -> Self {
+        NetworkAddress::Ethereum(address)
+    }
+}
+
+impl From<TronAddress> for NetworkAddress {
+    fn from(address: TronAddress) -> Self {
+        NetworkAddress::Tron(address)
+    }
+}
+
+impl From<BitcoinAddress> for NetworkAddress {
+    fn from(address: BitcoinAddress) -> Self {
+        NetworkAddress::Bitcoin(address)
+    }
+}
+
+/// Serde helpers for maps of addresses keyed by their network, stored as plain strings:
+/// `#[serde(with = "cwu_core_address::network_addresses")]`.
+///
+/// Each address is parsed for the network it is keyed by, so invalid addresses are
+/// rejected when deserializing. Entries are serialized sorted by network, so the output of
+/// a `HashMap` is stable enough to be hashed.
+pub mod network_addresses {
+    use super::NetworkAddress;
+    use cwu_model::Network;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error, ser::SerializeMap};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<'a, M, S>(addresses: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a M: IntoIterator<Item = (&'a Network, &'a NetworkAddress)>,
+        S: Serializer,
+    {
+        let addresses: BTreeMap<_, _> = addresses.into_iter().collect();
+        let mut map = serializer.serialize_map(Some(addresses.len()))?;
+        for (network, address) in addresses {
+            map.serialize_entry(network, &address.to_string())?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, M, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: FromIterator<(Network, NetworkAddress)>,
+        D: Deserializer<'de>,
+    {
+        BTreeMap::<Network, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(network, address)| {
+                NetworkAddress::parse(network, &address)
+                    .map(|address| (network, address))
+                    .map_err(|e| D::Error::custom(format!("{network} address {address}: {e}")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Addresses {
+        #[serde(with = "network_addresses")]
+        addresses: HashMap<Network, NetworkAddress>,
+    }
+
+    #[test]
+    fn test_parse_for_network() {
+        let tron =
+            NetworkAddress::parse(Network::Tron, "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX").unwrap();
+        assert_eq!(tron.network(), Network::Tron);
+        assert_eq!(tron.to_string(), "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX");
+        assert!(
+            NetworkAddress::parse(Network::Ethereum, "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX").is_err()
+        );
+        assert!(
+            NetworkAddress::parse(Network::Tron, "TMTpzDaQrCVsE1efSyCnsENcbBj2oUThyX").is_err()
+        );
+    }
+
+    #[test]
+    fn test_network_addresses_serde() {
+        let json = r#"{"addresses":{"Tron":"TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX"}}"#;
+        let addresses: Addresses = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&addresses).unwrap(), json);
+
+        let invalid = r#"{"addresses":{"Tron":"TMTpzDaQrCVsE1efSyCnsENcbBj2oUThyX"}}"#;
+        assert!(serde_json::from_str::<Addresses>(invalid).is_err());
+    }
+}
@@ -1,5 +1,7 @@
+use crate::cli::menu::wallet_picker::WalletPicker;
 use crate::cli::service::ConsoleService;
 use crate::cli::styles::{balance::StyledBalance, message::Message};
+use cwu_core_address::{NetworkAddress, classify_address};
 use cwu_service::CwuServiceTrait;
 use cwu_settings::CwuConfig;
 use dialoguer::{console::Term, theme::Theme};
@@ -12,19 +14,66 @@ impl CheckBalance {
         term: &Term,
         config: &CwuConfig,
     ) -> Result<(), anyhow::Error> {
-        let address: String = dialoguer::Input::with_theme(theme)
-            .with_prompt("Enter an address or 'q' to quit")
-            .interact_text_on(term)?;
-        if address != "q" {
-            match ConsoleService::new()
-                .check_balance(address.as_str(), config)
-                .await
-            {
-                Ok(balance) => println!("{}", StyledBalance::new(&balance)),
-                Err(_) => Message::error("Address not found!"),
-            };
-        }
+        let Some(address) = Self::address(theme, term)? else {
+            return Ok(());
+        };
+        match ConsoleService::new().check_balance(&address, config).await {
+            Ok(balance) => println!("{}", StyledBalance::new(&balance)),
+            Err(e) => Message::error(format!("Can't check the balance: {e}").as_str()),
+        };
 
         Ok(())
     }
+
+    /// Picks an address from the headers of local wallets (no unlocking needed), or
+    /// asks for one. Returns `None` if the user quits.
+    fn address(theme: &dyn Theme, term: &Term) -> Result<Option<NetworkAddress>, anyhow::Error> {
+        let addresses: Vec<(String, NetworkAddress)> = WalletPicker::headers()
+            .iter()
+            .flat_map(|header| {
+                header.addresses().iter().map(|(network, address)| {
+                    (
+                        format!("{} | {network}: {address}", header.name()),
+                        address.clone(),
+                    )
+                })
+            })
+            .collect();
+        if !addresses.is_empty() {
+            let mut items: Vec<&str> = addresses.iter().map(|(item, _)| item.as_str()).collect();
+            items.push("Enter an address");
+            let choice = dialoguer::Select::with_theme(theme)
+                .with_prompt("Pick a wallet address (press 'q' to back)")
+                .items(&items)
+                .default(0)
+                .interact_opt()?;
+            match choice {
+                None => return Ok(None),
+                Some(i) if i < addresses.len() => return Ok(Some(addresses[i].1.clone())),
+                Some(_) => {}
+            }
+        }
+
+        loop {
+            let address: String = dialoguer::Input::with_theme(theme)
+                .with_prompt("Enter an address or 'q' to quit")
+                .interact_text_on(term)?;
+            if address == "q" {
+                return Ok(None);
+            }
+            let classification = classify_address(&address);
+            // Diagnostics on an accepted address are warnings, without one they say why not.
+            let report = if classification.address().is_some() {
+                Message::warning
+            } else {
+                Message::error
+            };
+            for diagnostic in classification.diagnostics() {
+                report(diagnostic.to_string().as_str());
+            }
+            if let Some(address) = classification.address() {
+                return Ok(Some(address.clone()));
+            }
+        }
+    }
 }
@@ -0,0 +1,87 @@
+use crate::cli::styles::message::Message;
+use cwu_security_utils::Keyfile;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+#[derive(Clone, Copy)]
+enum KeyfileSetup {
+    None,
+    Generate,
+    Existing,
+}
+
+impl std::fmt::Display for KeyfileSetup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            KeyfileSetup::None => "No keyfile",
+            KeyfileSetup::Generate => "Generate a new keyfile (e.g. on a USB stick)",
+            KeyfileSetup::Existing => "Use an existing file as a keyfile",
+        };
+        f.write_str(s)
+    }
+}
+
+pub(super) enum KeyfilePrompt {}
+
+impl KeyfilePrompt {
+    /// Asks whether a new wallet should be protected with a keyfile.
+    ///
+    /// Returns `None` if the user quits, `Some(None)` for a wallet without a keyfile.
+    pub(super) fn setup(
+        theme: &dyn Theme,
+        term: &Term,
+    ) -> Result<Option<Option<Keyfile>>, anyhow::Error> {
+        let actions = [
+            KeyfileSetup::None,
+            KeyfileSetup::Generate,
+            KeyfileSetup::Existing,
+        ];
+
+        let action = dialoguer::Select::with_theme(theme)
+            .with_prompt("Require a keyfile to open the wallet? (press 'q' to back)")
+            .items(actions)
+            .default(0)
+            .interact_opt()?;
+        let Some(action) = action else {
+            return Ok(None);
+        };
+
+        let keyfile = match actions[action] {
+            KeyfileSetup::None => return Ok(Some(None)),
+            KeyfileSetup::Generate => {
+                let path: String = dialoguer::Input::with_theme(theme)
+                    .with_prompt("Enter a path for the new keyfile or 'q' to quit")
+                    .interact_text_on(term)?;
+                if path == "q" {
+                    return Ok(None);
+                }
+                let keyfile = Keyfile::generate(&path)?;
+                println!(
+                    "Keyfile written to '{path}'. Keep a copy: the wallet can't be opened without it."
+                );
+                keyfile
+            }
+            KeyfileSetup::Existing => match Self::load(theme, term)? {
+                Some(keyfile) => keyfile,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(Some(keyfile)))
+    }
+
+    /// Asks for the keyfile of an existing wallet. Returns `None` if the user quits.
+    pub(super) fn load(theme: &dyn Theme, term: &Term) -> Result<Option<Keyfile>, anyhow::Error> {
+        loop {
+            let path: String = dialoguer::Input::with_theme(theme)
+                .with_prompt("Enter the keyfile path or 'q' to quit")
+                .interact_text_on(term)?;
+            if path == "q" {
+                return Ok(None);
+            }
+            match Keyfile::from_file(&path) {
+                Ok(keyfile) => return Ok(Some(keyfile)),
+                Err(e) => Message::error(format!("Can't read the keyfile: {e}").as_str()),
+            }
+        }
+    }
+}
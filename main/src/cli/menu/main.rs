@@ -1,4 +1,7 @@
-use crate::cli::menu::{check_balance::CheckBalance, new_wallet::NewWallet, wallet::WalletMenu};
+use crate::cli::menu::{
+    check_balance::CheckBalance, new_wallet::NewWallet, restore_wallet::RestoreWallet,
+    wallet::WalletMenu, watch_only::WatchOnly,
+};
 use cwu_settings::CwuConfig;
 use dialoguer::console::Term;
 use dialoguer::theme::Theme;
@@ -7,7 +10,9 @@ use rand::prelude::IndexedRandom;
 pub(crate) enum MainMenu {
     OpenWallet,
     CreateWallet,
+    RestoreWallet,
     CheckBalance,
+    WatchOnly,
 }
 
 impl std::fmt::Display for MainMenu {
@@ -15,7 +20,9 @@ impl std::fmt::Display for MainMenu {
         let s = match self {
             MainMenu::OpenWallet => "Open existing wallet",
             MainMenu::CreateWallet => "Create a new wallet",
+            MainMenu::RestoreWallet => "Restore a wallet from a backup bundle",
             MainMenu::CheckBalance => "Check balance",
+            MainMenu::WatchOnly => "Derive watch-only addresses from an extended public key",
         };
         f.write_str(s)
     }
@@ -31,7 +38,9 @@ impl MainMenu {
             let actions = [
                 MainMenu::OpenWallet,
                 MainMenu::CreateWallet,
+                MainMenu::RestoreWallet,
                 MainMenu::CheckBalance,
+                MainMenu::WatchOnly,
             ];
 
             let action = dialoguer::Select::with_theme(theme)
@@ -50,14 +59,20 @@ impl MainMenu {
 
             match &actions[action] {
                 MainMenu::OpenWallet => {
-                    WalletMenu::apply(theme, term).await?;
+                    WalletMenu::apply(theme, term, config).await?;
                 }
                 MainMenu::CreateWallet => {
                     NewWallet::apply(theme, term).await?;
                 }
+                MainMenu::RestoreWallet => {
+                    RestoreWallet::apply(theme, term).await?;
+                }
                 MainMenu::CheckBalance => {
                     CheckBalance::apply(theme, term, config).await?;
                 }
+                MainMenu::WatchOnly => {
+                    WatchOnly::apply(theme, term).await?;
+                }
             }
         }
         Ok(())
@@ -0,0 +1,163 @@
+use colored::Colorize;
+use cwu_security_utils::{SecretString, Wordlist, estimate_password_strength};
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+use termimad::MadSkin;
+
+pub(super) enum MasterPasswordPrompt {}
+
+impl MasterPasswordPrompt {
+    /// Asks how a new password (`what`, e.g. "master password") should be chosen.
+    ///
+    /// Returns `None` if the user quits, otherwise the chosen kind and the password,
+    /// `None` meaning the wallet generates a random one.
+    pub(super) fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        what: &str,
+    ) -> Result<Option<(MasterPasswordKind, Option<SecretString>)>, anyhow::Error> {
+        let Some(password_kind) = MasterPasswordKind::apply(theme, term, what)? else {
+            return Ok(None);
+        };
+        let chosen_password = match password_kind {
+            MasterPasswordKind::Random => None,
+            MasterPasswordKind::Diceware => match Diceware::apply(theme, term)? {
+                Some(passphrase) => Some(passphrase),
+                None => return Ok(None),
+            },
+            MasterPasswordKind::Own => match OwnPassword::apply(theme, term, what)? {
+                Some(password) => Some(password),
+                None => return Ok(None),
+            },
+        };
+        Ok(Some((password_kind, chosen_password)))
+    }
+
+    pub(super) fn show(password_kind: MasterPasswordKind, label: &str, password: &SecretString) {
+        // The user already knows a password they typed in.
+        if !matches!(password_kind, MasterPasswordKind::Own) {
+            let skin = MadSkin::default();
+            let styled_message = format!(
+                "**{label} (between < >):** <**{}**>",
+                password.expose_secret().red()
+            );
+            println!("{}", skin.inline(&styled_message));
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum MasterPasswordKind {
+    Random,
+    Diceware,
+    Own,
+}
+
+impl std::fmt::Display for MasterPasswordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            MasterPasswordKind::Random => "Random password",
+            MasterPasswordKind::Diceware => "Diceware passphrase (easier to write down)",
+            MasterPasswordKind::Own => "My own password",
+        };
+        f.write_str(s)
+    }
+}
+
+impl MasterPasswordKind {
+    fn apply(
+        theme: &dyn Theme,
+        _: &Term,
+        what: &str,
+    ) -> Result<Option<MasterPasswordKind>, anyhow::Error> {
+        let actions = [
+            MasterPasswordKind::Random,
+            MasterPasswordKind::Diceware,
+            MasterPasswordKind::Own,
+        ];
+
+        let action = dialoguer::Select::with_theme(theme)
+            .with_prompt(format!("Pick a {what} type (press 'q' to back)"))
+            .items(actions)
+            .default(0)
+            .interact_opt()?;
+
+        Ok(action.map(|action| actions[action]))
+    }
+}
+
+const DICEWARE_WORD_COUNTS: [usize; 4] = [5, 6, 7, 8];
+
+enum Diceware {}
+
+impl Diceware {
+    fn apply(theme: &dyn Theme, term: &Term) -> Result<Option<SecretString>, anyhow::Error> {
+        let word_count = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a number of words (press 'q' to back)")
+            .items(DICEWARE_WORD_COUNTS)
+            .default(1)
+            .interact_opt()?;
+        let Some(word_count) = word_count else {
+            return Ok(None);
+        };
+
+        let wordlists = [Wordlist::EffLarge, Wordlist::Bip39English];
+        let wordlist = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a wordlist (press 'q' to back)")
+            .items(["EFF large (7776 words)", "BIP39 English (2048 words)"])
+            .default(0)
+            .interact_opt()?;
+        let Some(wordlist) = wordlist else {
+            return Ok(None);
+        };
+
+        let separator: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Pick a word separator")
+            .default("-".to_string())
+            .interact_text_on(term)?;
+
+        let passphrase = cwu_security_utils::generate_diceware_passphrase(
+            DICEWARE_WORD_COUNTS[word_count],
+            &separator,
+            wordlists[wordlist],
+        )?;
+        Ok(Some(passphrase))
+    }
+}
+
+pub(super) enum OwnPassword {}
+
+impl OwnPassword {
+    /// Asks for a new password and rejects it until it is strong enough.
+    pub(super) fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        what: &str,
+    ) -> Result<Option<SecretString>, anyhow::Error> {
+        loop {
+            let password: SecretString = dialoguer::Password::with_theme(theme)
+                .with_prompt(format!("Come up with a {what} or 'q' to quit"))
+                .with_confirmation(format!("Repeat the {what}"), "The passwords don't match")
+                .interact_on(term)?
+                .into();
+            if password.expose_secret() == "q" {
+                return Ok(None);
+            }
+
+            let estimate = estimate_password_strength(password.expose_secret());
+            let message = format!(
+                "Strength: {} (~{:.0} bits)",
+                estimate.strength(),
+                estimate.entropy_bits()
+            );
+            if estimate.is_strong_enough() {
+                println!("{}", message.green());
+                return Ok(Some(password));
+            }
+            println!(
+                "{}",
+                format!("{message}. Too weak, use a longer password or a passphrase.").red()
+            );
+        }
+    }
+}
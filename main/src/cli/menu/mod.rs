@@ -1,5 +1,10 @@
 pub(crate) mod main;
 
 mod check_balance;
+mod keyfile;
+mod master_password;
 mod new_wallet;
+mod restore_wallet;
 mod wallet;
+mod wallet_picker;
+mod watch_only;
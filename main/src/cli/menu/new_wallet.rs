@@ -1,9 +1,9 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::menu::master_password::MasterPasswordPrompt;
 use crate::cli::service::ConsoleService;
-use colored::Colorize;
 use cwu_service::CwuServiceTrait;
 use dialoguer::console::Term;
 use dialoguer::theme::Theme;
-use termimad::MadSkin;
 
 pub(super) struct NewWallet {}
 
@@ -23,20 +23,24 @@ impl NewWallet {
         if wallet_name.is_empty() || wallet_name == "q" {
             return Ok(());
         }
+        let Some((password_kind, chosen_password)) =
+            MasterPasswordPrompt::apply(theme, term, "master password")?
+        else {
+            return Ok(());
+        };
+        let Some(keyfile) = KeyfilePrompt::setup(theme, term)? else {
+            return Ok(());
+        };
         let master_password = ConsoleService::new()
             .create_wallet(
                 i32::from(word_count.unwrap()),
                 &lang.unwrap().to_string(),
                 wallet_name.as_str(),
+                chosen_password,
+                keyfile.as_ref(),
             )
             .await?;
-
-        let skin = MadSkin::default();
-        let styled_message = format!(
-            "**Master Password (between < >):** <**{}**>",
-            master_password.red()
-        );
-        println!("{}", skin.inline(&styled_message));
+        MasterPasswordPrompt::show(password_kind, "Master Password", &master_password);
         Ok(())
     }
 }
@@ -0,0 +1,62 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::menu::master_password::MasterPasswordPrompt;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_security_utils::SecretString;
+use cwu_service::CwuServiceTrait;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+pub(super) enum RestoreWallet {}
+
+impl RestoreWallet {
+    pub(super) async fn apply(theme: &dyn Theme, term: &Term) -> Result<(), anyhow::Error> {
+        let path: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter the backup bundle path or 'q' to quit")
+            .interact_text_on(term)?;
+        if path == "q" {
+            return Ok(());
+        }
+        let backup_password: SecretString = dialoguer::Password::with_theme(theme)
+            .with_prompt("Enter the backup password or 'q' to quit")
+            .interact_on(term)?
+            .into();
+        if backup_password.expose_secret() == "q" {
+            return Ok(());
+        }
+        let wallet_name: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter a name for the restored wallet (empty to keep the original)")
+            .allow_empty(true)
+            .interact_text_on(term)?;
+        let Some((password_kind, chosen_password)) =
+            MasterPasswordPrompt::apply(theme, term, "master password")?
+        else {
+            return Ok(());
+        };
+        let Some(keyfile) = KeyfilePrompt::setup(theme, term)? else {
+            return Ok(());
+        };
+
+        match ConsoleService::new()
+            .import_backup(
+                path.as_str(),
+                &backup_password,
+                Some(wallet_name.as_str()).filter(|name| !name.is_empty()),
+                chosen_password,
+                keyfile.as_ref(),
+            )
+            .await
+        {
+            Ok(restored) => {
+                println!("Wallet '{}' restored.", restored.name);
+                MasterPasswordPrompt::show(
+                    password_kind,
+                    "Master Password",
+                    &restored.master_password,
+                );
+            }
+            Err(e) => Message::error(format!("Can't restore the wallet: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+}
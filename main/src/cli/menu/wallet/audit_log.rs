@@ -0,0 +1,14 @@
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::audit_report::StyledAuditReport;
+use cwu_service::CwuServiceTrait;
+use cwu_wallet::EncryptedWallet;
+
+pub(super) enum AuditLog {}
+
+impl AuditLog {
+    pub(super) async fn apply(wallet: &EncryptedWallet) -> Result<(), anyhow::Error> {
+        let report = ConsoleService::new().verify_audit_log(wallet).await?;
+        println!("{}", StyledAuditReport::new(&report));
+        Ok(())
+    }
+}
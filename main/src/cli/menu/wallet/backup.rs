@@ -1,7 +1,7 @@
+use crate::cli::menu::wallet::unlock::UnlockWallet;
 use crate::cli::service::ConsoleService;
 use crate::cli::styles::mnemonic::StyledMnemonic;
-use cwu_service::CwuServiceTrait;
-use cwu_wallet::EncryptedWallet;
+use cwu_service::{CwuServiceTrait, WalletSession};
 use dialoguer::console::Term;
 use dialoguer::theme::Theme;
 
@@ -11,17 +11,13 @@ impl BackupWallet {
     pub(super) async fn apply(
         theme: &dyn Theme,
         term: &Term,
-        wallet: &EncryptedWallet,
+        session: &WalletSession,
     ) -> Result<(), anyhow::Error> {
-        let master_password: String = dialoguer::Password::with_theme(theme)
-            .with_prompt("Enter a master password or 'q' to quit")
-            .interact_on(term)?;
-        if master_password != "q" {
-            let mnemonic = ConsoleService::new()
-                .backup_wallet(wallet, master_password)
-                .await?;
-            println!("{}", StyledMnemonic::new(mnemonic));
+        if !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
         }
+        let phrase = ConsoleService::new().backup_wallet(session).await?;
+        println!("{}", StyledMnemonic::new(phrase));
         Ok(())
     }
 }
@@ -0,0 +1,210 @@
+use crate::cli::menu::wallet::send::SendTransaction;
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::delegations::StyledDelegations;
+use crate::cli::styles::format_timestamp;
+use crate::cli::styles::message::Message;
+use cwu_core_address::NetworkAddress;
+use cwu_model::{Delegation, Network, Resource};
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_settings::CwuConfig;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The longest a delegation can be locked for, 864,000 blocks.
+const MAX_LOCK_DAYS: u64 = 30;
+
+/// Delegating staked energy and bandwidth to other addresses.
+pub(super) enum DelegationMenu {
+    Delegate,
+    Undelegate,
+}
+
+impl std::fmt::Display for DelegationMenu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DelegationMenu::Delegate => "Delegate to an address",
+            DelegationMenu::Undelegate => "Take back a delegation",
+        };
+        f.write_str(s)
+    }
+}
+
+impl DelegationMenu {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let Some(address) = session.wallet().addresses().get(&Network::Tron) else {
+            Message::error("The wallet has no Tron address");
+            return Ok(());
+        };
+        let delegations = match ConsoleService::new().delegations(address, config).await {
+            Ok(delegations) => delegations,
+            Err(e) => {
+                Message::error(format!("Can't load the delegations: error: {e}").as_str());
+                return Ok(());
+            }
+        };
+        let owner = address.to_string();
+        println!("{}", StyledDelegations::new(&owner, &delegations));
+
+        let actions = [DelegationMenu::Delegate, DelegationMenu::Undelegate];
+        let Some(action) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick an option (press 'q' to quit)")
+            .items(&actions)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let outgoing: Vec<_> = delegations
+            .into_iter()
+            .filter(|delegation| delegation.from == owner)
+            .collect();
+        match actions[action] {
+            DelegationMenu::Delegate => Self::delegate(theme, term, session, address, config).await,
+            DelegationMenu::Undelegate => {
+                Self::undelegate(theme, term, session, &outgoing, config).await
+            }
+        }
+    }
+
+    async fn delegate(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let Some(to) = SendTransaction::recipient(theme, term)? else {
+            return Ok(());
+        };
+        let resources = [Resource::Energy, Resource::Bandwidth];
+        let Some(resource) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a resource (press 'q' to quit)")
+            .items(resources)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let resource = resources[resource];
+        match ConsoleService::new()
+            .delegatable(address, resource, config)
+            .await
+        {
+            Ok(max) => println!("Up to {max} TRX staked for {resource} can be delegated."),
+            Err(e) => Message::error(
+                format!("Can't check the delegatable {resource}: error: {e}").as_str(),
+            ),
+        }
+        let amount: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter the amount of staked TRX to delegate, e.g. 100")
+            .interact_text_on(term)?;
+        let amount = amount.trim();
+        let days: u64 = dialoguer::Input::with_theme(theme)
+            .with_prompt(format!(
+                "Lock it for how many days, up to {MAX_LOCK_DAYS}? It can't be taken back \
+                 meanwhile (0 for no lock)"
+            ))
+            .default(0)
+            .validate_with(|days: &u64| {
+                if *days <= MAX_LOCK_DAYS {
+                    Ok(())
+                } else {
+                    Err(format!("At most {MAX_LOCK_DAYS} days"))
+                }
+            })
+            .interact_text_on(term)?;
+        let lock = (days > 0).then(|| Duration::from_secs(days * 86_400));
+
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt(format!("Delegate {amount} TRX of {resource} to {to}?"))
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed || !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+        let sent = ConsoleService::new()
+            .delegate(session, &to, amount, resource, lock, config)
+            .await;
+        SendTransaction::report(sent, config).await;
+        Ok(())
+    }
+
+    async fn undelegate(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        outgoing: &[Delegation],
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        if outgoing.is_empty() {
+            println!("The wallet delegates nothing.");
+            return Ok(());
+        }
+        let items: Vec<_> = outgoing
+            .iter()
+            .map(|delegation| {
+                format!(
+                    "{} TRX of {} to {}",
+                    delegation.amount, delegation.resource, delegation.to
+                )
+            })
+            .collect();
+        let Some(delegation) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a delegation (press 'q' to quit)")
+            .items(&items)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let delegation = &outgoing[delegation];
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        if let Some(locked_until) = delegation.locked_until.filter(|until| *until > now) {
+            Message::error(
+                format!(
+                    "The delegation is locked until {}",
+                    format_timestamp(locked_until)
+                )
+                .as_str(),
+            );
+            return Ok(());
+        }
+        let to: NetworkAddress = match delegation.to.parse() {
+            Ok(to) => NetworkAddress::Tron(to),
+            Err(e) => {
+                Message::error(format!("Invalid address {}: {e}", delegation.to).as_str());
+                return Ok(());
+            }
+        };
+        let amount: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter the amount of TRX to take back")
+            .default(delegation.amount.clone())
+            .interact_text_on(term)?;
+        let amount = amount.trim();
+
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt(format!(
+                "Take back {amount} TRX of {} from {to}?",
+                delegation.resource
+            ))
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed || !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+        let sent = ConsoleService::new()
+            .undelegate(session, &to, amount, delegation.resource, config)
+            .await;
+        SendTransaction::report(sent, config).await;
+        Ok(())
+    }
+}
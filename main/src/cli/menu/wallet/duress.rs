@@ -0,0 +1,65 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::menu::master_password::MasterPasswordPrompt;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_security_utils::SecretString;
+use cwu_service::CwuServiceTrait;
+use cwu_wallet::EncryptedWallet;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+pub(super) enum DuressPassword {}
+
+impl DuressPassword {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        wallet: &EncryptedWallet,
+    ) -> Result<(), anyhow::Error> {
+        println!(
+            "A duress password opens a separate decoy wallet from the same file. \
+             Fund the decoy with a small amount to make it plausible. Enter the master \
+             password: with the duress password, the decoy replaces this wallet."
+        );
+        let keyfile = if wallet.uses_keyfile() {
+            match KeyfilePrompt::load(theme, term)? {
+                Some(keyfile) => Some(keyfile),
+                None => return Ok(()),
+            }
+        } else {
+            None
+        };
+        let master_password: SecretString = dialoguer::Password::with_theme(theme)
+            .with_prompt("Enter a master password or 'q' to quit")
+            .interact_on(term)?
+            .into();
+        if master_password.expose_secret() == "q" {
+            return Ok(());
+        }
+        let Some((password_kind, chosen_password)) =
+            MasterPasswordPrompt::apply(theme, term, "duress password")?
+        else {
+            return Ok(());
+        };
+
+        match ConsoleService::new()
+            .add_decoy_wallet(
+                wallet.name(),
+                &master_password,
+                keyfile.as_ref(),
+                chosen_password,
+            )
+            .await
+        {
+            Ok(duress_password) => {
+                MasterPasswordPrompt::show(password_kind, "Duress Password", &duress_password);
+                Message::warning(
+                    "The wallet list, watch-only addresses and balances shown without a \
+                     password now show the decoy, not this wallet.",
+                );
+            }
+            Err(e) => Message::error(format!("Can't create the decoy wallet: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::menu::master_password::OwnPassword;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_security_utils::SecretString;
+use cwu_service::CwuServiceTrait;
+use cwu_wallet::EncryptedWallet;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+pub(super) enum ExportBackup {}
+
+impl ExportBackup {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        wallet: &EncryptedWallet,
+    ) -> Result<(), anyhow::Error> {
+        let keyfile = if wallet.uses_keyfile() {
+            match KeyfilePrompt::load(theme, term)? {
+                Some(keyfile) => Some(keyfile),
+                None => return Ok(()),
+            }
+        } else {
+            None
+        };
+        let master_password: SecretString = dialoguer::Password::with_theme(theme)
+            .with_prompt("Enter a master password or 'q' to quit")
+            .interact_on(term)?
+            .into();
+        if master_password.expose_secret() == "q" {
+            return Ok(());
+        }
+        let Some(backup_password) = OwnPassword::apply(theme, term, "backup password")? else {
+            return Ok(());
+        };
+        let path: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter a path for the backup bundle")
+            .default(format!("{}.cwu-backup.json", wallet.name()))
+            .interact_text_on(term)?;
+
+        match ConsoleService::new()
+            .export_backup(
+                wallet,
+                &master_password,
+                keyfile.as_ref(),
+                &backup_password,
+                path.as_str(),
+            )
+            .await
+        {
+            Ok(()) => println!(
+                "Backup saved to '{path}'. It can only be restored with the backup password."
+            ),
+            Err(e) => Message::error(format!("Can't export the wallet: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+}
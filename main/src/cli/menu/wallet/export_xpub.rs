@@ -0,0 +1,33 @@
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use cwu_model::Network;
+use cwu_service::{CwuServiceTrait, WalletSession};
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+pub(super) enum ExportXpub {}
+
+impl ExportXpub {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+    ) -> Result<(), anyhow::Error> {
+        if !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+        let xpubs = ConsoleService::new().export_xpubs(session).await?;
+        println!(
+            "Anyone with these keys can see every address of the accounts, but can't spend from them."
+        );
+        for (network, xpub) in xpubs {
+            println!("{network}: {xpub}");
+        }
+        for network in Network::iter() {
+            if session.wallet().is_imported(*network) {
+                println!("{network}: imported key, not derived from the mnemonic");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use crate::cli::styles::transfers::StyledTransfers;
+use cwu_model::Network;
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_settings::CwuConfig;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+/// How many transfers are shown at once.
+const PAGE_SIZE: usize = 10;
+
+pub(super) enum TransactionHistory {}
+
+impl TransactionHistory {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let Some(address) = session.wallet().addresses().get(&Network::Tron) else {
+            Message::error("The wallet has no Tron address");
+            return Ok(());
+        };
+
+        let mut start = 0;
+        loop {
+            let page = match ConsoleService::new()
+                .transaction_history(session, address, start, PAGE_SIZE, config)
+                .await
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    Message::error(format!("Can't load the transactions: error: {e}").as_str());
+                    return Ok(());
+                }
+            };
+            println!("{}", StyledTransfers::new(&page.transfers));
+            if !page.has_more {
+                return Ok(());
+            }
+            start += PAGE_SIZE;
+            let more = dialoguer::Confirm::with_theme(theme)
+                .with_prompt("Show older transactions?")
+                .default(false)
+                .interact_on(term)?;
+            if !more {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -1,15 +1,50 @@
+mod audit_log;
 mod backup;
+mod delegation;
+mod duress;
+mod export_backup;
+mod export_xpub;
+mod history;
+mod offline;
 mod open_wallet;
+mod send;
+mod staking;
+mod unlock;
+mod vanity;
+mod voting;
 
+use crate::cli::menu::wallet::audit_log::AuditLog;
 use crate::cli::menu::wallet::backup::BackupWallet;
+use crate::cli::menu::wallet::delegation::DelegationMenu;
+use crate::cli::menu::wallet::duress::DuressPassword;
+use crate::cli::menu::wallet::export_backup::ExportBackup;
+use crate::cli::menu::wallet::export_xpub::ExportXpub;
+use crate::cli::menu::wallet::history::TransactionHistory;
+use crate::cli::menu::wallet::offline::OfflineSigning;
 use crate::cli::menu::wallet::open_wallet::OpenWallet;
+use crate::cli::menu::wallet::send::SendTransaction;
+use crate::cli::menu::wallet::staking::StakingMenu;
+use crate::cli::menu::wallet::vanity::VanityAddress;
+use crate::cli::menu::wallet::voting::VotingMenu;
+use cwu_settings::CwuConfig;
 use dialoguer::console::Term;
 use dialoguer::theme::Theme;
+use std::time::Duration;
 
 pub(super) enum WalletMenu {
     NewTransaction,
+    OfflineSigning,
     History,
+    Staking,
+    Delegation,
+    Voting,
     Backup,
+    ExportBackup,
+    ExportXpub,
+    DuressPassword,
+    AuditLog,
+    VanityAddress,
+    Lock,
     Exit,
 }
 
@@ -17,8 +52,18 @@ impl std::fmt::Display for WalletMenu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
             WalletMenu::NewTransaction => "Start a new transaction",
+            WalletMenu::OfflineSigning => "Sign a transaction offline (air-gapped)",
             WalletMenu::History => "Show transaction history",
+            WalletMenu::Staking => "Stake TRX for energy and bandwidth",
+            WalletMenu::Delegation => "Delegate energy and bandwidth to other addresses",
+            WalletMenu::Voting => "Vote for super representatives and claim rewards",
             WalletMenu::Backup => "Make a backup",
+            WalletMenu::ExportBackup => "Export an encrypted backup bundle",
+            WalletMenu::ExportXpub => "Export extended public keys (watch-only)",
+            WalletMenu::DuressPassword => "Set up a duress password (decoy wallet)",
+            WalletMenu::AuditLog => "Verify the audit log",
+            WalletMenu::VanityAddress => "Generate a vanity address",
+            WalletMenu::Lock => "Lock the wallet",
             WalletMenu::Exit => "Exit to the main menu",
         };
         f.write_str(s)
@@ -26,24 +71,49 @@ impl std::fmt::Display for WalletMenu {
 }
 
 impl WalletMenu {
-    pub(super) async fn apply(theme: &dyn Theme, term: &Term) -> Result<(), anyhow::Error> {
-        let wallet = OpenWallet::apply(theme, term).await?;
-        if wallet.is_none() {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let idle_timeout = Duration::from_secs(config.session.idle_timeout_secs);
+        let session = OpenWallet::apply(theme, term, idle_timeout).await?;
+        if session.is_none() {
             return Ok(());
         }
-        let wallet = wallet.unwrap();
+        let session = session.unwrap();
+        let wallet = session.wallet();
         loop {
             let actions = [
                 WalletMenu::NewTransaction,
+                WalletMenu::OfflineSigning,
                 WalletMenu::History,
+                WalletMenu::Staking,
+                WalletMenu::Delegation,
+                WalletMenu::Voting,
                 WalletMenu::Backup,
+                WalletMenu::ExportBackup,
+                WalletMenu::ExportXpub,
+                WalletMenu::DuressPassword,
+                WalletMenu::AuditLog,
+                WalletMenu::VanityAddress,
+                WalletMenu::Lock,
                 WalletMenu::Exit,
             ];
+            let status = match session.remaining() {
+                Some(remaining) => format!(
+                    "unlocked, locks in {}:{:02}",
+                    remaining.as_secs() / 60,
+                    remaining.as_secs() % 60
+                ),
+                None => "locked".to_string(),
+            };
 
             let action = dialoguer::Select::with_theme(theme)
                 .with_prompt(format!(
-                    "Wallet '{}': Pick an option (press 'q' to quit)",
-                    &wallet.name()
+                    "Wallet '{}' ({}): Pick an option (press 'q' to quit)",
+                    &wallet.name(),
+                    status
                 ))
                 .items(&actions)
                 .default(0)
@@ -58,14 +128,42 @@ impl WalletMenu {
 
             match &actions[action] {
                 WalletMenu::NewTransaction => {
-                    println!("New transaction");
+                    SendTransaction::apply(theme, term, &session, config).await?;
+                }
+                WalletMenu::OfflineSigning => {
+                    OfflineSigning::apply(theme, term, &session, config).await?;
                 }
                 WalletMenu::History => {
-                    println!("History");
+                    TransactionHistory::apply(theme, term, &session, config).await?;
+                }
+                WalletMenu::Staking => {
+                    StakingMenu::apply(theme, term, &session, config).await?;
+                }
+                WalletMenu::Delegation => {
+                    DelegationMenu::apply(theme, term, &session, config).await?;
+                }
+                WalletMenu::Voting => {
+                    VotingMenu::apply(theme, term, &session, config).await?;
                 }
                 WalletMenu::Backup => {
-                    BackupWallet::apply(theme, term, &wallet).await?;
+                    BackupWallet::apply(theme, term, &session).await?;
+                }
+                WalletMenu::ExportBackup => {
+                    ExportBackup::apply(theme, term, wallet).await?;
+                }
+                WalletMenu::ExportXpub => {
+                    ExportXpub::apply(theme, term, &session).await?;
+                }
+                WalletMenu::DuressPassword => {
+                    DuressPassword::apply(theme, term, wallet).await?;
+                }
+                WalletMenu::AuditLog => {
+                    AuditLog::apply(wallet).await?;
+                }
+                WalletMenu::VanityAddress => {
+                    VanityAddress::apply(theme, term, &session).await?;
                 }
+                WalletMenu::Lock => session.lock(),
                 WalletMenu::Exit => break,
             }
         }
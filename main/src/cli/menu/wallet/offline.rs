@@ -0,0 +1,218 @@
+use crate::cli::menu::wallet::send::SendTransaction;
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::format_timestamp;
+use crate::cli::styles::message::Message;
+use cwu_model::TransactionDetails;
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_settings::CwuConfig;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Signing on an air-gapped machine: the transaction is exported unsigned from an online
+/// machine, signed offline and broadcast back online, each step checking the file.
+pub(super) enum OfflineSigning {
+    Export,
+    Sign,
+    Broadcast,
+}
+
+impl std::fmt::Display for OfflineSigning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            OfflineSigning::Export => "Export an unsigned transaction (online)",
+            OfflineSigning::Sign => "Sign a transaction file (offline)",
+            OfflineSigning::Broadcast => "Broadcast a signed transaction file (online)",
+        };
+        f.write_str(s)
+    }
+}
+
+impl OfflineSigning {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let actions = [
+            OfflineSigning::Export,
+            OfflineSigning::Sign,
+            OfflineSigning::Broadcast,
+        ];
+        let Some(action) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a step (press 'q' to quit)")
+            .items(&actions)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        match actions[action] {
+            OfflineSigning::Export => Self::export(theme, term, session, config).await,
+            OfflineSigning::Sign => Self::sign(theme, term, session, config).await,
+            OfflineSigning::Broadcast => Self::broadcast(theme, term, config).await,
+        }
+    }
+
+    async fn export(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let Some(to) = SendTransaction::recipient(theme, term)? else {
+            return Ok(());
+        };
+        let Some(from) = session.wallet().addresses().get(&to.network()).cloned() else {
+            Message::error(format!("The wallet has no {} address", to.network()).as_str());
+            return Ok(());
+        };
+        let Some((asset, amount)) = SendTransaction::asset_and_amount(theme, term)? else {
+            return Ok(());
+        };
+        let path = Self::path(theme, term, "Enter the file to export to", "unsigned.json")?;
+        match ConsoleService::new()
+            .export_transaction(&from, &to, asset, &amount, &path, config)
+            .await
+        {
+            Ok(details) => {
+                Self::print(&details);
+                println!(
+                    "Exported to {}: sign it on the offline machine holding the wallet.",
+                    path.display()
+                );
+            }
+            Err(e) => Message::error(format!("Can't export the transaction: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+
+    async fn sign(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let path = Self::path(theme, term, "Enter the file to sign", "unsigned.json")?;
+        let Some(details) = Self::read(&path, config).await else {
+            return Ok(());
+        };
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt("Sign this transaction?")
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed || !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+        let signed_path = Self::path(
+            theme,
+            term,
+            "Enter the file to write the signed transaction to",
+            "signed.json",
+        )?;
+        match ConsoleService::new()
+            .sign_transaction_file(session, &path, &signed_path, config)
+            .await
+        {
+            Ok(signed) => {
+                println!(
+                    "Signed transaction {} written to {}: broadcast it from an online machine.",
+                    details.tx_id,
+                    signed_path.display()
+                );
+                if let Some(e) = signed.audit_error {
+                    Message::warning(
+                        format!("The signature is missing from the audit log: error: {e}").as_str(),
+                    );
+                }
+            }
+            Err(e) => Message::error(format!("Can't sign the transaction: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+
+    async fn broadcast(
+        theme: &dyn Theme,
+        term: &Term,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let path = Self::path(theme, term, "Enter the file to broadcast", "signed.json")?;
+        let Some(details) = Self::read(&path, config).await else {
+            return Ok(());
+        };
+        if details.signatures == 0 {
+            Message::error("The transaction isn't signed, sign it on the offline machine first");
+            return Ok(());
+        }
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt("Broadcast this transaction? It can't be reverted")
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed {
+            return Ok(());
+        }
+        match ConsoleService::new()
+            .broadcast_transaction_file(&path, config)
+            .await
+        {
+            Ok(tx_id) => {
+                println!("Transaction sent: {tx_id}");
+                SendTransaction::track(&tx_id, config).await;
+            }
+            Err(e) => Message::error(format!("Can't send the transaction: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+
+    /// Reads and prints a transaction file, `None` when it can't be trusted.
+    async fn read(path: &Path, config: &CwuConfig) -> Option<TransactionDetails> {
+        match ConsoleService::new()
+            .read_transaction_file(path, config)
+            .await
+        {
+            Ok(details) => {
+                Self::print(&details);
+                Some(details)
+            }
+            Err(e) => {
+                Message::error(format!("Can't read the transaction: error: {e}").as_str());
+                None
+            }
+        }
+    }
+
+    fn print(details: &TransactionDetails) {
+        let symbol = details.asset.symbol(details.network);
+        println!("Transaction: {}", details.tx_id);
+        println!("From:        {}", details.from);
+        println!("To:          {}", details.to);
+        println!("Amount:      {} {symbol}", details.amount);
+        if let Some(fee_limit) = &details.fee_limit {
+            println!("Fee limit:   {fee_limit} TRX");
+        }
+        println!("Expires:     {}", format_timestamp(details.expires_at));
+        println!("Signatures:  {}", details.signatures);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        if details.expires_at <= now {
+            Message::warning("The transaction has expired, export a new one");
+        }
+    }
+
+    fn path(
+        theme: &dyn Theme,
+        term: &Term,
+        prompt: &str,
+        default: &str,
+    ) -> Result<PathBuf, anyhow::Error> {
+        let path: String = dialoguer::Input::with_theme(theme)
+            .with_prompt(prompt)
+            .default(default.to_string())
+            .interact_text_on(term)?;
+        Ok(PathBuf::from(path.trim()))
+    }
+}
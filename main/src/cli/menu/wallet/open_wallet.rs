@@ -1,30 +1,60 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::menu::wallet_picker::WalletPicker;
 use crate::cli::service::ConsoleService;
 use crate::cli::styles::message::Message;
-use cwu_service::CwuServiceTrait;
+use cwu_security_utils::SecretString;
+use cwu_service::{CwuServiceTrait, WalletSession};
 use cwu_wallet::EncryptedWallet;
 use dialoguer::console::Term;
 use dialoguer::theme::Theme;
+use std::time::Duration;
 
 pub(super) enum OpenWallet {}
 
 impl OpenWallet {
+    /// Opens a wallet and unlocks it for `idle_timeout`, with a single password prompt.
     pub(super) async fn apply(
         theme: &dyn Theme,
         term: &Term,
-    ) -> Result<Option<EncryptedWallet>, anyhow::Error> {
-        let name: String = dialoguer::Input::with_theme(theme)
-            .with_prompt("Enter a wallet name or 'q' to quit")
-            .interact_text_on(term)?;
-        if name != "q" {
-            let master_password: String = dialoguer::Password::with_theme(theme)
+        idle_timeout: Duration,
+    ) -> Result<Option<WalletSession>, anyhow::Error> {
+        if let Some(name) = WalletPicker::pick_name(theme, term)? {
+            let keyfile = match EncryptedWallet::requires_keyfile(name.as_str()) {
+                Ok(false) => None,
+                Ok(true) => match KeyfilePrompt::load(theme, term)? {
+                    Some(keyfile) => Some(keyfile),
+                    None => return Ok(None),
+                },
+                Err(e) => {
+                    Message::error(
+                        format!("Can't open the wallet {}: error: {}", name, e).as_str(),
+                    );
+                    return Ok(None);
+                }
+            };
+            let master_password: SecretString = dialoguer::Password::with_theme(theme)
                 .with_prompt("Enter a master password or 'q' to quit")
-                .interact_on(term)?;
-            if master_password != "q" {
-                return match ConsoleService::new()
-                    .open_wallet(name.as_str(), master_password)
+                .interact_on(term)?
+                .into();
+            if master_password.expose_secret() != "q" {
+                let service = ConsoleService::new();
+                let wallet = service
+                    .open_wallet(name.as_str(), &master_password, keyfile.as_ref())
+                    .await;
+                let session = match wallet {
+                    Ok(wallet) => WalletSession::new(wallet, idle_timeout),
+                    Err(e) => {
+                        Message::error(
+                            format!("Can't open the wallet {}: error: {}", name, e).as_str(),
+                        );
+                        return Ok(None);
+                    }
+                };
+                return match service
+                    .unlock_wallet(&session, &master_password, keyfile.as_ref())
                     .await
                 {
-                    Ok(wallet) => Ok(Some(wallet)),
+                    Ok(()) => Ok(Some(session)),
                     Err(e) => {
                         Message::error(
                             format!("Can't open the wallet {}: error: {}", name, e).as_str(),
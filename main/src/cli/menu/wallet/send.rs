@@ -0,0 +1,186 @@
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_core_address::{NetworkAddress, classify_address};
+use cwu_model::{Asset, Network, SentTransaction, TransactionStatus};
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_settings::CwuConfig;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+pub(super) enum SendTransaction {}
+
+impl SendTransaction {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let wallet = session.wallet();
+        let Some(from) = wallet.addresses().get(&Network::Tron) else {
+            Message::error("The wallet has no Tron address");
+            return Ok(());
+        };
+        let Some(to) = Self::recipient(theme, term)? else {
+            return Ok(());
+        };
+        let Some((asset, amount)) = Self::asset_and_amount(theme, term)? else {
+            return Ok(());
+        };
+        let (amount, symbol) = (amount.as_str(), asset.symbol(Network::Tron));
+
+        println!("From:   {from}");
+        println!("To:     {to}");
+        println!("Amount: {amount} {symbol}");
+        match ConsoleService::new().is_activated(&to, config).await {
+            Ok(true) => {}
+            Ok(false) => Message::warning(match asset {
+                Asset::Native => {
+                    "The recipient isn't activated yet: this transfer activates it, which burns \
+                     1 TRX more, included in the fee below"
+                }
+                Asset::Usdt => {
+                    "The recipient isn't activated yet: it needs TRX sent to it first, which \
+                     burns 1 TRX more to activate it, before it can move these USDT"
+                }
+            }),
+            Err(e) => Message::error(format!("Can't check the recipient: error: {e}").as_str()),
+        }
+        match ConsoleService::new()
+            .estimate_fee(session, &to, asset, amount, config)
+            .await
+        {
+            Ok(estimate) => {
+                println!("{estimate}");
+                if let Some(threshold) = config.tron.fee_warning_threshold
+                    && estimate.burn() > threshold
+                {
+                    Message::warning(
+                        format!(
+                            "This transaction burns more than {} TRX: stake or rent energy \
+                             and bandwidth to avoid the fee",
+                            threshold as f64 / 1_000_000.0
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+            Err(e) => {
+                Message::error(format!("Can't estimate the fee: error: {e}").as_str());
+                return Ok(());
+            }
+        }
+
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt("Sign and broadcast this transaction? It can't be reverted")
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed || !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+
+        let sent = ConsoleService::new()
+            .send(session, &to, asset, amount, config)
+            .await;
+        Self::report(sent, config).await;
+        Ok(())
+    }
+
+    /// Reports a sent transaction, then tracks it.
+    pub(super) async fn report(sent: cwu_service::Result<SentTransaction>, config: &CwuConfig) {
+        match sent {
+            Ok(sent) => {
+                println!("Transaction sent: {}", sent.tx_id);
+                if let Some(e) = &sent.audit_error {
+                    Message::warning(
+                        format!("The transaction is missing from the audit log: error: {e}")
+                            .as_str(),
+                    );
+                }
+                Self::track(&sent.tx_id, config).await;
+            }
+            Err(e) => Message::error(format!("Can't send the transaction: error: {e}").as_str()),
+        }
+    }
+
+    /// Reports the status of a sent transaction until it is solidified or reverted.
+    pub(super) async fn track(tx_id: &str, config: &CwuConfig) {
+        match ConsoleService::new()
+            .track_transaction(Network::Tron, tx_id, config, |_| {})
+            .await
+        {
+            Ok(status @ TransactionStatus::Reverted { .. }) => {
+                Message::error(format!("The transaction failed: {status}").as_str())
+            }
+            Ok(status @ TransactionStatus::Solidified { .. }) => println!("{status}"),
+            Ok(status) => {
+                println!("{status}, not solidified yet: check the transaction history later")
+            }
+            Err(e) => Message::error(
+                format!("Can't get the status of the transaction: error: {e}").as_str(),
+            ),
+        }
+    }
+
+    /// Asks what to send and how much, `None` if the user quits.
+    pub(super) fn asset_and_amount(
+        theme: &dyn Theme,
+        term: &Term,
+    ) -> Result<Option<(Asset, String)>, anyhow::Error> {
+        let assets = [Asset::Native, Asset::Usdt];
+        let symbols = assets.map(|asset| asset.symbol(Network::Tron));
+        let Some(asset) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick what to send (press 'q' to quit)")
+            .items(symbols)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(None);
+        };
+        let amount: String = dialoguer::Input::with_theme(theme)
+            .with_prompt(format!(
+                "Enter the amount of {} to send, e.g. 12.5",
+                symbols[asset]
+            ))
+            .validate_with(|amount: &String| {
+                ConsoleService::new()
+                    .validate_amount(Network::Tron, assets[asset], amount)
+                    .map_err(|e| e.to_string())
+            })
+            .interact_text_on(term)?;
+        Ok(Some((assets[asset], amount.trim().to_string())))
+    }
+
+    /// Asks for a Tron address until a valid one is entered, `None` if the user quits.
+    pub(super) fn recipient(
+        theme: &dyn Theme,
+        term: &Term,
+    ) -> Result<Option<NetworkAddress>, anyhow::Error> {
+        loop {
+            let address: String = dialoguer::Input::with_theme(theme)
+                .with_prompt("Enter the recipient Tron address or 'q' to quit")
+                .interact_text_on(term)?;
+            if address == "q" {
+                return Ok(None);
+            }
+            let classification = classify_address(&address);
+            // Diagnostics on an accepted address are warnings, without one they say why not.
+            let report = if classification.address().is_some() {
+                Message::warning
+            } else {
+                Message::error
+            };
+            for diagnostic in classification.diagnostics() {
+                report(diagnostic.to_string().as_str());
+            }
+            match classification.address() {
+                Some(address @ NetworkAddress::Tron(_)) => return Ok(Some(address.clone())),
+                Some(address) => Message::error(
+                    format!("This is a {} address, not a Tron one", address.network()).as_str(),
+                ),
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::cli::menu::wallet::send::SendTransaction;
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use crate::cli::styles::staking::StyledStaking;
+use cwu_model::{Network, Resource};
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_settings::CwuConfig;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+/// Staking TRX for energy and bandwidth with Stake 2.0.
+pub(super) enum StakingMenu {
+    Stake,
+    Unstake,
+    Withdraw,
+    CancelUnstaking,
+}
+
+impl std::fmt::Display for StakingMenu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            StakingMenu::Stake => "Stake TRX",
+            StakingMenu::Unstake => "Unstake TRX",
+            StakingMenu::Withdraw => "Withdraw unlocked TRX",
+            StakingMenu::CancelUnstaking => "Cancel all unstaking",
+        };
+        f.write_str(s)
+    }
+}
+
+impl StakingMenu {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let Some(address) = session.wallet().addresses().get(&Network::Tron) else {
+            Message::error("The wallet has no Tron address");
+            return Ok(());
+        };
+        let staking = match ConsoleService::new().staking(address, config).await {
+            Ok(staking) => staking,
+            Err(e) => {
+                Message::error(format!("Can't check staking: error: {e}").as_str());
+                return Ok(());
+            }
+        };
+        println!("{}", StyledStaking::new(&staking));
+
+        let actions = [
+            StakingMenu::Stake,
+            StakingMenu::Unstake,
+            StakingMenu::Withdraw,
+            StakingMenu::CancelUnstaking,
+        ];
+        let Some(action) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick an option (press 'q' to quit)")
+            .items(&actions)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let service = ConsoleService::new();
+        let sent = match actions[action] {
+            StakingMenu::Stake => {
+                let Some((resource, amount)) = Self::resource_and_amount(theme, term, "stake")?
+                else {
+                    return Ok(());
+                };
+                if !Self::confirm(
+                    theme,
+                    term,
+                    session,
+                    format!("Stake {amount} TRX for {resource}?").as_str(),
+                )
+                .await?
+                {
+                    return Ok(());
+                }
+                service.stake(session, &amount, resource, config).await
+            }
+            StakingMenu::Unstake => {
+                let Some((resource, amount)) = Self::resource_and_amount(theme, term, "unstake")?
+                else {
+                    return Ok(());
+                };
+                if !Self::confirm(
+                    theme,
+                    term,
+                    session,
+                    format!(
+                        "Unstake {amount} TRX from {resource}? It stops giving {resource} now, \
+                         and can be withdrawn once unlocked, at the end of the unstaking \
+                         period of the network"
+                    )
+                    .as_str(),
+                )
+                .await?
+                {
+                    return Ok(());
+                }
+                service.unstake(session, &amount, resource, config).await
+            }
+            StakingMenu::Withdraw => {
+                if staking.withdrawable == "0" {
+                    println!("No unstaked TRX is unlocked yet.");
+                    return Ok(());
+                }
+                let prompt = format!("Withdraw {} TRX to the balance?", staking.withdrawable);
+                if !Self::confirm(theme, term, session, prompt.as_str()).await? {
+                    return Ok(());
+                }
+                service.withdraw_unstaked(session, config).await
+            }
+            StakingMenu::CancelUnstaking => {
+                if staking.unstaking.is_empty() {
+                    println!("No TRX is being unstaked.");
+                    return Ok(());
+                }
+                let prompt = "Cancel all unstaking? The locked TRX is staked again, \
+                              the unlocked TRX is withdrawn";
+                if !Self::confirm(theme, term, session, prompt).await? {
+                    return Ok(());
+                }
+                service.cancel_unstaking(session, config).await
+            }
+        };
+        SendTransaction::report(sent, config).await;
+        Ok(())
+    }
+
+    /// Asks which resource and how much TRX to `verb`, `None` if the user quits.
+    fn resource_and_amount(
+        theme: &dyn Theme,
+        term: &Term,
+        verb: &str,
+    ) -> Result<Option<(Resource, String)>, anyhow::Error> {
+        let resources = [Resource::Energy, Resource::Bandwidth];
+        let Some(resource) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a resource (press 'q' to quit)")
+            .items(resources)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(None);
+        };
+        let amount: String = dialoguer::Input::with_theme(theme)
+            .with_prompt(format!("Enter the amount of TRX to {verb}, e.g. 100"))
+            .interact_text_on(term)?;
+        Ok(Some((resources[resource], amount.trim().to_string())))
+    }
+
+    /// Asks to confirm a transaction, unlocking the wallet if needed.
+    async fn confirm(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        prompt: &str,
+    ) -> Result<bool, anyhow::Error> {
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt(prompt)
+            .default(false)
+            .interact_on(term)?;
+        Ok(confirmed && UnlockWallet::ensure(theme, term, session).await?)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_security_utils::SecretString;
+use cwu_service::{CwuServiceTrait, WalletSession};
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+pub(super) enum UnlockWallet {}
+
+impl UnlockWallet {
+    /// Unlocks the session again if it was locked, returns whether it is unlocked.
+    pub(super) async fn ensure(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+    ) -> Result<bool, anyhow::Error> {
+        if session.is_unlocked() {
+            return Ok(true);
+        }
+        let keyfile = if session.wallet().uses_keyfile() {
+            match KeyfilePrompt::load(theme, term)? {
+                Some(keyfile) => Some(keyfile),
+                None => return Ok(false),
+            }
+        } else {
+            None
+        };
+        let master_password: SecretString = dialoguer::Password::with_theme(theme)
+            .with_prompt("The wallet is locked. Enter a master password or 'q' to quit")
+            .interact_on(term)?
+            .into();
+        if master_password.expose_secret() == "q" {
+            return Ok(false);
+        }
+        match ConsoleService::new()
+            .unlock_wallet(session, &master_password, keyfile.as_ref())
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                Message::error(format!("Can't unlock the wallet: error: {}", e).as_str());
+                Ok(false)
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::cli::menu::keyfile::KeyfilePrompt;
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_model::Network;
+use cwu_security_utils::SecretString;
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_wallet::VanityPattern;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use termimad::crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use termimad::crossterm::terminal;
+
+/// How long the key watcher waits for a key before checking whether the search ended.
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(super) enum VanityAddress {}
+
+impl VanityAddress {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+    ) -> Result<(), anyhow::Error> {
+        let networks = [Network::Tron, Network::Ethereum];
+        let Some(network) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a network (press 'q' to quit)")
+            .items(networks)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let network = networks[network];
+
+        let prefix: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter the prefix of the address, e.g. 'TCWU' or '0xcafe'")
+            .allow_empty(true)
+            .interact_text_on(term)?;
+        let suffix: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter the suffix of the address")
+            .allow_empty(true)
+            .interact_text_on(term)?;
+        let pattern = match VanityPattern::new(network, prefix.trim(), suffix.trim()) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                Message::error(format!("{e}").as_str());
+                return Ok(());
+            }
+        };
+
+        let sources = ["Random keys", "Keys of the wallet mnemonic"];
+        let Some(source) = dialoguer::Select::with_theme(theme)
+            .with_prompt(format!(
+                "About {:.0} keys to try on average. Pick where keys come from (press 'q' to quit)",
+                pattern.expected_attempts()
+            ))
+            .items(sources)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let from_mnemonic = source == 1;
+        if from_mnemonic && !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let stop_keys = StopKeys::watch();
+        let stopped = stop_keys.stopped();
+        let found = ConsoleService::new()
+            .vanity_search(
+                &pattern,
+                from_mnemonic.then_some(session),
+                threads,
+                move |_| !stopped.load(Ordering::Relaxed),
+            )
+            .await;
+        drop(stop_keys);
+        let found = match found {
+            Ok(Some(found)) => found,
+            Ok(None) => {
+                println!("No matching address found.");
+                return Ok(());
+            }
+            Err(e) => {
+                Message::error(format!("Can't search for the address: error: {e}").as_str());
+                return Ok(());
+            }
+        };
+        match found.index() {
+            Some(index) => println!(
+                "Found {} after {} keys, at index {index} of the wallet mnemonic.",
+                found.address(),
+                found.attempts()
+            ),
+            None => println!("Found {} after {} keys.", found.address(), found.attempts()),
+        }
+
+        let wallet = session.wallet();
+        let import = dialoguer::Confirm::with_theme(theme)
+            .with_prompt(format!(
+                "Replace the {network} address of wallet '{}' with it?",
+                wallet.name()
+            ))
+            .default(true)
+            .interact_on(term)?;
+        if !import {
+            println!("The key was discarded.");
+            return Ok(());
+        }
+
+        let keyfile = if wallet.uses_keyfile() {
+            match KeyfilePrompt::load(theme, term)? {
+                Some(keyfile) => Some(keyfile),
+                None => return Ok(()),
+            }
+        } else {
+            None
+        };
+        let master_password: SecretString = dialoguer::Password::with_theme(theme)
+            .with_prompt("Enter a master password or 'q' to quit")
+            .interact_on(term)?
+            .into();
+        if master_password.expose_secret() == "q" {
+            return Ok(());
+        }
+        match ConsoleService::new()
+            .import_key(
+                wallet,
+                &master_password,
+                keyfile.as_ref(),
+                network,
+                found.private_key(),
+            )
+            .await
+        {
+            Ok(address) => println!(
+                "The {network} address of the wallet is now {address}. \
+                 Open the wallet again to use it, and export a new backup bundle."
+            ),
+            Err(e) => Message::error(format!("Can't import the key: error: {e}").as_str()),
+        }
+        Ok(())
+    }
+}
+
+/// Stops a vanity search when 'q' or Ctrl-C is pressed.
+///
+/// The terminal is in raw mode while watching, so Ctrl-C comes as a key instead of a signal
+/// ending the program.
+struct StopKeys {
+    stopped: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+    watcher: Option<thread::JoinHandle<()>>,
+}
+
+impl StopKeys {
+    fn watch() -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let done = Arc::new(AtomicBool::new(false));
+        let watcher = thread::spawn({
+            let (stopped, done) = (stopped.clone(), done.clone());
+            move || {
+                if terminal::enable_raw_mode().is_err() {
+                    return;
+                }
+                while !done.load(Ordering::Relaxed) {
+                    match event::poll(KEY_POLL_INTERVAL) {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(_) => break,
+                    }
+                    if let Ok(Event::Key(key)) = event::read() {
+                        let ctrl_c = key.code == KeyCode::Char('c')
+                            && key.modifiers.contains(KeyModifiers::CONTROL);
+                        if key.code == KeyCode::Char('q') || ctrl_c {
+                            stopped.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                }
+                let _ = terminal::disable_raw_mode();
+            }
+        });
+        Self {
+            stopped,
+            done,
+            watcher: Some(watcher),
+        }
+    }
+
+    fn stopped(&self) -> Arc<AtomicBool> {
+        self.stopped.clone()
+    }
+}
+
+impl Drop for StopKeys {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(watcher) = self.watcher.take() {
+            let _ = watcher.join();
+        }
+    }
+}
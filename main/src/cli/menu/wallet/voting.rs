@@ -0,0 +1,198 @@
+use crate::cli::menu::wallet::send::SendTransaction;
+use crate::cli::menu::wallet::unlock::UnlockWallet;
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::format_timestamp;
+use crate::cli::styles::message::Message;
+use crate::cli::styles::voting::StyledVoting;
+use cwu_core_address::NetworkAddress;
+use cwu_model::{Network, Voting};
+use cwu_service::{CwuServiceTrait, WalletSession};
+use cwu_settings::CwuConfig;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+/// The witnesses listed to vote for: the super representatives are the 27 most voted.
+const LISTED_WITNESSES: usize = 27;
+
+/// Voting for super representatives with the tron power of staked TRX, and claiming the
+/// rewards the votes earn.
+pub(super) enum VotingMenu {
+    Vote,
+    ClaimRewards,
+}
+
+impl std::fmt::Display for VotingMenu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            VotingMenu::Vote => "Vote for super representatives",
+            VotingMenu::ClaimRewards => "Claim rewards",
+        };
+        f.write_str(s)
+    }
+}
+
+impl VotingMenu {
+    pub(super) async fn apply(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        let Some(address) = session.wallet().addresses().get(&Network::Tron) else {
+            Message::error("The wallet has no Tron address");
+            return Ok(());
+        };
+        let voting = match ConsoleService::new().voting(address, config).await {
+            Ok(voting) => voting,
+            Err(e) => {
+                Message::error(format!("Can't load the votes: error: {e}").as_str());
+                return Ok(());
+            }
+        };
+        println!("{}", StyledVoting::new(&voting));
+
+        let actions = [VotingMenu::Vote, VotingMenu::ClaimRewards];
+        let Some(action) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick an option (press 'q' to quit)")
+            .items(&actions)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        match actions[action] {
+            VotingMenu::Vote => Self::vote(theme, term, session, &voting, config).await,
+            VotingMenu::ClaimRewards => {
+                Self::claim_rewards(theme, term, session, &voting, config).await
+            }
+        }
+    }
+
+    async fn vote(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        voting: &Voting,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        if voting.tron_power == 0 {
+            println!("The wallet has no tron power: stake TRX first, one vote per TRX.");
+            return Ok(());
+        }
+        let witnesses = match ConsoleService::new()
+            .witnesses(LISTED_WITNESSES, config)
+            .await
+        {
+            Ok(witnesses) => witnesses,
+            Err(e) => {
+                Message::error(format!("Can't load the witnesses: error: {e}").as_str());
+                return Ok(());
+            }
+        };
+        let items: Vec<_> = witnesses
+            .iter()
+            .map(|witness| {
+                format!(
+                    "{} {} - {} votes, keeps {}% of the rewards",
+                    witness.address, witness.url, witness.votes, witness.brokerage
+                )
+            })
+            .collect();
+        let Some(picked) = dialoguer::MultiSelect::with_theme(theme)
+            .with_prompt("Pick the witnesses to vote for with space (press 'q' to quit)")
+            .items(&items)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        if picked.is_empty() {
+            println!("No witness picked.");
+            return Ok(());
+        }
+
+        let mut remaining = voting.tron_power;
+        let mut votes = Vec::new();
+        for (i, witness) in picked.iter().map(|i| &witnesses[*i]).enumerate() {
+            // The last witness gets the remaining votes by default.
+            let default = if i + 1 == picked.len() { remaining } else { 0 };
+            let count: u64 = dialoguer::Input::with_theme(theme)
+                .with_prompt(format!(
+                    "How many votes for {}? {remaining} left",
+                    witness.address
+                ))
+                .default(default)
+                .validate_with(|count: &u64| {
+                    if *count <= remaining {
+                        Ok(())
+                    } else {
+                        Err(format!("At most {remaining} votes"))
+                    }
+                })
+                .interact_text_on(term)?;
+            if count == 0 {
+                continue;
+            }
+            remaining -= count;
+            let witness = match witness.address.parse() {
+                Ok(witness) => NetworkAddress::Tron(witness),
+                Err(e) => {
+                    Message::error(format!("Invalid address {}: {e}", witness.address).as_str());
+                    return Ok(());
+                }
+            };
+            votes.push((witness, count));
+        }
+        if votes.is_empty() {
+            println!("No votes cast.");
+            return Ok(());
+        }
+
+        let cast = voting.tron_power - remaining;
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt(format!(
+                "Cast {cast} votes for {} witnesses? They replace the current votes",
+                votes.len()
+            ))
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed || !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+        let sent = ConsoleService::new().vote(session, &votes, config).await;
+        SendTransaction::report(sent, config).await;
+        Ok(())
+    }
+
+    async fn claim_rewards(
+        theme: &dyn Theme,
+        term: &Term,
+        session: &WalletSession,
+        voting: &Voting,
+        config: &CwuConfig,
+    ) -> Result<(), anyhow::Error> {
+        if voting.reward == "0" {
+            println!("No rewards to claim.");
+            return Ok(());
+        }
+        if let Some(claimable_at) = voting.claimable_at {
+            Message::error(
+                format!(
+                    "The rewards can be claimed once a day, next from {}",
+                    format_timestamp(claimable_at)
+                )
+                .as_str(),
+            );
+            return Ok(());
+        }
+        let confirmed = dialoguer::Confirm::with_theme(theme)
+            .with_prompt(format!("Claim {} TRX to the balance?", voting.reward))
+            .default(false)
+            .interact_on(term)?;
+        if !confirmed || !UnlockWallet::ensure(theme, term, session).await? {
+            return Ok(());
+        }
+        let sent = ConsoleService::new().claim_rewards(session, config).await;
+        SendTransaction::report(sent, config).await;
+        Ok(())
+    }
+}
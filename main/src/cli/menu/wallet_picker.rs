@@ -0,0 +1,70 @@
+use cwu_wallet::{EncryptedWallet, WalletHeader};
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+/// A wallet found in the current directory, described by its plaintext header.
+struct WalletEntry {
+    name: String,
+    header: Option<WalletHeader>,
+}
+
+impl std::fmt::Display for WalletEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        match &self.header {
+            Some(header) => {
+                for (network, address) in header.addresses() {
+                    write!(f, " | {network}: {address}")?;
+                }
+                Ok(())
+            }
+            None => write!(f, " | locked (legacy wallet)"),
+        }
+    }
+}
+
+pub(super) enum WalletPicker {}
+
+impl WalletPicker {
+    /// Lets the user pick one of the wallets in the current directory, or type a name.
+    ///
+    /// Returns `None` if the user quits.
+    pub(super) fn pick_name(
+        theme: &dyn Theme,
+        term: &Term,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let wallets = wallets();
+        if !wallets.is_empty() {
+            let mut items: Vec<String> = wallets.iter().map(ToString::to_string).collect();
+            items.push("Enter a wallet name".to_string());
+            let choice = dialoguer::Select::with_theme(theme)
+                .with_prompt("Pick a wallet (press 'q' to back)")
+                .items(&items)
+                .default(0)
+                .interact_opt()?;
+            match choice {
+                None => return Ok(None),
+                Some(i) if i < wallets.len() => return Ok(Some(wallets[i].name.clone())),
+                Some(_) => {}
+            }
+        }
+
+        let name: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter a wallet name or 'q' to quit")
+            .interact_text_on(term)?;
+        Ok(Some(name).filter(|name| name != "q"))
+    }
+
+    /// The headers of the wallets in the current directory that have one.
+    pub(super) fn headers() -> Vec<WalletHeader> {
+        wallets().into_iter().filter_map(|w| w.header).collect()
+    }
+}
+
+fn wallets() -> Vec<WalletEntry> {
+    EncryptedWallet::list(".")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, header)| WalletEntry { name, header })
+        .collect()
+}
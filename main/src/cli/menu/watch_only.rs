@@ -0,0 +1,55 @@
+use crate::cli::service::ConsoleService;
+use crate::cli::styles::message::Message;
+use cwu_model::Network;
+use cwu_service::CwuServiceTrait;
+use dialoguer::console::Term;
+use dialoguer::theme::Theme;
+
+/// How many addresses are shown at once.
+const PAGE_SIZE: u32 = 10;
+
+pub(super) enum WatchOnly {}
+
+impl WatchOnly {
+    pub(super) async fn apply(theme: &dyn Theme, term: &Term) -> Result<(), anyhow::Error> {
+        let networks = Network::ALL_VARIANTS;
+        let Some(network) = dialoguer::Select::with_theme(theme)
+            .with_prompt("Pick a network (press 'q' to quit)")
+            .items(networks)
+            .default(0)
+            .interact_opt()?
+        else {
+            return Ok(());
+        };
+        let network = networks[network];
+        let xpub: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("Enter an account extended public key (xpub, ypub, zpub...)")
+            .interact_text_on(term)?;
+
+        let mut start = 0;
+        loop {
+            match ConsoleService::new()
+                .watch_only_addresses(network, xpub.as_str(), start, PAGE_SIZE)
+                .await
+            {
+                Ok(addresses) => {
+                    for (index, address) in (start..).zip(addresses) {
+                        println!("{index:>6}  {address}");
+                    }
+                }
+                Err(e) => {
+                    Message::error(format!("Can't derive the addresses: error: {e}").as_str());
+                    return Ok(());
+                }
+            }
+            start += PAGE_SIZE;
+            let more = dialoguer::Confirm::with_theme(theme)
+                .with_prompt("Show the next addresses?")
+                .default(false)
+                .interact_on(term)?;
+            if !more {
+                return Ok(());
+            }
+        }
+    }
+}
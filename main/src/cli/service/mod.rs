@@ -1,8 +1,18 @@
 use crate::cli::styles::progress::Progress;
-use cwu_model::Balance;
-use cwu_service::{CwuService, CwuServiceTrait, Result};
+use cwu_core_address::NetworkAddress;
+use cwu_model::{
+    Asset, Balance, Delegation, FeeEstimate, Network, Resource, SentTransaction, SignedTransaction,
+    Staking, TransactionDetails, TransactionStatus, TransferPage, Voting, Witness,
+};
+use cwu_security_utils::{Keyfile, SecretString};
+use cwu_service::{CwuService, CwuServiceTrait, Result, WalletSession};
 use cwu_settings::CwuConfig;
-use cwu_wallet::EncryptedWallet;
+use cwu_wallet::{
+    AuditReport, EncryptedWallet, ExtendedPublicKey, RecoveryPhrase, RestoredWallet, VanityMatch,
+    VanityPattern, VanityProgress,
+};
+use std::path::Path;
+use std::time::Duration;
 
 pub(crate) struct ConsoleService {
     internal: CwuService,
@@ -22,39 +32,492 @@ impl CwuServiceTrait for ConsoleService {
         word_count: i32,
         language: &str,
         wallet_name: &str,
-    ) -> Result<String> {
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<SecretString> {
         let progress = Progress::with_spinner("Creating a new wallet...");
         let master_password = self
             .internal
-            .create_wallet(word_count, language, wallet_name)
+            .create_wallet(word_count, language, wallet_name, master_password, keyfile)
             .await;
         progress.finish();
         master_password
     }
 
-    async fn open_wallet(&self, name: &str, master_password: String) -> Result<EncryptedWallet> {
+    async fn open_wallet(
+        &self,
+        name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<EncryptedWallet> {
         let progress = Progress::with_spinner(format!("Opening wallet '{}'...", name).as_str());
-        let wallet = self.internal.open_wallet(name, master_password).await;
+        let wallet = self
+            .internal
+            .open_wallet(name, master_password, keyfile)
+            .await;
         progress.finish();
         wallet
     }
 
-    async fn backup_wallet(
+    async fn unlock_wallet(
+        &self,
+        session: &WalletSession,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<()> {
+        let progress = Progress::with_spinner(
+            format!("Unlocking wallet '{}'...", session.wallet().name()).as_str(),
+        );
+        let result = self
+            .internal
+            .unlock_wallet(session, master_password, keyfile)
+            .await;
+        progress.finish();
+        result
+    }
+
+    async fn backup_wallet(&self, session: &WalletSession) -> Result<RecoveryPhrase> {
+        let progress = Progress::with_spinner(
+            format!("Backup wallet '{}'...", session.wallet().name()).as_str(),
+        );
+        let mnemonic = self.internal.backup_wallet(session).await;
+        progress.finish();
+        mnemonic
+    }
+
+    async fn add_decoy_wallet(
+        &self,
+        wallet_name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        duress_password: Option<SecretString>,
+    ) -> Result<SecretString> {
+        let progress = Progress::with_spinner("Creating a decoy wallet...");
+        let duress_password = self
+            .internal
+            .add_decoy_wallet(wallet_name, master_password, keyfile, duress_password)
+            .await;
+        progress.finish();
+        duress_password
+    }
+
+    async fn export_backup(
         &self,
         wallet: &EncryptedWallet,
-        master_password: String,
-    ) -> Result<String> {
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        backup_password: &SecretString,
+        path: &str,
+    ) -> Result<()> {
+        let progress = Progress::with_spinner(
+            format!("Exporting wallet '{}' to '{}'...", wallet.name(), path).as_str(),
+        );
+        let result = self
+            .internal
+            .export_backup(wallet, master_password, keyfile, backup_password, path)
+            .await;
+        progress.finish();
+        result
+    }
+
+    async fn import_backup(
+        &self,
+        path: &str,
+        backup_password: &SecretString,
+        wallet_name: Option<&str>,
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<RestoredWallet> {
         let progress =
-            Progress::with_spinner(format!("Backup wallet '{}'...", wallet.name()).as_str());
-        let mnemonic = self.internal.backup_wallet(wallet, master_password).await;
+            Progress::with_spinner(format!("Restoring a wallet from '{}'...", path).as_str());
+        let wallet = self
+            .internal
+            .import_backup(path, backup_password, wallet_name, master_password, keyfile)
+            .await;
         progress.finish();
-        mnemonic
+        wallet
+    }
+
+    async fn verify_audit_log(&self, wallet: &EncryptedWallet) -> Result<AuditReport> {
+        let progress = Progress::with_spinner("Verifying the audit log...");
+        let report = self.internal.verify_audit_log(wallet).await;
+        progress.finish();
+        report
+    }
+
+    async fn vanity_search(
+        &self,
+        pattern: &VanityPattern,
+        session: Option<&WalletSession>,
+        threads: usize,
+        mut on_progress: impl FnMut(&VanityProgress) -> bool + Send + 'static,
+    ) -> Result<Option<VanityMatch>> {
+        let progress = Progress::with_spinner(
+            format!(
+                "Searching ~{:.0} keys for a vanity address...",
+                pattern.expected_attempts()
+            )
+            .as_str(),
+        );
+        let reporter = progress.clone();
+        let found = self
+            .internal
+            .vanity_search(pattern, session, threads, move |snapshot| {
+                let remaining = snapshot
+                    .expected_remaining()
+                    .map_or("-".to_string(), |d| format!("{}s", d.as_secs()));
+                reporter.set_message(
+                    format!(
+                        "Tried {} keys ({:.0}/s), {:.1}% chance so far, ~{} to go on average \
+                         (press 'q' to stop)",
+                        snapshot.attempts(),
+                        snapshot.rate(),
+                        snapshot.probability() * 100.0,
+                        remaining
+                    )
+                    .as_str(),
+                );
+                on_progress(snapshot)
+            })
+            .await;
+        progress.finish();
+        found
     }
 
-    async fn check_balance(&self, address: &str, config: &CwuConfig) -> Result<Balance> {
+    async fn import_key(
+        &self,
+        wallet: &EncryptedWallet,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        network: Network,
+        private_key: &SecretString,
+    ) -> Result<NetworkAddress> {
+        let progress = Progress::with_spinner(
+            format!("Importing the {} key into '{}'...", network, wallet.name()).as_str(),
+        );
+        let address = self
+            .internal
+            .import_key(wallet, master_password, keyfile, network, private_key)
+            .await;
+        progress.finish();
+        address
+    }
+
+    async fn export_xpubs(
+        &self,
+        session: &WalletSession,
+    ) -> Result<Vec<(Network, ExtendedPublicKey)>> {
+        let progress = Progress::with_spinner("Deriving the extended public keys...");
+        let xpubs = self.internal.export_xpubs(session).await;
+        progress.finish();
+        xpubs
+    }
+
+    async fn watch_only_addresses(
+        &self,
+        network: Network,
+        xpub: &str,
+        start: u32,
+        count: u32,
+    ) -> Result<Vec<NetworkAddress>> {
+        self.internal
+            .watch_only_addresses(network, xpub, start, count)
+            .await
+    }
+
+    async fn estimate_fee(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        config: &CwuConfig,
+    ) -> Result<FeeEstimate> {
+        let progress = Progress::with_spinner("Estimating the fee...");
+        let estimate = self
+            .internal
+            .estimate_fee(session, to, asset, amount, config)
+            .await;
+        progress.finish();
+        estimate
+    }
+
+    fn validate_amount(&self, network: Network, asset: Asset, amount: &str) -> Result<()> {
+        self.internal.validate_amount(network, asset, amount)
+    }
+
+    async fn send(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress = Progress::with_spinner(format!("Sending to {to}...").as_str());
+        let sent = self.internal.send(session, to, asset, amount, config).await;
+        progress.finish();
+        sent
+    }
+
+    async fn export_transaction(
+        &self,
+        from: &NetworkAddress,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        path: &Path,
+        config: &CwuConfig,
+    ) -> Result<TransactionDetails> {
+        let progress = Progress::with_spinner("Building the transaction...");
+        let details = self
+            .internal
+            .export_transaction(from, to, asset, amount, path, config)
+            .await;
+        progress.finish();
+        details
+    }
+
+    async fn read_transaction_file(
+        &self,
+        path: &Path,
+        config: &CwuConfig,
+    ) -> Result<TransactionDetails> {
+        let progress = Progress::with_spinner("Checking the transaction...");
+        let details = self.internal.read_transaction_file(path, config).await;
+        progress.finish();
+        details
+    }
+
+    async fn sign_transaction_file(
+        &self,
+        session: &WalletSession,
+        path: &Path,
+        signed_path: &Path,
+        config: &CwuConfig,
+    ) -> Result<SignedTransaction> {
+        let progress = Progress::with_spinner("Signing the transaction...");
+        let signed = self
+            .internal
+            .sign_transaction_file(session, path, signed_path, config)
+            .await;
+        progress.finish();
+        signed
+    }
+
+    async fn broadcast_transaction_file(&self, path: &Path, config: &CwuConfig) -> Result<String> {
+        let progress = Progress::with_spinner("Broadcasting the transaction...");
+        let tx_id = self.internal.broadcast_transaction_file(path, config).await;
+        progress.finish();
+        tx_id
+    }
+
+    async fn track_transaction(
+        &self,
+        network: Network,
+        tx_id: &str,
+        config: &CwuConfig,
+        mut on_status: impl FnMut(&TransactionStatus) + Send,
+    ) -> Result<TransactionStatus> {
+        let progress = Progress::with_spinner("Waiting for the transaction to be confirmed...");
+        let status = self
+            .internal
+            .track_transaction(network, tx_id, config, |status| {
+                progress.set_message(
+                    format!("{status}, waiting for the transaction to be solidified...").as_str(),
+                );
+                on_status(status)
+            })
+            .await;
+        progress.finish();
+        status
+    }
+
+    async fn transaction_history(
+        &self,
+        session: &WalletSession,
+        address: &NetworkAddress,
+        start: usize,
+        count: usize,
+        config: &CwuConfig,
+    ) -> Result<TransferPage> {
+        let progress = Progress::with_spinner("Loading transactions...");
+        let page = self
+            .internal
+            .transaction_history(session, address, start, count, config)
+            .await;
+        progress.finish();
+        page
+    }
+
+    async fn check_balance(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<Balance> {
         let progress = Progress::with_spinner("Checking balance...");
         let balance = self.internal.check_balance(address, config).await;
         progress.finish();
         balance
     }
+
+    async fn is_activated(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<bool> {
+        let progress = Progress::with_spinner("Checking the recipient...");
+        let activated = self.internal.is_activated(address, config).await;
+        progress.finish();
+        activated
+    }
+
+    async fn staking(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<Staking> {
+        let progress = Progress::with_spinner("Checking staking...");
+        let staking = self.internal.staking(address, config).await;
+        progress.finish();
+        staking
+    }
+
+    async fn stake(
+        &self,
+        session: &WalletSession,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress =
+            Progress::with_spinner(format!("Staking {amount} TRX for {resource}...").as_str());
+        let sent = self.internal.stake(session, amount, resource, config).await;
+        progress.finish();
+        sent
+    }
+
+    async fn unstake(
+        &self,
+        session: &WalletSession,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress =
+            Progress::with_spinner(format!("Unstaking {amount} TRX from {resource}...").as_str());
+        let sent = self
+            .internal
+            .unstake(session, amount, resource, config)
+            .await;
+        progress.finish();
+        sent
+    }
+
+    async fn withdraw_unstaked(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress = Progress::with_spinner("Withdrawing unstaked TRX...");
+        let sent = self.internal.withdraw_unstaked(session, config).await;
+        progress.finish();
+        sent
+    }
+
+    async fn cancel_unstaking(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress = Progress::with_spinner("Cancelling unstaking...");
+        let sent = self.internal.cancel_unstaking(session, config).await;
+        progress.finish();
+        sent
+    }
+
+    async fn delegations(
+        &self,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> Result<Vec<Delegation>> {
+        let progress = Progress::with_spinner("Loading delegations...");
+        let delegations = self.internal.delegations(address, config).await;
+        progress.finish();
+        delegations
+    }
+
+    async fn delegatable(
+        &self,
+        address: &NetworkAddress,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<String> {
+        let progress =
+            Progress::with_spinner(format!("Checking the delegatable {resource}...").as_str());
+        let amount = self.internal.delegatable(address, resource, config).await;
+        progress.finish();
+        amount
+    }
+
+    async fn delegate(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        amount: &str,
+        resource: Resource,
+        lock: Option<Duration>,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress = Progress::with_spinner(format!("Delegating {resource} to {to}...").as_str());
+        let sent = self
+            .internal
+            .delegate(session, to, amount, resource, lock, config)
+            .await;
+        progress.finish();
+        sent
+    }
+
+    async fn undelegate(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress =
+            Progress::with_spinner(format!("Taking back {resource} from {to}...").as_str());
+        let sent = self
+            .internal
+            .undelegate(session, to, amount, resource, config)
+            .await;
+        progress.finish();
+        sent
+    }
+
+    async fn witnesses(&self, count: usize, config: &CwuConfig) -> Result<Vec<Witness>> {
+        let progress = Progress::with_spinner("Loading witnesses...");
+        let witnesses = self.internal.witnesses(count, config).await;
+        progress.finish();
+        witnesses
+    }
+
+    async fn voting(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<Voting> {
+        let progress = Progress::with_spinner("Loading votes and rewards...");
+        let voting = self.internal.voting(address, config).await;
+        progress.finish();
+        voting
+    }
+
+    async fn vote(
+        &self,
+        session: &WalletSession,
+        votes: &[(NetworkAddress, u64)],
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress = Progress::with_spinner("Voting...");
+        let sent = self.internal.vote(session, votes, config).await;
+        progress.finish();
+        sent
+    }
+
+    async fn claim_rewards(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let progress = Progress::with_spinner("Claiming rewards...");
+        let sent = self.internal.claim_rewards(session, config).await;
+        progress.finish();
+        sent
+    }
 }
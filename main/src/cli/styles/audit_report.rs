@@ -0,0 +1,62 @@
+use crate::cli::styles::format_timestamp;
+use cwu_wallet::{AuditEntry, AuditReport};
+use std::fmt::{Display, Formatter};
+use termimad::MadSkin;
+use termimad::crossterm::style::Stylize;
+
+pub struct StyledAuditReport<'a> {
+    report: &'a AuditReport,
+}
+
+impl<'a> StyledAuditReport<'a> {
+    pub fn new(report: &'a AuditReport) -> Self {
+        StyledAuditReport { report }
+    }
+}
+
+impl<'a> Display for StyledAuditReport<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let skin = MadSkin::default();
+
+        let header = "--- AUDIT LOG ---";
+        let footer = format!("**{}**", "-".repeat(header.len()));
+
+        let mut lines = vec![format!("**{}**", header.green())];
+        if self.report.entries().is_empty() {
+            lines.push("No entries yet".to_string());
+        }
+        for entry in self.report.entries() {
+            lines.push(format_entry(entry));
+        }
+        if self.report.is_intact() {
+            lines.push(format!(
+                "{}",
+                format!("**Intact: {} entries**", self.report.entries().len()).green()
+            ));
+        } else {
+            lines.push(format!("{}", "**The log was tampered with:**".red()));
+            for problem in self.report.problems() {
+                lines.push(format!("{}", format!("- {problem}").red()));
+            }
+        }
+        lines.push(format!("{}", footer.green()));
+
+        write!(f, "{}", skin.inline(&lines.join("\n")))
+    }
+}
+
+fn format_entry(entry: &AuditEntry) -> String {
+    let event = entry.event();
+    let mut line = format!(
+        "{} **{}**",
+        format_timestamp(entry.timestamp()),
+        event.operation()
+    );
+    if let (Some(network), Some(address)) = (event.network(), event.address()) {
+        line.push_str(&format!(" {network} {address}"));
+    }
+    if let Some(tx_id) = event.tx_id() {
+        line.push_str(&format!(" tx {tx_id}"));
+    }
+    line
+}
@@ -20,13 +20,30 @@ impl<'a> Display for StyledBalance<'a> {
         let header = "--- CURRENT BALANCE ---";
         let footer = format!("**{}**", "-".repeat(header.len()));
 
-        let styled_message = format!(
-            "**{}**\n**Network:** {}\n**USDT:** {}\n{}",
-            header.green(),
-            self.balance.network(),
-            self.balance.usdt(),
-            footer.green()
-        );
+        let mut lines = vec![
+            format!("**{}**", header.green()),
+            format!("**Network:** {}", self.balance.network()),
+            format!("**USDT:** {}", self.balance.usdt()),
+        ];
+        if let Some(account) = self.balance.tron_account() {
+            lines.push(format!("**TRX:** {}", account.trx));
+            lines.push(format!("**Staked:** {} TRX", account.staked));
+            lines.push(format!(
+                "**Energy:** {} of {}",
+                account.energy, account.energy_limit
+            ));
+            lines.push(format!(
+                "**Bandwidth:** {} of {}",
+                account.bandwidth, account.bandwidth_limit
+            ));
+            if account.activated {
+                lines.push("**Activated:** yes".to_string());
+            } else {
+                lines.push("**Activated:** no, the first TRX sent to it activates it".to_string());
+            }
+        }
+        lines.push(format!("{}", footer.green()));
+        let styled_message = lines.join("\n");
 
         write!(f, "{}", skin.inline(&styled_message))
     }
@@ -0,0 +1,57 @@
+use crate::cli::styles::format_timestamp;
+use cwu_model::Delegation;
+use std::fmt::{Display, Formatter};
+use termimad::MadSkin;
+use termimad::crossterm::style::Stylize;
+
+pub struct StyledDelegations<'a> {
+    /// The address the delegations are shown for.
+    address: &'a str,
+    delegations: &'a [Delegation],
+}
+
+impl<'a> StyledDelegations<'a> {
+    pub fn new(address: &'a str, delegations: &'a [Delegation]) -> Self {
+        StyledDelegations {
+            address,
+            delegations,
+        }
+    }
+}
+
+impl<'a> Display for StyledDelegations<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let skin = MadSkin::default();
+
+        let header = "--- DELEGATIONS ---";
+        let footer = format!("**{}**", "-".repeat(header.len()));
+
+        let mut lines = vec![format!("**{}**", header.green())];
+        if self.delegations.is_empty() {
+            lines.push("No delegations".to_string());
+        }
+        for delegation in self.delegations {
+            let mut line = if delegation.from == self.address {
+                format!(
+                    "{} TRX of {} **to** {}",
+                    delegation.amount, delegation.resource, delegation.to
+                )
+            } else {
+                format!(
+                    "{} TRX of {} **from** {}",
+                    delegation.amount, delegation.resource, delegation.from
+                )
+            };
+            if let Some(locked_until) = delegation.locked_until {
+                line.push_str(&format!(
+                    ", locked until {}",
+                    format_timestamp(locked_until)
+                ));
+            }
+            lines.push(line);
+        }
+        lines.push(format!("{}", footer.green()));
+
+        write!(f, "{}", skin.inline(&lines.join("\n")))
+    }
+}
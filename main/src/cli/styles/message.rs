@@ -21,4 +21,13 @@ impl Message {
 
         let _ = io::stderr().flush();
     }
+
+    pub fn warning(warning: &str) {
+        let markdown_text = format!("**{}**", warning);
+        let skin = MadSkin::default();
+        let styled_output = skin.inline(&markdown_text);
+        eprintln!("{}", styled_output.to_string().yellow());
+
+        let _ = io::stderr().flush();
+    }
 }
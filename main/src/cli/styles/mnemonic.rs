@@ -1,14 +1,15 @@
+use cwu_wallet::RecoveryPhrase;
 use std::fmt::{Display, Formatter};
 use termimad::MadSkin;
 use termimad::crossterm::style::Stylize;
 
 pub struct StyledMnemonic {
-    mnemonic: String,
+    phrase: RecoveryPhrase,
 }
 
 impl StyledMnemonic {
-    pub fn new(mnemonic: String) -> Self {
-        StyledMnemonic { mnemonic }
+    pub fn new(phrase: RecoveryPhrase) -> Self {
+        StyledMnemonic { phrase }
     }
 }
 
@@ -20,7 +21,7 @@ impl Display for StyledMnemonic {
         let footer = format!("**{}**", "-".repeat(header.len())).red();
 
         let header = header.to_string().red();
-        let info = "**Anyone who has this phrase controls all your funds**\n"
+        let info = "**Anyone who has this phrase and passphrase controls all your funds**\n"
             .to_string()
             .red();
         let rules = "🚨 **Security Rules: Backup OFFLINE**\n";
@@ -28,15 +29,24 @@ impl Display for StyledMnemonic {
         let rule2 = "- **NEVER** share this phrase with anyone\n";
         let backup = "✅ **Secure Backup Steps**\n".to_string().green();
         let step1 = "1. **Write** it down physically on paper or metal.\n";
-        let step2 = "2. **Verify** the spelling and order of every word.\n";
+        let step2 = "2. **Verify** the spelling and order of every word, and every character \
+                     of the passphrase: the phrase alone doesn't recover the funds.\n";
         let step3 = "3. **Store copies** in separate, secure locations (e.g., safe, bank vault).\n";
-        let mnemonic = format!("\n**{}**\n", self.mnemonic.to_uppercase()).green();
+        let mnemonic = format!(
+            "\n**{}**\n",
+            self.phrase.mnemonic.expose_secret().to_uppercase()
+        )
+        .green();
+        // Printed verbatim: its symbols would be taken for markdown.
+        let passphrase = format!("Passphrase: {}", self.phrase.passphrase.expose_secret()).green();
 
         let styled_message = format!(
-            "{}{}{}{}{}{}{}{}{}{}{}",
-            header, info, rules, rule1, rule2, backup, step1, step2, step3, mnemonic, footer
+            "{}{}{}{}{}{}{}{}{}{}",
+            header, info, rules, rule1, rule2, backup, step1, step2, step3, mnemonic
         );
 
-        write!(f, "{}", skin.inline(&styled_message))
+        writeln!(f, "{}", skin.inline(&styled_message))?;
+        writeln!(f, "{passphrase}")?;
+        write!(f, "{}", skin.inline(&footer.to_string()))
     }
 }
@@ -1,4 +1,30 @@
+pub(crate) mod audit_report;
 pub(crate) mod balance;
+pub(crate) mod delegations;
 pub(crate) mod message;
 pub(crate) mod mnemonic;
 pub(crate) mod progress;
+pub(crate) mod staking;
+pub(crate) mod transfers;
+pub(crate) mod voting;
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+pub(crate) fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+    // Howard Hinnant's `civil_from_days`.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
@@ -1,6 +1,7 @@
 use indicatif::ProgressBar;
 use std::time::Duration;
 
+#[derive(Clone)]
 pub(crate) struct Progress {
     progress_bar: ProgressBar,
 }
@@ -10,6 +11,10 @@ impl Progress {
         Self { progress_bar }
     }
 
+    pub fn set_message(&self, message: &str) {
+        self.progress_bar.set_message(message.to_owned())
+    }
+
     pub fn finish(&self) {
         self.progress_bar.finish_using_style()
     }
@@ -0,0 +1,50 @@
+use crate::cli::styles::format_timestamp;
+use cwu_model::Staking;
+use std::fmt::{Display, Formatter};
+use termimad::MadSkin;
+use termimad::crossterm::style::Stylize;
+
+pub struct StyledStaking<'a> {
+    staking: &'a Staking,
+}
+
+impl<'a> StyledStaking<'a> {
+    pub fn new(staking: &'a Staking) -> Self {
+        StyledStaking { staking }
+    }
+}
+
+impl<'a> Display for StyledStaking<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let skin = MadSkin::default();
+
+        let header = "--- STAKING ---";
+        let footer = format!("**{}**", "-".repeat(header.len()));
+
+        let mut lines = vec![
+            format!("**{}**", header.green()),
+            format!("**Network:** {}", self.staking.network),
+            format!("**Staked for bandwidth:** {} TRX", self.staking.bandwidth),
+            format!("**Staked for energy:** {} TRX", self.staking.energy),
+            format!(
+                "**Delegated:** {} TRX of bandwidth, {} TRX of energy",
+                self.staking.delegated_bandwidth, self.staking.delegated_energy
+            ),
+        ];
+        for unstaking in &self.staking.unstaking {
+            lines.push(format!(
+                "**Unstaking:** {} TRX from {}, unlocks {}",
+                unstaking.amount,
+                unstaking.resource,
+                format_timestamp(unstaking.unlocks_at)
+            ));
+        }
+        lines.push(format!(
+            "**Withdrawable:** {} TRX",
+            self.staking.withdrawable
+        ));
+        lines.push(format!("{}", footer.green()));
+
+        write!(f, "{}", skin.inline(&lines.join("\n")))
+    }
+}
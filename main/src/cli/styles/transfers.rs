@@ -0,0 +1,59 @@
+use crate::cli::styles::format_timestamp;
+use cwu_model::{Asset, Direction, Transfer, TransferStatus};
+use std::fmt::{Display, Formatter};
+use termimad::MadSkin;
+use termimad::crossterm::style::Stylize;
+
+pub struct StyledTransfers<'a> {
+    transfers: &'a [Transfer],
+}
+
+impl<'a> StyledTransfers<'a> {
+    pub fn new(transfers: &'a [Transfer]) -> Self {
+        StyledTransfers { transfers }
+    }
+}
+
+impl<'a> Display for StyledTransfers<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let skin = MadSkin::default();
+
+        let header = "--- TRANSACTION HISTORY ---";
+        let footer = format!("**{}**", "-".repeat(header.len()));
+
+        let mut lines = vec![format!("**{}**", header.green())];
+        if self.transfers.is_empty() {
+            lines.push("No transactions yet".to_string());
+        }
+        for transfer in self.transfers {
+            lines.push(format_transfer(transfer));
+        }
+        lines.push(format!("{}", footer.green()));
+
+        write!(f, "{}", skin.inline(&lines.join("\n")))
+    }
+}
+
+fn format_transfer(transfer: &Transfer) -> String {
+    let symbol = transfer.asset.symbol(transfer.network);
+    let amount = match transfer.direction {
+        Direction::In => format!("+{} {symbol} from", transfer.amount).green(),
+        Direction::Out => format!("-{} {symbol} to", transfer.amount).red(),
+    };
+    let mut line = format!(
+        "{} **{amount}** {}",
+        format_timestamp(transfer.timestamp),
+        transfer.counterparty
+    );
+    if let Some(fee) = &transfer.fee {
+        line.push_str(&format!(
+            ", fee {fee} {}",
+            Asset::Native.symbol(transfer.network)
+        ));
+    }
+    if let TransferStatus::Failed(error) = &transfer.status {
+        line.push_str(&format!(" {}", format!("**FAILED: {error}**").red()));
+    }
+    line.push_str(&format!("\n    tx {}", transfer.tx_id));
+    line
+}
@@ -0,0 +1,48 @@
+use crate::cli::styles::format_timestamp;
+use cwu_model::Voting;
+use std::fmt::{Display, Formatter};
+use termimad::MadSkin;
+use termimad::crossterm::style::Stylize;
+
+pub struct StyledVoting<'a> {
+    voting: &'a Voting,
+}
+
+impl<'a> StyledVoting<'a> {
+    pub fn new(voting: &'a Voting) -> Self {
+        StyledVoting { voting }
+    }
+}
+
+impl<'a> Display for StyledVoting<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let skin = MadSkin::default();
+
+        let header = "--- VOTING ---";
+        let footer = format!("**{}**", "-".repeat(header.len()));
+
+        let cast: u64 = self.voting.votes.iter().map(|vote| vote.count).sum();
+        let mut lines = vec![
+            format!("**{}**", header.green()),
+            format!("**Network:** {}", self.voting.network),
+            format!(
+                "**Tron power:** {} votes, {cast} cast",
+                self.voting.tron_power
+            ),
+        ];
+        for vote in &self.voting.votes {
+            lines.push(format!("**Vote:** {} for {}", vote.count, vote.witness));
+        }
+        let mut reward = format!("**Reward:** {} TRX", self.voting.reward);
+        if let Some(claimable_at) = self.voting.claimable_at {
+            reward.push_str(&format!(
+                ", claimable from {}",
+                format_timestamp(claimable_at)
+            ));
+        }
+        lines.push(reward);
+        lines.push(format!("{}", footer.green()));
+
+        write!(f, "{}", skin.inline(&lines.join("\n")))
+    }
+}
@@ -0,0 +1,21 @@
+use crate::network::Network;
+use serde::{Deserialize, Serialize};
+
+/// What a transaction moves: the native coin of its network, or USDT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Asset {
+    Native,
+    Usdt,
+}
+
+impl Asset {
+    /// The ticker of the asset on `network`, e.g. `TRX` for the native coin of Tron.
+    pub fn symbol(&self, network: Network) -> &'static str {
+        match (self, network) {
+            (Asset::Usdt, _) => "USDT",
+            (Asset::Native, Network::Ethereum) => "ETH",
+            (Asset::Native, Network::Tron) => "TRX",
+            (Asset::Native, Network::Bitcoin) => "BTC",
+        }
+    }
+}
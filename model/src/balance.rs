@@ -1,22 +1,62 @@
 use crate::network::Network;
 use std::fmt::{Display, Formatter};
 
+/// The TRX and resources of a Tron account, amounts being decimal TRX.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TronAccount {
+    pub trx: String,
+    /// Staked with Stake 2.0, including what is delegated to other addresses.
+    pub staked: String,
+    pub energy: u64,
+    pub energy_limit: u64,
+    /// The staked and the daily free bandwidth left.
+    pub bandwidth: u64,
+    pub bandwidth_limit: u64,
+    /// Whether the account exists on chain: an address is activated by the first TRX sent to
+    /// it, which burns 1 TRX more.
+    pub activated: bool,
+}
+
 #[derive(Debug)]
 pub struct Balance {
     network: Network,
     usdt: String,
+    tron_account: Option<TronAccount>,
 }
 
 impl Display for Balance {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Network: {}", self.network)?;
-        write!(f, "USDT Balance: {}", self.usdt)
+        write!(f, "USDT Balance: {}", self.usdt)?;
+        if let Some(account) = &self.tron_account {
+            writeln!(f)?;
+            writeln!(f, "TRX Balance: {}", account.trx)?;
+            writeln!(f, "Staked: {} TRX", account.staked)?;
+            writeln!(f, "Energy: {}/{}", account.energy, account.energy_limit)?;
+            writeln!(
+                f,
+                "Bandwidth: {}/{}",
+                account.bandwidth, account.bandwidth_limit
+            )?;
+            write!(f, "Activated: {}", account.activated)?;
+        }
+        Ok(())
     }
 }
 
 impl Balance {
     pub fn new(network: Network, usdt: String) -> Self {
-        Self { network, usdt }
+        Self {
+            network,
+            usdt,
+            tron_account: None,
+        }
+    }
+
+    /// Adds the TRX and resources of the account, for Tron balances.
+    pub fn with_tron_account(mut self, account: TronAccount) -> Self {
+        self.tron_account = Some(account);
+        self
     }
 
     pub fn network(&self) -> &Network {
@@ -26,4 +66,8 @@ impl Balance {
     pub fn usdt(&self) -> &String {
         &self.usdt
     }
+
+    pub fn tron_account(&self) -> Option<&TronAccount> {
+        self.tron_account.as_ref()
+    }
 }
@@ -0,0 +1,121 @@
+use std::fmt::{Display, Formatter};
+
+/// The expected cost of a Tron transaction.
+///
+/// A transaction consumes bandwidth, for its size, and energy, for smart contract calls.
+/// Resources the sender doesn't have, from staking or the daily free bandwidth, are paid for
+/// by burning TRX. All amounts are in sun, 1 TRX = 1,000,000 sun.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeEstimate {
+    /// The bandwidth of the signed transaction.
+    pub bandwidth: i64,
+    /// The energy of the smart contract call, zero for plain transfers.
+    pub energy: i64,
+    /// The bandwidth the sender has left, the larger of its staked and free bandwidth.
+    pub available_bandwidth: i64,
+    /// The energy the sender has left.
+    pub available_energy: i64,
+    /// The current price of a bandwidth unit.
+    pub bandwidth_price: i64,
+    /// The current price of an energy unit.
+    pub energy_price: i64,
+    /// Fees on top of resources, e.g. activating a new recipient account.
+    pub extra_fee: i64,
+}
+
+impl FeeEstimate {
+    /// The TRX burnt for bandwidth: a transaction is covered by either the staked or the free
+    /// bandwidth, otherwise all its bandwidth is paid for.
+    pub fn bandwidth_burn(&self) -> i64 {
+        if self.available_bandwidth >= self.bandwidth {
+            0
+        } else {
+            self.bandwidth * self.bandwidth_price
+        }
+    }
+
+    /// The TRX burnt for the energy the sender lacks.
+    pub fn energy_burn(&self) -> i64 {
+        (self.energy - self.available_energy).max(0) * self.energy_price
+    }
+
+    /// The TRX the transaction is expected to burn in total.
+    pub fn burn(&self) -> i64 {
+        self.bandwidth_burn() + self.energy_burn() + self.extra_fee
+    }
+}
+
+impl Display for FeeEstimate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Bandwidth: {} (available {}), burns {}",
+            self.bandwidth,
+            self.available_bandwidth,
+            Trx(self.bandwidth_burn())
+        )?;
+        if self.energy > 0 {
+            writeln!(
+                f,
+                "Energy:    {} (available {}), burns {}",
+                self.energy,
+                self.available_energy,
+                Trx(self.energy_burn())
+            )?;
+        }
+        if self.extra_fee > 0 {
+            writeln!(f, "Other:     {}", Trx(self.extra_fee))?;
+        }
+        write!(f, "Fee:       {}", Trx(self.burn()))
+    }
+}
+
+/// Formats an amount in sun as TRX.
+struct Trx(i64);
+
+impl Display for Trx {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let fraction = format!("{:06}", self.0 % 1_000_000);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{} TRX", self.0 / 1_000_000)
+        } else {
+            write!(f, "{}.{fraction} TRX", self.0 / 1_000_000)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_estimate_burn() {
+        let estimate = FeeEstimate {
+            bandwidth: 345,
+            energy: 64_285,
+            available_bandwidth: 600,
+            available_energy: 30_000,
+            bandwidth_price: 1_000,
+            energy_price: 100,
+            extra_fee: 0,
+        };
+        assert_eq!(estimate.bandwidth_burn(), 0);
+        assert_eq!(estimate.energy_burn(), 3_428_500);
+        assert_eq!(
+            estimate.to_string().lines().last(),
+            Some("Fee:       3.4285 TRX")
+        );
+
+        // Bandwidth is never partially covered.
+        let estimate = FeeEstimate {
+            available_bandwidth: 344,
+            available_energy: 100_000,
+            extra_fee: 1_000_000,
+            ..estimate
+        };
+        assert_eq!(estimate.bandwidth_burn(), 345_000);
+        assert_eq!(estimate.energy_burn(), 0);
+        assert_eq!(estimate.burn(), 1_345_000);
+    }
+}
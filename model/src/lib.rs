@@ -1,7 +1,25 @@
+mod asset;
 mod balance;
+mod fee;
 mod network;
+mod sent_transaction;
+mod signed_transaction;
+mod staking;
+mod transaction_details;
+mod transaction_status;
+mod transfer;
+mod voting;
 mod wallet;
 
-pub use balance::Balance;
+pub use asset::Asset;
+pub use balance::{Balance, TronAccount};
+pub use fee::FeeEstimate;
 pub use network::Network;
+pub use sent_transaction::SentTransaction;
+pub use signed_transaction::SignedTransaction;
+pub use staking::{Delegation, Resource, Staking, Unstaking};
+pub use transaction_details::TransactionDetails;
+pub use transaction_status::TransactionStatus;
+pub use transfer::{Direction, Transfer, TransferPage, TransferStatus};
+pub use voting::{Vote, Voting, Witness};
 pub use wallet::Wallet;
@@ -2,10 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::slice::Iter;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Network {
     Ethereum,
     Tron,
+    Bitcoin,
 }
 
 impl Display for Network {
@@ -13,12 +14,13 @@ impl Display for Network {
         match self {
             Network::Ethereum => write!(f, "Ethereum"),
             Network::Tron => write!(f, "Tron"),
+            Network::Bitcoin => write!(f, "Bitcoin"),
         }
     }
 }
 
 impl Network {
-    pub const ALL_VARIANTS: [Network; 2] = [Network::Ethereum, Network::Tron];
+    pub const ALL_VARIANTS: [Network; 3] = [Network::Ethereum, Network::Tron, Network::Bitcoin];
 
     pub fn iter() -> Iter<'static, Network> {
         Network::ALL_VARIANTS.iter()
@@ -0,0 +1,10 @@
+/// A broadcast transaction.
+///
+/// The audit log is written once the transaction is on its way: failing to record it
+/// doesn't undo the transaction, so the failure is reported next to the id instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentTransaction {
+    pub tx_id: String,
+    /// Why the transaction is missing from the audit log, if it is.
+    pub audit_error: Option<String>,
+}
@@ -0,0 +1,12 @@
+use crate::transaction_details::TransactionDetails;
+
+/// A transaction signed offline and written to a file.
+///
+/// The audit log is written after the signed file: failing to record the signature doesn't
+/// unwrite the file, so the failure is reported next to the details instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub details: TransactionDetails,
+    /// Why the signature is missing from the audit log, if it is.
+    pub audit_error: Option<String>,
+}
@@ -0,0 +1,62 @@
+use crate::network::Network;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// What TRX is staked for with Stake 2.0, each resource being staked separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Resource {
+    Bandwidth,
+    Energy,
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Resource::Bandwidth => "bandwidth",
+            Resource::Energy => "energy",
+        };
+        f.write_str(s)
+    }
+}
+
+/// TRX being unstaked: it stops giving resources at once, and can be withdrawn back to the
+/// balance once unlocked, 14 days later on mainnet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unstaking {
+    pub resource: Resource,
+    /// The decimal amount of TRX, e.g. `12.5`.
+    pub amount: String,
+    /// Seconds since the Unix epoch when the TRX can be withdrawn.
+    pub unlocks_at: u64,
+}
+
+/// The TRX an account stakes, amounts being decimal TRX.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Staking {
+    pub network: Network,
+    /// Staked for bandwidth.
+    pub bandwidth: String,
+    /// Staked for energy.
+    pub energy: String,
+    /// Staked for bandwidth and delegated to other addresses.
+    pub delegated_bandwidth: String,
+    /// Staked for energy and delegated to other addresses.
+    pub delegated_energy: String,
+    /// TRX being unstaked, the first to unlock first.
+    pub unstaking: Vec<Unstaking>,
+    /// Unstaked TRX which is unlocked and can be withdrawn.
+    pub withdrawable: String,
+}
+
+/// A resource staked by one address and delegated to another, which uses it as its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegation {
+    pub from: String,
+    pub to: String,
+    pub resource: Resource,
+    /// The decimal amount of staked TRX, e.g. `12.5`.
+    pub amount: String,
+    /// Seconds since the Unix epoch until which `from` can't take the resource back, `None`
+    /// when the delegation isn't locked.
+    pub locked_until: Option<u64>,
+}
@@ -0,0 +1,21 @@
+use crate::asset::Asset;
+use crate::network::Network;
+use serde::{Deserialize, Serialize};
+
+/// What a transaction does, as shown before it is signed or broadcast.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionDetails {
+    pub network: Network,
+    pub tx_id: String,
+    pub from: String,
+    pub to: String,
+    pub asset: Asset,
+    /// The decimal amount, e.g. `12.5`.
+    pub amount: String,
+    /// The most smart contract calls may burn in fees, in the native coin.
+    pub fee_limit: Option<String>,
+    /// Seconds since the Unix epoch after which the transaction can't be broadcast.
+    pub expires_at: u64,
+    /// How many signatures the transaction carries.
+    pub signatures: usize,
+}
@@ -0,0 +1,37 @@
+use std::fmt::{Display, Formatter};
+
+/// How far a broadcast transaction has made it into the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not in a block yet.
+    Pending,
+    /// In a block which can still be rolled back.
+    Confirmed { block: u64 },
+    /// In a block which can't be rolled back anymore.
+    Solidified { block: u64 },
+    /// In a block, but the transaction failed, e.g. out of energy. Its fee is still paid.
+    Reverted { block: u64, reason: String },
+}
+
+impl TransactionStatus {
+    /// Whether the status can't change anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Solidified { .. } | TransactionStatus::Reverted { .. }
+        )
+    }
+}
+
+impl Display for TransactionStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionStatus::Pending => write!(f, "Pending"),
+            TransactionStatus::Confirmed { block } => write!(f, "Confirmed in block {block}"),
+            TransactionStatus::Solidified { block } => write!(f, "Solidified in block {block}"),
+            TransactionStatus::Reverted { block, reason } => {
+                write!(f, "Reverted in block {block}: {reason}")
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::asset::Asset;
+use crate::network::Network;
+use serde::{Deserialize, Serialize};
+
+/// Whether a transfer credits or debits the wallet address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    In,
+    Out,
+}
+
+/// The outcome of the transaction of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferStatus {
+    Success,
+    /// The transaction was included in a block but failed, e.g. `OUT_OF_ENERGY`.
+    Failed(String),
+}
+
+/// A transfer in or out of a wallet address, as listed in its history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub network: Network,
+    pub tx_id: String,
+    pub direction: Direction,
+    pub asset: Asset,
+    /// The decimal amount, e.g. `12.5`.
+    pub amount: String,
+    /// The sender of incoming transfers, the recipient of outgoing ones.
+    pub counterparty: String,
+    /// The decimal fee paid by the wallet in the native coin, when known.
+    pub fee: Option<String>,
+    pub status: TransferStatus,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// A page of the transfer history of an address, newest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferPage {
+    pub transfers: Vec<Transfer>,
+    /// Whether older transfers follow.
+    pub has_more: bool,
+}
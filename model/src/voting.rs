@@ -0,0 +1,37 @@
+use crate::network::Network;
+use serde::{Deserialize, Serialize};
+
+/// A candidate for producing blocks, voted for with the tron power of staked TRX.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Witness {
+    pub address: String,
+    pub url: String,
+    pub votes: u64,
+    /// Whether it is one of the super representatives producing the blocks, otherwise a
+    /// partner which still shares its vote rewards.
+    pub active: bool,
+    /// The percentage of the rewards the witness keeps, voters share the rest.
+    pub brokerage: u8,
+}
+
+/// Votes cast for a witness.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vote {
+    pub witness: String,
+    pub count: u64,
+}
+
+/// The votes of an account and the rewards they earned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Voting {
+    pub network: Network,
+    /// The votes the account can cast, one per staked TRX.
+    pub tron_power: u64,
+    /// The votes cast, replaced as a whole by the next vote.
+    pub votes: Vec<Vote>,
+    /// The decimal amount of TRX the votes earned and which can be claimed, e.g. `12.5`.
+    pub reward: String,
+    /// Seconds since the Unix epoch from when the reward can be claimed again, `None` when it
+    /// can be claimed now: a claim is allowed once a day.
+    pub claimable_at: Option<u64>,
+}
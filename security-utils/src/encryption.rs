@@ -1,9 +1,10 @@
+use crate::{Keyfile, SecretBytes, SecretString};
 use aes_gcm::{
     Aes256Gcm,
     aead::{Aead, KeyInit, Payload},
 };
 use argon2::{
-    Argon2, Params, PasswordHasher,
+    Argon2, Params,
     password_hash::{self, SaltString, rand_core::OsRng},
 };
 use base64::{DecodeError, prelude::*};
@@ -28,6 +29,24 @@ impl EncryptedPayload {
         let payload: Self = serde_json::from_str(&file_content)?;
         Ok(payload)
     }
+
+    /// Creates a payload of random bytes with the same shape as `template`.
+    ///
+    /// It can't be told apart from a real payload, and can't be decrypted by anyone.
+    pub fn random_like(template: &EncryptedPayload) -> Result<Self> {
+        let ciphertext_len = BASE64_STANDARD.decode(&template.ciphertext_b64)?.len();
+        let mut rng = rng();
+        let mut ciphertext = vec![0u8; ciphertext_len];
+        rng.fill_bytes(&mut ciphertext);
+        let mut nonce_bytes = [0u8; NONCE_SIZE];
+        rng.fill_bytes(&mut nonce_bytes);
+
+        Ok(EncryptedPayload {
+            ciphertext_b64: BASE64_STANDARD.encode(&ciphertext),
+            salt_phc: SaltString::generate(&mut OsRng).to_string(),
+            nonce_b64: BASE64_STANDARD.encode(nonce_bytes),
+        })
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -53,6 +72,12 @@ pub enum EncryptionError {
     #[error("{0}")]
     SerdeError(#[from] serde_json::Error),
 
+    #[error("Keyfile not found: {0}")]
+    KeyfileNotFound(String),
+
+    #[error("Keyfile is empty: {0}")]
+    EmptyKeyfile(String),
+
     #[error("{0}")]
     Error(String),
 }
@@ -60,7 +85,17 @@ pub enum EncryptionError {
 pub type Result<T> = std::result::Result<T, EncryptionError>;
 
 /// Derives a 32-byte (256-bit) key from a master password and salt using Argon2id.
-fn derive_key(master_pass: &str, salt: &SaltString) -> Result<[u8; KEY_SIZE]> {
+///
+/// A keyfile, if any, is passed to Argon2 as its secret input, so the key depends on both
+/// factors. Without a keyfile the derivation is unchanged.
+///
+/// The key is written straight into a [`SecretBytes`] buffer, so no copy of it is left
+/// behind on the stack or inside an intermediate hash structure.
+fn derive_key(
+    master_pass: &SecretString,
+    keyfile: Option<&Keyfile>,
+    salt: &SaltString,
+) -> Result<SecretBytes> {
     // Define Argon2 parameters for key derivation (not just password hashing)
     // Argon2id is the recommended variant (hybrid of 'i' and 'd').
     let params = Params::new(
@@ -70,23 +105,27 @@ fn derive_key(master_pass: &str, salt: &SaltString) -> Result<[u8; KEY_SIZE]> {
         Some(KEY_SIZE), // Output key length
     )?;
 
-    let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
-
-    // Hash the combined password to get the derived key
-    // The hash output is the key material.
-    let hash_output = argon2.hash_password(master_pass.as_bytes(), salt)?;
-
-    // Extract the raw 32-byte key from the hash structure
-    let key_slice = hash_output
-        .hash
-        .as_ref()
-        .and_then(|h| h.as_bytes().get(..KEY_SIZE))
-        .ok_or_else(|| {
-            EncryptionError::Error("Failed to extract key of correct size".to_string())
-        })?;
-
-    let mut derived_key = [0u8; KEY_SIZE];
-    derived_key.copy_from_slice(key_slice);
+    let argon2 = match keyfile {
+        Some(keyfile) => Argon2::new_with_secret(
+            keyfile.secret(),
+            argon2::Algorithm::Argon2id,
+            argon2::Version::V0x13,
+            params,
+        )?,
+        None => Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params),
+    };
+
+    // The salt is stored in its B64 (PHC) form, Argon2 works on the decoded bytes.
+    let mut salt_buf = [0u8; 64];
+    let salt_bytes = salt.as_salt().decode_b64(&mut salt_buf)?;
+
+    // Hash the password to get the derived key material.
+    let mut derived_key = SecretBytes::zeroed(KEY_SIZE);
+    argon2.hash_password_into(
+        master_pass.expose_secret().as_bytes(),
+        salt_bytes,
+        derived_key.expose_secret_mut(),
+    )?;
 
     Ok(derived_key)
 }
@@ -94,13 +133,35 @@ fn derive_key(master_pass: &str, salt: &SaltString) -> Result<[u8; KEY_SIZE]> {
 /// Encrypts a string using AES-256-GCM, deriving the key from a master password.
 ///
 /// Returns an `EncryptedPayload` struct containing the encrypted data and metadata.
-pub fn encrypt(plaintext: &str, master_pass: &str) -> Result<EncryptedPayload> {
+pub fn encrypt(plaintext: &str, master_pass: &SecretString) -> Result<EncryptedPayload> {
+    encrypt_with_keyfile(plaintext, master_pass, None)
+}
+
+/// Same as [`encrypt`], additionally mixing a keyfile into the key derivation.
+pub fn encrypt_with_keyfile(
+    plaintext: &str,
+    master_pass: &SecretString,
+    keyfile: Option<&Keyfile>,
+) -> Result<EncryptedPayload> {
+    encrypt_with_aad(plaintext, master_pass, keyfile, &[])
+}
+
+/// Same as [`encrypt_with_keyfile`], additionally authenticating `aad`.
+///
+/// The associated data is not encrypted nor stored in the payload, but decryption fails
+/// unless exactly the same data is passed to [`decrypt_with_aad`].
+pub fn encrypt_with_aad(
+    plaintext: &str,
+    master_pass: &SecretString,
+    keyfile: Option<&Keyfile>,
+    aad: &[u8],
+) -> Result<EncryptedPayload> {
     // 1. Generate a secure, unique KDF Salt for Argon2
     let salt = SaltString::generate(&mut OsRng);
 
-    // 2. Derive the 32-byte AES key from the master password and salt
-    let key_bytes = derive_key(master_pass, &salt)?;
-    let key = key_bytes.as_slice().into();
+    // 2. Derive the 32-byte AES key from the master password, keyfile and salt
+    let key_bytes = derive_key(master_pass, keyfile, &salt)?;
+    let key = key_bytes.expose_secret().into();
 
     // 3. Generate a secure 12-byte Nonce for AES-GCM
     let mut rng = rng();
@@ -111,7 +172,13 @@ pub fn encrypt(plaintext: &str, master_pass: &str) -> Result<EncryptedPayload> {
     // 4. Encryption (AES-256-GCM)
     let cipher = Aes256Gcm::new(key);
 
-    match cipher.encrypt(nonce, plaintext.as_bytes()) {
+    match cipher.encrypt(
+        nonce,
+        Payload {
+            msg: plaintext.as_bytes(),
+            aad,
+        },
+    ) {
         Ok(ciphertext_with_tag) => {
             // 5. Base64 Encoding and Output
             let ciphertext_b64 = BASE64_STANDARD.encode(&ciphertext_with_tag);
@@ -129,36 +196,143 @@ pub fn encrypt(plaintext: &str, master_pass: &str) -> Result<EncryptedPayload> {
 }
 
 /// Decrypts an `EncryptedPayload` using AES-256-GCM, deriving the key from a master password.
-pub fn decrypt(payload: &EncryptedPayload, master_pass: &str) -> Result<String> {
-    // 1. Decode Base64 inputs
-    let ciphertext_with_tag = BASE64_STANDARD.decode(&payload.ciphertext_b64)?;
-    let nonce_bytes = BASE64_STANDARD.decode(&payload.nonce_b64)?;
+///
+/// The plaintext is returned as a [`SecretString`], so it is wiped once the caller is done.
+pub fn decrypt(payload: &EncryptedPayload, master_pass: &SecretString) -> Result<SecretString> {
+    decrypt_with_keyfile(payload, master_pass, None)
+}
 
-    // 2. Parse the KDF salt string back into a SaltString
+/// Same as [`decrypt`], for payloads encrypted with [`encrypt_with_keyfile`].
+pub fn decrypt_with_keyfile(
+    payload: &EncryptedPayload,
+    master_pass: &SecretString,
+    keyfile: Option<&Keyfile>,
+) -> Result<SecretString> {
+    decrypt_with_aad(payload, master_pass, keyfile, &[])
+}
+
+/// Same as [`decrypt_with_keyfile`], for payloads encrypted with [`encrypt_with_aad`].
+pub fn decrypt_with_aad(
+    payload: &EncryptedPayload,
+    master_pass: &SecretString,
+    keyfile: Option<&Keyfile>,
+    aad: &[u8],
+) -> Result<SecretString> {
+    let key_bytes = derive_payload_key(payload, master_pass, keyfile)?;
+    decrypt_payload(payload, &key_bytes, aad)
+}
+
+/// Derives the key a payload was encrypted with, from the master password and keyfile.
+///
+/// Deriving is the expensive part of [`decrypt_with_keyfile`]: the key can be kept (in a
+/// [`SecretBytes`]) and handed to [`decrypt_with_derived_key`] to decrypt the payload again.
+pub fn derive_payload_key(
+    payload: &EncryptedPayload,
+    master_pass: &SecretString,
+    keyfile: Option<&Keyfile>,
+) -> Result<SecretBytes> {
     let salt = SaltString::from_b64(&payload.salt_phc)?;
+    derive_key(master_pass, keyfile, &salt)
+}
 
-    // 3. Derive the 32-byte AES key from the master password and salt
-    let key_bytes = derive_key(master_pass, &salt)?;
-    let key = key_bytes.as_slice().into();
+/// Decrypts a payload encrypted with [`encrypt_with_keyfile`], given its derived key.
+pub fn decrypt_with_derived_key(
+    payload: &EncryptedPayload,
+    key: &SecretBytes,
+) -> Result<SecretString> {
+    decrypt_payload(payload, key, &[])
+}
 
-    // 4. Create Nonce
+fn decrypt_payload(
+    payload: &EncryptedPayload,
+    key_bytes: &SecretBytes,
+    aad: &[u8],
+) -> Result<SecretString> {
+    // 1. Decode Base64 inputs
+    let ciphertext_with_tag = BASE64_STANDARD.decode(&payload.ciphertext_b64)?;
+    let nonce_bytes = BASE64_STANDARD.decode(&payload.nonce_b64)?;
+
+    // 2. Create Nonce
     let nonce = nonce_bytes.as_slice().into();
 
-    // 5. Decryption (AES-256-GCM)
-    let cipher = Aes256Gcm::new(key);
+    // 3. Decryption (AES-256-GCM)
+    let cipher = raw_key_cipher(key_bytes)?;
 
     match cipher.decrypt(
         nonce,
         Payload {
             msg: &ciphertext_with_tag,
-            aad: &[],
+            aad,
+        },
+    ) {
+        Ok(plaintext_bytes) => Ok(String::from_utf8(plaintext_bytes)?.into()),
+        Err(e) => Err(EncryptionError::Error(format!("Decryption error: {:?}", e))),
+    }
+}
+
+/// Encrypts `plaintext` with AES-256-GCM under a ready-made 32-byte key, authenticating `aad`.
+///
+/// Unlike [`encrypt`], there is no key derivation: this is meant for many small records
+/// under a key that was itself derived from a secret. Returns the Base64 of the nonce
+/// followed by the ciphertext and tag.
+pub fn encrypt_with_key(plaintext: &[u8], key: &SecretBytes, aad: &[u8]) -> Result<String> {
+    let cipher = raw_key_cipher(key)?;
+
+    let mut nonce_bytes = [0u8; NONCE_SIZE];
+    rng().fill_bytes(&mut nonce_bytes);
+    let nonce = nonce_bytes.as_slice().into();
+
+    match cipher.encrypt(
+        nonce,
+        Payload {
+            msg: plaintext,
+            aad,
+        },
+    ) {
+        Ok(ciphertext_with_tag) => {
+            let mut sealed = Vec::with_capacity(NONCE_SIZE + ciphertext_with_tag.len());
+            sealed.extend_from_slice(&nonce_bytes);
+            sealed.extend_from_slice(&ciphertext_with_tag);
+            Ok(BASE64_STANDARD.encode(sealed))
+        }
+        Err(e) => Err(EncryptionError::Error(format!("Encryption error: {:?}", e))),
+    }
+}
+
+/// Decrypts the output of [`encrypt_with_key`].
+pub fn decrypt_with_key(sealed: &str, key: &SecretBytes, aad: &[u8]) -> Result<SecretString> {
+    let cipher = raw_key_cipher(key)?;
+
+    let sealed = BASE64_STANDARD.decode(sealed)?;
+    if sealed.len() < NONCE_SIZE {
+        return Err(EncryptionError::Error(
+            "Sealed data is too short".to_string(),
+        ));
+    }
+    let (nonce_bytes, ciphertext_with_tag) = sealed.split_at(NONCE_SIZE);
+
+    match cipher.decrypt(
+        nonce_bytes.into(),
+        Payload {
+            msg: ciphertext_with_tag,
+            aad,
         },
     ) {
-        Ok(plaintext_bytes) => Ok(String::from_utf8(plaintext_bytes)?),
+        Ok(plaintext_bytes) => Ok(String::from_utf8(plaintext_bytes)?.into()),
         Err(e) => Err(EncryptionError::Error(format!("Decryption error: {:?}", e))),
     }
 }
 
+fn raw_key_cipher(key: &SecretBytes) -> Result<Aes256Gcm> {
+    if key.len() != KEY_SIZE {
+        return Err(EncryptionError::Error(format!(
+            "Invalid key size: {} bytes",
+            key.len()
+        )));
+    }
+    Ok(Aes256Gcm::new(key.expose_secret().into()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,26 +340,26 @@ mod tests {
     #[test]
     fn test_encrypt_decrypt_roundtrip() {
         let original_text = "The treasure is buried under the old oak tree.";
-        let master_pass = "MySecretPassphrase";
+        let master_pass = SecretString::from("MySecretPassphrase");
 
         // Encrypt
-        let payload = encrypt(original_text, master_pass).expect("Encryption failed");
+        let payload = encrypt(original_text, &master_pass).expect("Encryption failed");
 
         // Decrypt
-        let decrypted_text = decrypt(&payload, master_pass).expect("Decryption failed");
+        let decrypted_text = decrypt(&payload, &master_pass).expect("Decryption failed");
 
-        assert_eq!(original_text, decrypted_text);
+        assert_eq!(original_text, decrypted_text.expose_secret());
     }
 
     #[test]
     fn test_encrypt_different_passwords_fail() {
         let original_text = "This is a secret message.";
-        let master_pass = "CorrectPassword";
-        let wrong_pass = "IncorrectPassword";
+        let master_pass = SecretString::from("CorrectPassword");
+        let wrong_pass = SecretString::from("IncorrectPassword");
 
-        let payload = encrypt(original_text, master_pass).expect("Encryption failed");
+        let payload = encrypt(original_text, &master_pass).expect("Encryption failed");
 
-        let result = decrypt(&payload, wrong_pass);
+        let result = decrypt(&payload, &wrong_pass);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().to_string(),
@@ -193,12 +367,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encrypt_different_aad_fail() {
+        let master_pass = SecretString::from("MyPassword");
+
+        let payload = encrypt_with_aad("secret", &master_pass, None, b"header").unwrap();
+
+        assert!(decrypt_with_aad(&payload, &master_pass, None, b"tampered").is_err());
+        assert!(decrypt(&payload, &master_pass).is_err());
+        let decrypted = decrypt_with_aad(&payload, &master_pass, None, b"header").unwrap();
+        assert_eq!(decrypted.expose_secret(), "secret");
+    }
+
+    #[test]
+    fn test_random_payload_looks_real() {
+        let master_pass = SecretString::from("MyPassword");
+        let payload = encrypt("a secret of some length", &master_pass).unwrap();
+
+        let random = EncryptedPayload::random_like(&payload).unwrap();
+
+        assert_eq!(random.ciphertext_b64.len(), payload.ciphertext_b64.len());
+        assert_eq!(random.salt_phc.len(), payload.salt_phc.len());
+        assert_eq!(random.nonce_b64.len(), payload.nonce_b64.len());
+        assert!(decrypt(&random, &master_pass).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_with_key_roundtrip() {
+        let key = SecretBytes::from(vec![7u8; KEY_SIZE]);
+        let wrong_key = SecretBytes::from(vec![8u8; KEY_SIZE]);
+
+        let sealed = encrypt_with_key(b"record", &key, b"aad").unwrap();
+
+        assert!(decrypt_with_key(&sealed, &wrong_key, b"aad").is_err());
+        assert!(decrypt_with_key(&sealed, &key, b"other").is_err());
+        let decrypted = decrypt_with_key(&sealed, &key, b"aad").unwrap();
+        assert_eq!(decrypted.expose_secret(), "record");
+        assert!(encrypt_with_key(b"record", &SecretBytes::zeroed(16), b"").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_with_derived_key() {
+        let master_pass = SecretString::from("MyPassword");
+        let keyfile = Keyfile::from_bytes(b"usb stick");
+        let payload = encrypt_with_keyfile("secret", &master_pass, Some(&keyfile)).unwrap();
+
+        let key = derive_payload_key(&payload, &master_pass, Some(&keyfile)).unwrap();
+        let decrypted = decrypt_with_derived_key(&payload, &key).unwrap();
+        assert_eq!(decrypted.expose_secret(), "secret");
+
+        let wrong_key = derive_payload_key(&payload, &master_pass, None).unwrap();
+        assert!(decrypt_with_derived_key(&payload, &wrong_key).is_err());
+    }
+
     #[test]
     fn test_encrypt_different_nonce_fail() {
         let original_text = "This is another secret message.";
-        let master_pass = "MyPassword";
+        let master_pass = SecretString::from("MyPassword");
 
-        let mut payload = encrypt(original_text, master_pass).expect("Encryption failed");
+        let mut payload = encrypt(original_text, &master_pass).expect("Encryption failed");
 
         // Generate a different nonce
         let mut rng = rng();
@@ -206,7 +433,7 @@ mod tests {
         rng.fill_bytes(&mut different_nonce_bytes);
         payload.nonce_b64 = BASE64_STANDARD.encode(different_nonce_bytes);
 
-        let result = decrypt(&payload, master_pass);
+        let result = decrypt(&payload, &master_pass);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err().to_string(), "Decryption error: Error");
     }
@@ -214,15 +441,15 @@ mod tests {
     #[test]
     fn test_encrypt_different_salt_fail() {
         let original_text = "Yet another secret.";
-        let master_pass = "SuperSecret";
+        let master_pass = SecretString::from("SuperSecret");
 
-        let mut payload = encrypt(original_text, master_pass).expect("Encryption failed");
+        let mut payload = encrypt(original_text, &master_pass).expect("Encryption failed");
 
         // Generate a different salt
         let different_salt = SaltString::generate(&mut OsRng);
         payload.salt_phc = different_salt.to_string();
 
-        let result = decrypt(&payload, master_pass);
+        let result = decrypt(&payload, &master_pass);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().to_string(),
@@ -0,0 +1,107 @@
+use crate::{EncryptionError, Result, SecretBytes};
+use rand::{RngCore, rng};
+use sha2::{Digest, Sha256};
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+/// The size of a generated keyfile, in bytes.
+pub const KEYFILE_SIZE: usize = 64;
+
+const FINGERPRINT_DOMAIN: &[u8] = b"cwu-keyfile-fingerprint";
+
+/// A keyfile: a second factor mixed into the key derivation next to the master password.
+///
+/// Any file can serve as a keyfile, only the SHA-256 digest of its content is kept in memory.
+pub struct Keyfile {
+    digest: SecretBytes,
+}
+
+impl Keyfile {
+    /// Writes a new random keyfile to `path`. Existing files are never overwritten.
+    pub fn generate(path: impl AsRef<Path>) -> Result<Self> {
+        let mut content = SecretBytes::zeroed(KEYFILE_SIZE);
+        rng().fill_bytes(content.expose_secret_mut());
+
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        file.write_all(content.expose_secret())?;
+        file.sync_all()?;
+
+        Ok(Self::from_bytes(content.expose_secret()))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(EncryptionError::KeyfileNotFound(path.display().to_string()));
+        }
+        let content = SecretBytes::from(std::fs::read(path)?);
+        if content.is_empty() {
+            return Err(EncryptionError::EmptyKeyfile(path.display().to_string()));
+        }
+        Ok(Self::from_bytes(content.expose_secret()))
+    }
+
+    pub fn from_bytes(content: &[u8]) -> Self {
+        Self {
+            digest: SecretBytes::from(Sha256::digest(content).to_vec()),
+        }
+    }
+
+    /// A short public identifier, stored next to the wallet to tell a wrong keyfile
+    /// apart from a wrong password. It is derived from the digest with a separate
+    /// domain, so it does not reveal the secret used in key derivation.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(FINGERPRINT_DOMAIN);
+        hasher.update(self.digest.expose_secret());
+        hex::encode(&hasher.finalize()[..8])
+    }
+
+    pub(crate) fn secret(&self) -> &[u8] {
+        self.digest.expose_secret()
+    }
+}
+
+impl std::fmt::Debug for Keyfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Keyfile({})", self.fingerprint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SecretString, decrypt_with_keyfile, encrypt_with_keyfile};
+
+    #[test]
+    fn test_generate_keyfile() {
+        let path = std::env::temp_dir().join(format!("cwu-{}.key", rng().next_u64()));
+        let keyfile = Keyfile::generate(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), KEYFILE_SIZE as u64);
+
+        let loaded = Keyfile::from_file(&path).unwrap();
+        assert_eq!(keyfile.fingerprint(), loaded.fingerprint());
+        // Never overwrite an existing keyfile.
+        assert!(Keyfile::generate(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            Keyfile::from_file(&path),
+            Err(EncryptionError::KeyfileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_keyfile_is_required_to_decrypt() {
+        let master_pass = SecretString::from("MySecretPassphrase");
+        let keyfile = Keyfile::from_bytes(b"usb stick");
+        let wrong_keyfile = Keyfile::from_bytes(b"another usb stick");
+
+        let payload = encrypt_with_keyfile("secret", &master_pass, Some(&keyfile)).unwrap();
+
+        assert!(decrypt_with_keyfile(&payload, &master_pass, None).is_err());
+        assert!(decrypt_with_keyfile(&payload, &master_pass, Some(&wrong_keyfile)).is_err());
+        let decrypted = decrypt_with_keyfile(&payload, &master_pass, Some(&keyfile)).unwrap();
+        assert_eq!(decrypted.expose_secret(), "secret");
+        assert_ne!(keyfile.fingerprint(), wrong_keyfile.fingerprint());
+    }
+}
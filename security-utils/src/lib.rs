@@ -1,5 +1,19 @@
 mod encryption;
+mod keyfile;
+mod passphrase;
 mod password;
+mod secret;
+mod strength;
 
-pub use encryption::{EncryptedPayload, EncryptionError, Result, decrypt, encrypt};
+pub use encryption::{
+    EncryptedPayload, EncryptionError, Result, decrypt, decrypt_with_aad, decrypt_with_derived_key,
+    decrypt_with_key, decrypt_with_keyfile, derive_payload_key, encrypt, encrypt_with_aad,
+    encrypt_with_key, encrypt_with_keyfile,
+};
+pub use keyfile::{KEYFILE_SIZE, Keyfile};
+pub use passphrase::{MIN_DICEWARE_WORDS, Wordlist, generate_diceware_passphrase};
 pub use password::{PasswordError, generate_secure_password};
+pub use secret::{SecretBytes, SecretString};
+pub use strength::{
+    MIN_MASTER_PASSWORD_BITS, PasswordEstimate, PasswordStrength, estimate_password_strength,
+};
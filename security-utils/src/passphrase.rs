@@ -0,0 +1,121 @@
+use crate::{PasswordError, SecretString};
+use bip39::Language;
+use rand::prelude::IndexedRandom;
+use rand::rng;
+use std::sync::LazyLock;
+
+/// The minimum number of words in a generated passphrase.
+pub const MIN_DICEWARE_WORDS: usize = 4;
+
+/// The EFF large wordlist (7776 words, five dice rolls per word).
+///
+/// Source: <https://www.eff.org/dice>, licensed under CC BY 3.0 US.
+const EFF_LARGE_WORDLIST: &str = include_str!("wordlists/eff_large_wordlist.txt");
+
+static EFF_LARGE_WORDS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
+    EFF_LARGE_WORDLIST
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .collect()
+});
+
+/// The wordlists a diceware passphrase can be drawn from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Wordlist {
+    /// The EFF large wordlist: 7776 short, distinct English words (~12.9 bits per word).
+    #[default]
+    EffLarge,
+    /// The English BIP39 wordlist: 2048 words (11 bits per word).
+    Bip39English,
+}
+
+impl Wordlist {
+    pub fn words(&self) -> &'static [&'static str] {
+        match self {
+            Wordlist::EffLarge => EFF_LARGE_WORDS.as_slice(),
+            Wordlist::Bip39English => Language::English.word_list(),
+        }
+    }
+
+    /// The entropy contributed by a single, uniformly chosen word.
+    pub fn bits_per_word(&self) -> f64 {
+        (self.words().len() as f64).log2()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        match self {
+            Wordlist::EffLarge => EFF_LARGE_WORDS.contains(&word),
+            Wordlist::Bip39English => Language::English.find_word(word).is_some(),
+        }
+    }
+}
+
+/// Generates a diceware-style passphrase, e.g. `cradle-unusable-anybody-jazz-mousiness-sprint`.
+///
+/// Words are chosen uniformly and independently with a CSPRNG, so the entropy is exactly
+/// `word_count * wordlist.bits_per_word()`, regardless of the separator.
+pub fn generate_diceware_passphrase(
+    word_count: usize,
+    separator: &str,
+    wordlist: Wordlist,
+) -> Result<SecretString, PasswordError> {
+    if word_count < MIN_DICEWARE_WORDS {
+        return Err(PasswordError::TooFewWords);
+    }
+
+    let mut rng = rng();
+    let words = wordlist.words();
+    let mut passphrase = String::with_capacity(word_count * 10 + word_count * separator.len());
+    for i in 0..word_count {
+        if i > 0 {
+            passphrase.push_str(separator);
+        }
+        // Unwrap is safe as wordlists are not empty.
+        passphrase.push_str(words.choose(&mut rng).unwrap());
+    }
+
+    Ok(passphrase.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eff_wordlist() {
+        let words = Wordlist::EffLarge.words();
+        assert_eq!(words.len(), 7776);
+        assert_eq!(words[0], "abacus");
+        assert_eq!(words[7775], "zoom");
+        assert!(Wordlist::EffLarge.contains("jazz"));
+        assert!(!Wordlist::EffLarge.contains("11111"));
+    }
+
+    #[test]
+    fn test_bip39_wordlist() {
+        assert_eq!(Wordlist::Bip39English.words().len(), 2048);
+        assert_eq!(Wordlist::Bip39English.bits_per_word(), 11.0);
+        assert!(Wordlist::Bip39English.contains("abandon"));
+    }
+
+    #[test]
+    fn test_diceware_passphrase() {
+        for wordlist in [Wordlist::EffLarge, Wordlist::Bip39English] {
+            let passphrase = generate_diceware_passphrase(6, "-", wordlist).unwrap();
+            let words: Vec<&str> = passphrase.expose_secret().split('-').collect();
+            assert_eq!(words.len(), 6);
+            assert!(words.iter().all(|w| wordlist.contains(w)));
+        }
+    }
+
+    #[test]
+    fn test_diceware_custom_separator() {
+        let passphrase = generate_diceware_passphrase(5, " ", Wordlist::Bip39English).unwrap();
+        assert_eq!(passphrase.expose_secret().split(' ').count(), 5);
+    }
+
+    #[test]
+    fn test_diceware_errors() {
+        assert!(generate_diceware_passphrase(3, "-", Wordlist::EffLarge).is_err());
+    }
+}
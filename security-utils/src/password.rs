@@ -1,3 +1,4 @@
+use crate::SecretString;
 use rand::prelude::IndexedRandom;
 use rand::{rng, seq::SliceRandom};
 
@@ -17,13 +18,19 @@ const CHARS_SYMBOLS: [u8; 28] = *b"!@#$%^&*()-_+=[]{}|;:,.<>?/~";
 pub enum PasswordError {
     #[error("The requested length must be greater or equal to 4.")]
     TooShort,
+
+    #[error("The requested word count must be greater or equal to 4.")]
+    TooFewWords,
+
+    #[error("The password is too weak ({0:.0} bits of entropy), choose a longer one.")]
+    TooWeak(f64),
 }
 
 /// Generates a cryptographically secure password.
 ///
 /// This function uses a 'strict' approach: it ensures that at least one character
 /// from each enabled set is included, guaranteeing complexity compliance.
-pub fn generate_secure_password(length: usize) -> Result<String, PasswordError> {
+pub fn generate_secure_password(length: usize) -> Result<SecretString, PasswordError> {
     if length < 4 {
         return Err(PasswordError::TooShort);
     }
@@ -49,7 +56,8 @@ pub fn generate_secure_password(length: usize) -> Result<String, PasswordError>
 
     // The `from_utf8` call is safe because all source characters are valid ASCII.
     Ok(String::from_utf8(password_bytes)
-        .expect("Password generation should result in valid UTF-8 ASCII"))
+        .expect("Password generation should result in valid UTF-8 ASCII")
+        .into())
 }
 
 #[cfg(test)]
@@ -64,7 +72,7 @@ mod tests {
     #[test]
     fn test_password_length() {
         let password = generate_secure_password(16).unwrap();
-        assert_eq!(password.len(), 16);
+        assert_eq!(password.expose_secret().len(), 16);
     }
 
     #[test]
@@ -72,28 +80,29 @@ mod tests {
         // Run multiple times to reduce the chance of false positive
         for _ in 0..100 {
             let password = generate_secure_password(20).unwrap();
+            let password = password.expose_secret();
 
             // Should contain a lowercase character
             assert!(
-                contains_any(&password, CHARS_LOWERCASE.as_slice()),
+                contains_any(password, CHARS_LOWERCASE.as_slice()),
                 "Missing lowercase"
             );
 
             // Must contain numbers
             assert!(
-                contains_any(&password, CHARS_NUMBERS.as_slice()),
+                contains_any(password, CHARS_NUMBERS.as_slice()),
                 "Missing numbers"
             );
 
             // Must contain symbols
             assert!(
-                contains_any(&password, CHARS_SYMBOLS.as_slice()),
+                contains_any(password, CHARS_SYMBOLS.as_slice()),
                 "Missing symbols"
             );
 
             // Must contain uppercase
             assert!(
-                contains_any(&password, CHARS_UPPERCASE.as_slice()),
+                contains_any(password, CHARS_UPPERCASE.as_slice()),
                 "Missing uppercase"
             );
         }
@@ -0,0 +1,190 @@
+use std::fmt::{Debug, Formatter};
+use zeroize::Zeroize;
+
+/// A string holding sensitive data (passwords, mnemonics, serialized keys).
+///
+/// The content is wiped from memory on drop, never printed by `Debug`, and,
+/// when the `mlock` feature is enabled on Linux, kept out of swap.
+pub struct SecretString {
+    inner: String,
+}
+
+impl SecretString {
+    pub fn new(inner: String) -> Self {
+        memlock::lock(inner.as_ptr(), inner.capacity());
+        Self { inner }
+    }
+
+    /// Gives access to the secret value. Keep the borrow as short as possible.
+    pub fn expose_secret(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+impl Clone for SecretString {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretString([REDACTED])")
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        let (ptr, capacity) = (self.inner.as_ptr(), self.inner.capacity());
+        self.inner.zeroize();
+        memlock::unlock(ptr, capacity);
+    }
+}
+
+/// A byte buffer holding sensitive data (derived keys, seeds, raw private keys).
+///
+/// Same guarantees as [`SecretString`].
+pub struct SecretBytes {
+    inner: Vec<u8>,
+}
+
+impl SecretBytes {
+    pub fn new(inner: Vec<u8>) -> Self {
+        memlock::lock(inner.as_ptr(), inner.capacity());
+        Self { inner }
+    }
+
+    /// Allocates a zero-filled buffer of the given length.
+    pub fn zeroed(len: usize) -> Self {
+        Self::new(vec![0u8; len])
+    }
+
+    /// Gives access to the secret value. Keep the borrow as short as possible.
+    pub fn expose_secret(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+
+    pub(crate) fn expose_secret_mut(&mut self) -> &mut [u8] {
+        self.inner.as_mut_slice()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(value: Vec<u8>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&[u8]> for SecretBytes {
+    fn from(value: &[u8]) -> Self {
+        Self::new(value.to_vec())
+    }
+}
+
+impl Clone for SecretBytes {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl Debug for SecretBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretBytes([REDACTED])")
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        let (ptr, capacity) = (self.inner.as_ptr(), self.inner.capacity());
+        self.inner.zeroize();
+        memlock::unlock(ptr, capacity);
+    }
+}
+
+/// Best-effort page locking, so secrets are not written to swap.
+///
+/// Failures (e.g. `RLIMIT_MEMLOCK` exhausted) are ignored: locking is a hardening
+/// measure, not something the wallet can depend on.
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+mod memlock {
+    pub(super) fn lock(ptr: *const u8, len: usize) {
+        if len > 0 {
+            // SAFETY: `ptr` points to a live allocation of at least `len` bytes.
+            unsafe {
+                libc::mlock(ptr.cast(), len);
+            }
+        }
+    }
+
+    pub(super) fn unlock(ptr: *const u8, len: usize) {
+        if len > 0 {
+            // SAFETY: `ptr` points to a live allocation of at least `len` bytes.
+            unsafe {
+                libc::munlock(ptr.cast(), len);
+            }
+        }
+    }
+}
+
+#[cfg(not(all(feature = "mlock", target_os = "linux")))]
+mod memlock {
+    pub(super) fn lock(_ptr: *const u8, _len: usize) {}
+
+    pub(super) fn unlock(_ptr: *const u8, _len: usize) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_string_debug_is_redacted() {
+        let secret = SecretString::from("MySecretPassphrase");
+        assert_eq!(format!("{:?}", secret), "SecretString([REDACTED])");
+        assert_eq!(secret.expose_secret(), "MySecretPassphrase");
+    }
+
+    #[test]
+    fn test_secret_bytes_debug_is_redacted() {
+        let secret = SecretBytes::from(vec![1u8, 2, 3]);
+        assert_eq!(format!("{:?}", secret), "SecretBytes([REDACTED])");
+        assert_eq!(secret.expose_secret(), &[1u8, 2, 3]);
+    }
+
+    #[test]
+    fn test_secret_clone() {
+        let secret = SecretString::from("secret");
+        let cloned = secret.clone();
+        drop(secret);
+        assert_eq!(cloned.expose_secret(), "secret");
+        assert_eq!(cloned.len(), 6);
+    }
+}
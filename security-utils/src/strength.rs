@@ -0,0 +1,254 @@
+use crate::passphrase::Wordlist;
+use std::fmt::{Display, Formatter};
+
+/// The minimum entropy accepted for a user-chosen master password.
+pub const MIN_MASTER_PASSWORD_BITS: f64 = 60.0;
+
+/// Passwords that show up at the top of every breach corpus.
+const COMMON_PASSWORDS: [&str; 24] = [
+    "123456",
+    "123456789",
+    "12345678",
+    "1234567890",
+    "password",
+    "qwerty",
+    "qwertyuiop",
+    "abc123",
+    "111111",
+    "123123",
+    "iloveyou",
+    "admin",
+    "welcome",
+    "monkey",
+    "dragon",
+    "letmein",
+    "football",
+    "sunshine",
+    "master",
+    "princess",
+    "passw0rd",
+    "trustno1",
+    "baseball",
+    "superman",
+];
+
+/// Keyboard rows, used to detect walks like `asdf` or `7890`.
+const KEYBOARD_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PasswordStrength {
+    VeryWeak,
+    Weak,
+    Fair,
+    Strong,
+    VeryStrong,
+}
+
+impl Display for PasswordStrength {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            PasswordStrength::VeryWeak => "Very weak",
+            PasswordStrength::Weak => "Weak",
+            PasswordStrength::Fair => "Fair",
+            PasswordStrength::Strong => "Strong",
+            PasswordStrength::VeryStrong => "Very strong",
+        };
+        f.write_str(s)
+    }
+}
+
+impl PasswordStrength {
+    fn from_bits(bits: f64) -> Self {
+        match bits {
+            b if b < 28.0 => PasswordStrength::VeryWeak,
+            b if b < 36.0 => PasswordStrength::Weak,
+            b if b < MIN_MASTER_PASSWORD_BITS => PasswordStrength::Fair,
+            b if b < 80.0 => PasswordStrength::Strong,
+            _ => PasswordStrength::VeryStrong,
+        }
+    }
+}
+
+/// The result of [`estimate_password_strength`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordEstimate {
+    entropy_bits: f64,
+    strength: PasswordStrength,
+}
+
+impl PasswordEstimate {
+    pub fn entropy_bits(&self) -> f64 {
+        self.entropy_bits
+    }
+
+    pub fn strength(&self) -> PasswordStrength {
+        self.strength
+    }
+
+    /// Whether the password may be used as a master password.
+    pub fn is_strong_enough(&self) -> bool {
+        self.entropy_bits >= MIN_MASTER_PASSWORD_BITS
+    }
+}
+
+/// Estimates how many guesses an attacker needs for a password, in bits.
+///
+/// This is a conservative heuristic, not a guarantee:
+/// - a phrase made only of wordlist words is rated as a diceware passphrase
+///   (`words * bits_per_word`), since that is how an attacker would enumerate it;
+/// - otherwise every character is worth `log2(pool)` bits, where the pool is the union of
+///   the character classes used, except characters continuing a repetition, a sequence
+///   (`abc`, `321`) or a keyboard walk (`qwer`), which are worth 1 bit;
+/// - well-known passwords and single dictionary words are capped accordingly.
+pub fn estimate_password_strength(password: &str) -> PasswordEstimate {
+    let entropy_bits = passphrase_entropy(password)
+        .unwrap_or_else(|| character_entropy(password).min(dictionary_entropy(password)));
+    PasswordEstimate {
+        entropy_bits,
+        strength: PasswordStrength::from_bits(entropy_bits),
+    }
+}
+
+/// Rates a password that is entirely made of wordlist words, e.g. `word-word-word`.
+fn passphrase_entropy(password: &str) -> Option<f64> {
+    let lowercase = password.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.len() < 2 {
+        return None;
+    }
+
+    [Wordlist::Bip39English, Wordlist::EffLarge]
+        .into_iter()
+        .find(|wordlist| words.iter().all(|w| wordlist.contains(w)))
+        .map(|wordlist| words.len() as f64 * wordlist.bits_per_word())
+}
+
+/// Caps the entropy of a well-known password or a dictionary word decorated with
+/// digits and symbols (e.g. `Dragon2024!`).
+fn dictionary_entropy(password: &str) -> f64 {
+    let lowercase = password.to_lowercase();
+    if COMMON_PASSWORDS.contains(&lowercase.as_str()) {
+        return 0.0;
+    }
+
+    let core = lowercase.trim_matches(|c: char| !c.is_ascii_alphabetic());
+    let is_word = COMMON_PASSWORDS.contains(&core)
+        || Wordlist::EffLarge.contains(core)
+        || Wordlist::Bip39English.contains(core);
+    if core.is_empty() || !is_word {
+        return f64::MAX;
+    }
+
+    // The attacker picks a word, a capitalization and brute-forces the decoration.
+    let decoration = lowercase.len() - core.len();
+    Wordlist::EffLarge.bits_per_word() + 1.0 + decoration as f64 * (43f64).log2()
+}
+
+fn character_entropy(password: &str) -> f64 {
+    let bits_per_char = pool_size(password).log2();
+    let chars: Vec<char> = password.chars().collect();
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| match i {
+            0 => bits_per_char,
+            _ if is_predictable(chars[i - 1], c) => 1.0,
+            _ => bits_per_char,
+        })
+        .sum()
+}
+
+fn pool_size(password: &str) -> f64 {
+    let mut pool = 0;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if password
+        .chars()
+        .any(|c| c.is_ascii_punctuation() || c == ' ')
+    {
+        pool += 33;
+    }
+    if !password.is_ascii() {
+        pool += 100;
+    }
+    pool.max(1) as f64
+}
+
+/// Whether `current` follows `previous` as a repetition, a sequence or a keyboard walk.
+fn is_predictable(previous: char, current: char) -> bool {
+    let (previous, current) = (previous.to_ascii_lowercase(), current.to_ascii_lowercase());
+    if previous == current || (previous as i64 - current as i64).abs() == 1 {
+        return true;
+    }
+    KEYBOARD_ROWS.iter().any(|row| {
+        let bytes = row.as_bytes();
+        bytes.windows(2).any(|w| {
+            (w[0] as char == previous && w[1] as char == current)
+                || (w[1] as char == previous && w[0] as char == current)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Wordlist, generate_diceware_passphrase, generate_secure_password};
+
+    #[test]
+    fn test_common_passwords_are_very_weak() {
+        for password in ["password", "123456", "qwerty", "Password", "letmein"] {
+            let estimate = estimate_password_strength(password);
+            assert_eq!(
+                estimate.strength(),
+                PasswordStrength::VeryWeak,
+                "{password}"
+            );
+            assert!(!estimate.is_strong_enough());
+        }
+    }
+
+    #[test]
+    fn test_sequences_and_repetitions_are_weak() {
+        assert!(!estimate_password_strength("aaaaaaaaaaaaaaaa").is_strong_enough());
+        assert!(!estimate_password_strength("abcdefghijklmnop").is_strong_enough());
+        assert!(!estimate_password_strength("qwertyuiopasdfgh").is_strong_enough());
+        assert!(!estimate_password_strength("1234567890123456").is_strong_enough());
+    }
+
+    #[test]
+    fn test_decorated_dictionary_word_is_weak() {
+        let estimate = estimate_password_strength("Dragon2024!");
+        assert!(estimate.strength() <= PasswordStrength::Fair);
+        assert!(!estimate.is_strong_enough());
+    }
+
+    #[test]
+    fn test_generated_passwords_are_strong() {
+        let password = generate_secure_password(16).unwrap();
+        let estimate = estimate_password_strength(password.expose_secret());
+        assert!(estimate.is_strong_enough(), "{}", estimate.entropy_bits());
+    }
+
+    #[test]
+    fn test_diceware_passphrase_entropy() {
+        let passphrase = generate_diceware_passphrase(6, "-", Wordlist::EffLarge).unwrap();
+        let estimate = estimate_password_strength(passphrase.expose_secret());
+        assert!((estimate.entropy_bits() - 6.0 * 7776f64.log2()).abs() < 1e-9);
+        assert_eq!(estimate.strength(), PasswordStrength::Strong);
+
+        let estimate = estimate_password_strength("abandon ability able");
+        assert_eq!(estimate.entropy_bits(), 33.0);
+        assert_eq!(estimate.strength(), PasswordStrength::Weak);
+    }
+}
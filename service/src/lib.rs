@@ -1,13 +1,25 @@
 mod result;
 mod service;
+mod session;
 #[cfg(feature = "wasm")]
 mod wasm;
 
-use cwu_model::Balance;
+use cwu_core_address::NetworkAddress;
+use cwu_model::{
+    Asset, Balance, Delegation, FeeEstimate, Network, Resource, SentTransaction, SignedTransaction,
+    Staking, TransactionDetails, TransactionStatus, TransferPage, Voting, Witness,
+};
+use cwu_security_utils::{Keyfile, SecretString};
 use cwu_settings::CwuConfig;
-use cwu_wallet::EncryptedWallet;
+use cwu_wallet::{
+    AuditReport, EncryptedWallet, ExtendedPublicKey, RecoveryPhrase, RestoredWallet, VanityMatch,
+    VanityPattern, VanityProgress,
+};
 pub use result::{CwuServiceError, Result};
 pub use service::CwuService;
+pub use session::WalletSession;
+use std::path::Path;
+use std::time::Duration;
 
 pub trait CwuServiceTrait {
     fn create_wallet(
@@ -15,23 +27,305 @@ pub trait CwuServiceTrait {
         word_count: i32,
         language: &str,
         wallet_name: &str,
-    ) -> impl Future<Output = Result<String>> + Send;
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> impl Future<Output = Result<SecretString>> + Send;
 
     fn open_wallet(
         &self,
         name: &str,
-        master_password: String,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
     ) -> impl Future<Output = Result<EncryptedWallet>> + Send;
 
+    fn unlock_wallet(
+        &self,
+        session: &WalletSession,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// The mnemonic and passphrase of the wallet of an unlocked session.
     fn backup_wallet(
+        &self,
+        session: &WalletSession,
+    ) -> impl Future<Output = Result<RecoveryPhrase>> + Send;
+
+    fn add_decoy_wallet(
+        &self,
+        wallet_name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        duress_password: Option<SecretString>,
+    ) -> impl Future<Output = Result<SecretString>> + Send;
+
+    fn export_backup(
         &self,
         wallet: &EncryptedWallet,
-        master_password: String,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        backup_password: &SecretString,
+        path: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn import_backup(
+        &self,
+        path: &str,
+        backup_password: &SecretString,
+        wallet_name: Option<&str>,
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> impl Future<Output = Result<RestoredWallet>> + Send;
+
+    fn verify_audit_log(
+        &self,
+        wallet: &EncryptedWallet,
+    ) -> impl Future<Output = Result<AuditReport>> + Send;
+
+    /// Searches random keys, or the mnemonic indices of an unlocked session, for an address
+    /// matching `pattern`.
+    fn vanity_search(
+        &self,
+        pattern: &VanityPattern,
+        session: Option<&WalletSession>,
+        threads: usize,
+        on_progress: impl FnMut(&VanityProgress) -> bool + Send + 'static,
+    ) -> impl Future<Output = Result<Option<VanityMatch>>> + Send;
+
+    fn import_key(
+        &self,
+        wallet: &EncryptedWallet,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        network: Network,
+        private_key: &SecretString,
+    ) -> impl Future<Output = Result<NetworkAddress>> + Send;
+
+    /// The account level extended public keys of the networks derived from the mnemonic.
+    fn export_xpubs(
+        &self,
+        session: &WalletSession,
+    ) -> impl Future<Output = Result<Vec<(Network, ExtendedPublicKey)>>> + Send;
+
+    /// The receiving addresses `start..start + count` of an extended public key.
+    fn watch_only_addresses(
+        &self,
+        network: Network,
+        xpub: &str,
+        start: u32,
+        count: u32,
+    ) -> impl Future<Output = Result<Vec<NetworkAddress>>> + Send;
+
+    /// Estimates the fee of sending `amount` of `asset` from the wallet of a session, before
+    /// it is unlocked.
+    fn estimate_fee(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<FeeEstimate>> + Send;
+
+    /// Checks `amount` of `asset` on `network`, e.g. `"12.5"`, before anything is signed.
+    fn validate_amount(&self, network: Network, asset: Asset, amount: &str) -> Result<()>;
+
+    /// Sends `amount` of `asset` on the recipient's network, e.g. `"12.5"` TRX or USDT, from
+    /// the wallet of an unlocked session.
+    fn send(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// Builds an unsigned transaction sending `amount` of `asset` from the address `from` and
+    /// writes it to `path`, to be signed on an offline machine. Needs no wallet, only its
+    /// address.
+    fn export_transaction(
+        &self,
+        from: &NetworkAddress,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        path: &Path,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<TransactionDetails>> + Send;
+
+    /// Reads a transaction file, checking that its details match the transaction it holds.
+    fn read_transaction_file(
+        &self,
+        path: &Path,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<TransactionDetails>> + Send;
+
+    /// Signs the transaction file at `path` with the wallet of an unlocked session, without
+    /// the network, and writes the signed transaction to `signed_path`.
+    fn sign_transaction_file(
+        &self,
+        session: &WalletSession,
+        path: &Path,
+        signed_path: &Path,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SignedTransaction>> + Send;
+
+    /// Broadcasts the signed transaction file at `path` and returns the transaction id.
+    fn broadcast_transaction_file(
+        &self,
+        path: &Path,
+        config: &CwuConfig,
     ) -> impl Future<Output = Result<String>> + Send;
 
+    /// Follows a broadcast transaction until it is solidified or reverted, reporting each
+    /// change of its status to `on_status`, and returns the last status.
+    fn track_transaction(
+        &self,
+        network: Network,
+        tx_id: &str,
+        config: &CwuConfig,
+        on_status: impl FnMut(&TransactionStatus) + Send,
+    ) -> impl Future<Output = Result<TransactionStatus>> + Send;
+
+    /// The transfers `start..start + count` of the history of an address of the wallet of a
+    /// session, newest first.
+    ///
+    /// The first page is fetched fresh, older ones come from the encrypted cache of the wallet
+    /// when they can.
+    fn transaction_history(
+        &self,
+        session: &WalletSession,
+        address: &NetworkAddress,
+        start: usize,
+        count: usize,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<TransferPage>> + Send;
+
     fn check_balance(
         &self,
-        address: &str,
+        address: &NetworkAddress,
         config: &CwuConfig,
     ) -> impl Future<Output = Result<Balance>> + Send;
+
+    /// Whether a Tron address is activated: the first TRX sent to it activates it, which
+    /// burns 1 TRX more.
+    fn is_activated(
+        &self,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<bool>> + Send;
+
+    /// What an address stakes, and the TRX it is unstaking.
+    fn staking(
+        &self,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<Staking>> + Send;
+
+    /// Stakes `amount` TRX, e.g. `"12.5"`, for `resource` from the wallet of an unlocked
+    /// session, and returns the transaction id.
+    fn stake(
+        &self,
+        session: &WalletSession,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// Starts unstaking `amount` TRX staked for `resource` from the wallet of an unlocked
+    /// session, and returns the transaction id.
+    fn unstake(
+        &self,
+        session: &WalletSession,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// Withdraws the unlocked unstaked TRX of the wallet of an unlocked session back to its
+    /// balance, and returns the transaction id.
+    fn withdraw_unstaked(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// Cancels every unstaking in progress of the wallet of an unlocked session, and returns
+    /// the transaction id.
+    fn cancel_unstaking(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// The resources an address delegates to other addresses, and those delegated to it.
+    fn delegations(
+        &self,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<Vec<Delegation>>> + Send;
+
+    /// The most TRX staked for `resource` that an address can delegate, e.g. `"12.5"`.
+    fn delegatable(
+        &self,
+        address: &NetworkAddress,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// Delegates `amount` TRX staked for `resource` from the wallet of an unlocked session to
+    /// `to`, locked for `lock` when given, and returns the transaction id.
+    fn delegate(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        amount: &str,
+        resource: Resource,
+        lock: Option<Duration>,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// Takes back `amount` TRX staked for `resource` which the wallet of an unlocked session
+    /// delegates to `to`, and returns the transaction id.
+    fn undelegate(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// The `count` Tron witnesses with the most votes, the most voted first.
+    fn witnesses(
+        &self,
+        count: usize,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<Vec<Witness>>> + Send;
+
+    /// The votes an address casts, and the rewards they earned.
+    fn voting(
+        &self,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<Voting>> + Send;
+
+    /// Votes for witnesses from the wallet of an unlocked session, `votes` being how many
+    /// votes each gets, and returns the transaction id. The votes replace all the previous
+    /// ones.
+    fn vote(
+        &self,
+        session: &WalletSession,
+        votes: &[(NetworkAddress, u64)],
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
+
+    /// Claims the rewards the votes of the wallet of an unlocked session earned back to its
+    /// balance, and returns the transaction id.
+    fn claim_rewards(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> impl Future<Output = Result<SentTransaction>> + Send;
 }
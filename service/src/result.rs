@@ -1,6 +1,7 @@
 #[cfg(feature = "wasm")]
 use crate::wasm::WasmError;
 use cwu_ether::EtherError;
+use cwu_model::Network;
 use cwu_tron::TronError;
 use cwu_wallet::WalletError;
 
@@ -9,6 +10,12 @@ pub enum CwuServiceError {
     #[error("Address not found")]
     AddressNotFound,
 
+    #[error("The wallet session is locked, unlock it with the master password")]
+    SessionLocked,
+
+    #[error("{0} is not supported yet")]
+    UnsupportedNetwork(Network),
+
     #[error("{0}")]
     EtherError(#[from] EtherError),
 
@@ -24,6 +31,9 @@ pub enum CwuServiceError {
 
     #[error("{0}")]
     WalletError(#[from] WalletError),
+
+    #[error("{0}")]
+    TaskError(#[from] tokio::task::JoinError),
 }
 
 pub type Result<T> = std::result::Result<T, CwuServiceError>;
@@ -1,11 +1,21 @@
 #[cfg(feature = "wasm")]
 use crate::wasm::Host;
-use crate::{CwuServiceError, CwuServiceTrait, Result};
+use crate::{CwuServiceError, CwuServiceTrait, Result, WalletSession};
+use cwu_core_address::NetworkAddress;
 use cwu_ether::Usdt;
-use cwu_model::{Balance, Network};
+use cwu_model::{
+    Asset, Balance, Delegation, FeeEstimate, Network, Resource, SentTransaction, SignedTransaction,
+    Staking, TransactionDetails, TransactionStatus, TransferPage, Voting, Witness,
+};
+use cwu_security_utils::{Keyfile, SecretBytes, SecretString};
 use cwu_settings::CwuConfig;
-use cwu_tron::Tron;
-use cwu_wallet::EncryptedWallet;
+use cwu_tron::{OfflineTransaction, Tron, TronError, TronHistory, TronTracker};
+use cwu_wallet::{
+    AuditEvent, AuditOperation, AuditReport, EncryptedWallet, ExtendedPublicKey, RecoveryPhrase,
+    RestoredWallet, VanityMatch, VanityPattern, VanityProgress, VanitySource, WatchOnlyWallet,
+};
+use std::path::Path;
+use std::time::Duration;
 
 pub struct CwuService {}
 
@@ -27,7 +37,9 @@ impl CwuServiceTrait for CwuService {
         word_count: i32,
         language: &str,
         wallet_name: &str,
-    ) -> Result<String> {
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<SecretString> {
         #[cfg(feature = "wasm")]
         {
             let mut host = Host::set_up()?;
@@ -35,57 +47,607 @@ impl CwuServiceTrait for CwuService {
         }
         #[cfg(not(feature = "wasm"))]
         {
-            Ok(EncryptedWallet::create(word_count, language, wallet_name)?)
+            Ok(EncryptedWallet::create(
+                word_count,
+                language,
+                wallet_name,
+                master_password,
+                keyfile,
+            )?)
         }
     }
 
-    async fn open_wallet(&self, name: &str, master_password: String) -> Result<EncryptedWallet> {
-        Ok(EncryptedWallet::open(name, master_password)?)
+    async fn open_wallet(
+        &self,
+        name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<EncryptedWallet> {
+        let wallet = EncryptedWallet::open(name, master_password, keyfile)?;
+        wallet.audit(AuditEvent::new(AuditOperation::Open))?;
+        Ok(wallet)
+    }
+
+    async fn unlock_wallet(
+        &self,
+        session: &WalletSession,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<()> {
+        session.unlock(master_password, keyfile)
+    }
+
+    async fn backup_wallet(&self, session: &WalletSession) -> Result<RecoveryPhrase> {
+        let wallet = session.wallet();
+        let phrase = session.with_keys(|keys| Ok(wallet.backup_with_keys(keys)?))?;
+        wallet.audit(AuditEvent::new(AuditOperation::Backup))?;
+        Ok(phrase)
     }
 
-    async fn backup_wallet(
+    async fn add_decoy_wallet(
+        &self,
+        wallet_name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        duress_password: Option<SecretString>,
+    ) -> Result<SecretString> {
+        Ok(EncryptedWallet::add_decoy(
+            wallet_name,
+            master_password,
+            keyfile,
+            duress_password,
+        )?)
+    }
+
+    async fn export_backup(
         &self,
         wallet: &EncryptedWallet,
-        master_password: String,
-    ) -> Result<String> {
-        Ok(wallet.backup(master_password)?)
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        backup_password: &SecretString,
+        path: &str,
+    ) -> Result<()> {
+        wallet.export_backup(master_password, keyfile, backup_password, path)?;
+        wallet.audit(AuditEvent::new(AuditOperation::ExportBackup))?;
+        Ok(())
+    }
+
+    async fn import_backup(
+        &self,
+        path: &str,
+        backup_password: &SecretString,
+        wallet_name: Option<&str>,
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<RestoredWallet> {
+        Ok(EncryptedWallet::import_backup(
+            path,
+            backup_password,
+            wallet_name,
+            master_password,
+            keyfile,
+        )?)
+    }
+
+    async fn verify_audit_log(&self, wallet: &EncryptedWallet) -> Result<AuditReport> {
+        Ok(wallet.verify_audit_log()?)
+    }
+
+    async fn vanity_search(
+        &self,
+        pattern: &VanityPattern,
+        session: Option<&WalletSession>,
+        threads: usize,
+        on_progress: impl FnMut(&VanityProgress) -> bool + Send + 'static,
+    ) -> Result<Option<VanityMatch>> {
+        let source = match session {
+            Some(session) => session
+                .with_keys(|keys| Ok(VanitySource::wallet_indices(session.wallet(), keys)?))?,
+            None => VanitySource::RandomKeys,
+        };
+        let pattern = pattern.clone();
+        let found =
+            tokio::task::spawn_blocking(move || pattern.search(&source, threads, on_progress))
+                .await??;
+        Ok(found)
+    }
+
+    async fn import_key(
+        &self,
+        wallet: &EncryptedWallet,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        network: Network,
+        private_key: &SecretString,
+    ) -> Result<NetworkAddress> {
+        let address = EncryptedWallet::import_key(
+            wallet.name(),
+            master_password,
+            keyfile,
+            network,
+            private_key,
+        )?;
+        wallet.audit(AuditEvent::new(AuditOperation::ImportKey).with_address(&address))?;
+        Ok(address)
     }
 
-    async fn check_balance(&self, address: &str, config: &CwuConfig) -> Result<Balance> {
-        for network in Network::iter() {
-            return match network {
-                Network::Ethereum => {
-                    let usdt = Usdt::new()?;
-                    let balance = usdt.balance(address).await;
-                    if balance.is_err() {
-                        continue;
+    async fn export_xpubs(
+        &self,
+        session: &WalletSession,
+    ) -> Result<Vec<(Network, ExtendedPublicKey)>> {
+        let wallet = session.wallet();
+        let xpubs = session.with_keys(|keys| {
+            Network::iter()
+                .filter(|&&network| !wallet.is_imported(network))
+                .map(|&network| Ok((network, wallet.account_xpub(keys, network)?)))
+                .collect::<Result<Vec<_>>>()
+        })?;
+        wallet.audit(AuditEvent::new(AuditOperation::ExportXpub))?;
+        Ok(xpubs)
+    }
+
+    async fn watch_only_addresses(
+        &self,
+        network: Network,
+        xpub: &str,
+        start: u32,
+        count: u32,
+    ) -> Result<Vec<NetworkAddress>> {
+        let wallet = WatchOnlyWallet::new(network, xpub)?;
+        (start..start.saturating_add(count))
+            .map(|index| Ok(wallet.address(index)?))
+            .collect()
+    }
+
+    async fn estimate_fee(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        config: &CwuConfig,
+    ) -> Result<FeeEstimate> {
+        let from = session.wallet().addresses().get(&to.network());
+        match (from, to) {
+            (Some(NetworkAddress::Tron(from)), NetworkAddress::Tron(to)) => {
+                let tron = Tron::new(config).await?;
+                let estimate = match asset {
+                    Asset::Native => {
+                        let amount = cwu_tron::parse_trx(amount)?;
+                        tron.estimate_trx_transfer(from, to, amount).await?
                     }
-                    let balance = balance?;
-                    let usdt_balance = if let Ok(usdt) = usdt.usdt_balance(balance, 6) {
-                        usdt
-                    } else {
-                        "0 USDT".to_string()
-                    };
-                    Ok(Balance::new(Network::Ethereum, usdt_balance))
-                }
-                Network::Tron => {
-                    let tron = Tron::new(config).await?;
-                    let trx_balance = tron.trx_balance(address).await;
-                    if trx_balance.is_err() {
-                        eprintln!("Error: {}", trx_balance.err().unwrap());
-                        continue;
+                    Asset::Usdt => {
+                        let amount = cwu_tron::parse_usdt(amount)?;
+                        tron.estimate_usdt_transfer(from, to, amount).await?
+                    }
+                };
+                Ok(estimate)
+            }
+            _ => Err(CwuServiceError::UnsupportedNetwork(to.network())),
+        }
+    }
+
+    fn validate_amount(&self, network: Network, asset: Asset, amount: &str) -> Result<()> {
+        match (network, asset) {
+            (Network::Tron, Asset::Native) => {
+                cwu_tron::parse_trx(amount)?;
+            }
+            (Network::Tron, Asset::Usdt) => {
+                cwu_tron::parse_usdt(amount)?;
+            }
+            (network, _) => return Err(CwuServiceError::UnsupportedNetwork(network)),
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let wallet = session.wallet();
+        let tx_id = match to {
+            NetworkAddress::Tron(address) => {
+                let private_key =
+                    session.with_keys(|keys| Ok(wallet.signing_key(keys, Network::Tron)?))?;
+                let tron = Tron::new(config).await?;
+                match asset {
+                    Asset::Native => {
+                        let amount = cwu_tron::parse_trx(amount)?;
+                        tron.send_trx(private_key.expose_secret(), address, amount)
+                            .await?
+                    }
+                    Asset::Usdt => {
+                        let amount = cwu_tron::parse_usdt(amount)?;
+                        tron.send_usdt(private_key.expose_secret(), address, amount)
+                            .await?
                     }
-                    let usdt_balance = match tron.usdt_balance(address).await {
-                        Ok(usdt) => usdt.to_string(),
-                        Err(err) => {
-                            eprintln!("Error: {err}");
-                            "0 USDT".to_string()
-                        }
-                    };
-                    Ok(Balance::new(Network::Tron, usdt_balance))
                 }
-            };
+            }
+            _ => return Err(CwuServiceError::UnsupportedNetwork(to.network())),
+        };
+        Ok(audited(session, AuditOperation::Send, to, tx_id))
+    }
+
+    async fn export_transaction(
+        &self,
+        from: &NetworkAddress,
+        to: &NetworkAddress,
+        asset: Asset,
+        amount: &str,
+        path: &Path,
+        config: &CwuConfig,
+    ) -> Result<TransactionDetails> {
+        let (NetworkAddress::Tron(from), NetworkAddress::Tron(to)) = (from, to) else {
+            return Err(CwuServiceError::UnsupportedNetwork(to.network()));
+        };
+        let tron = Tron::new(config).await?;
+        let transaction = match asset {
+            Asset::Native => {
+                let amount = cwu_tron::parse_trx(amount)?;
+                tron.export_trx(from, to, amount).await?
+            }
+            Asset::Usdt => {
+                let amount = cwu_tron::parse_usdt(amount)?;
+                tron.export_usdt(from, to, amount).await?
+            }
+        };
+        transaction.write(path)?;
+        Ok(transaction.details().clone())
+    }
+
+    async fn read_transaction_file(
+        &self,
+        path: &Path,
+        config: &CwuConfig,
+    ) -> Result<TransactionDetails> {
+        let transaction = OfflineTransaction::read(path, config).await?;
+        Ok(transaction.details().clone())
+    }
+
+    async fn sign_transaction_file(
+        &self,
+        session: &WalletSession,
+        path: &Path,
+        signed_path: &Path,
+        config: &CwuConfig,
+    ) -> Result<SignedTransaction> {
+        let wallet = session.wallet();
+        let transaction = OfflineTransaction::read(path, config).await?;
+        let recipient = recipient(transaction.details())?;
+        let private_key = session.with_keys(|keys| Ok(wallet.signing_key(keys, Network::Tron)?))?;
+        let signed = transaction.sign(private_key.expose_secret()).await?;
+        signed.write(signed_path)?;
+        let details = signed.details().clone();
+        let audit_error = wallet
+            .audit(
+                AuditEvent::new(AuditOperation::Sign)
+                    .with_address(&recipient)
+                    .with_tx_id(&details.tx_id),
+            )
+            .err()
+            .map(|e| e.to_string());
+        Ok(SignedTransaction {
+            details,
+            audit_error,
+        })
+    }
+
+    async fn broadcast_transaction_file(&self, path: &Path, config: &CwuConfig) -> Result<String> {
+        let transaction = OfflineTransaction::read(path, config).await?;
+        Ok(Tron::new(config)
+            .await?
+            .broadcast_offline(&transaction)
+            .await?)
+    }
+
+    async fn track_transaction(
+        &self,
+        network: Network,
+        tx_id: &str,
+        config: &CwuConfig,
+        on_status: impl FnMut(&TransactionStatus) + Send,
+    ) -> Result<TransactionStatus> {
+        match network {
+            Network::Tron => Ok(TronTracker::new(config)?.track(tx_id, on_status).await?),
+            _ => Err(CwuServiceError::UnsupportedNetwork(network)),
         }
-        Err(CwuServiceError::AddressNotFound)
+    }
+
+    async fn transaction_history(
+        &self,
+        session: &WalletSession,
+        address: &NetworkAddress,
+        start: usize,
+        count: usize,
+        config: &CwuConfig,
+    ) -> Result<TransferPage> {
+        let NetworkAddress::Tron(address) = address else {
+            return Err(CwuServiceError::UnsupportedNetwork(address.network()));
+        };
+        let wallet = session.wallet();
+        let cache_name = format!("{address}.{}.history", config.network_mode);
+        let cached = wallet.read_cache(&cache_name);
+        let mut history = TronHistory::open(
+            config,
+            address,
+            cached.as_ref().map(|cached| cached.expose_secret()),
+        )?;
+        if start == 0 {
+            history.refresh().await?;
+        }
+        while history.transfers().len() <= start + count && history.has_more() {
+            history.load_more().await?;
+        }
+        wallet.write_cache(&cache_name, &history.cache()?)?;
+        let transfers = history.transfers();
+        Ok(TransferPage {
+            has_more: transfers.len() > start + count || history.has_more(),
+            transfers: transfers.into_iter().skip(start).take(count).collect(),
+        })
+    }
+
+    async fn check_balance(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<Balance> {
+        match address {
+            NetworkAddress::Ethereum(address) => {
+                let usdt = Usdt::new()?;
+                let balance = usdt.balance(&address.to_string()).await?;
+                let usdt_balance = if let Ok(usdt) = usdt.usdt_balance(balance, 6) {
+                    usdt
+                } else {
+                    "0 USDT".to_string()
+                };
+                Ok(Balance::new(Network::Ethereum, usdt_balance))
+            }
+            NetworkAddress::Tron(address) => {
+                let tron = Tron::new(config).await?;
+                let account = tron.account(address).await?;
+                let usdt_balance = tron.usdt_balance(address).await?.to_string();
+                Ok(Balance::new(Network::Tron, usdt_balance).with_tron_account(account))
+            }
+            NetworkAddress::Bitcoin(_) => {
+                Err(CwuServiceError::UnsupportedNetwork(Network::Bitcoin))
+            }
+        }
+    }
+
+    async fn is_activated(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<bool> {
+        let NetworkAddress::Tron(address) = address else {
+            return Err(CwuServiceError::UnsupportedNetwork(address.network()));
+        };
+        Ok(Tron::new(config).await?.is_activated(address).await?)
+    }
+
+    async fn staking(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<Staking> {
+        let NetworkAddress::Tron(address) = address else {
+            return Err(CwuServiceError::UnsupportedNetwork(address.network()));
+        };
+        Ok(Tron::new(config).await?.staking(address).await?)
+    }
+
+    async fn stake(
+        &self,
+        session: &WalletSession,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let (address, private_key) = tron_signer(session)?;
+        let amount = cwu_tron::parse_trx(amount)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .stake(private_key.expose_secret(), amount, resource)
+            .await?;
+        Ok(audited(session, AuditOperation::Stake, &address, tx_id))
+    }
+
+    async fn unstake(
+        &self,
+        session: &WalletSession,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let (address, private_key) = tron_signer(session)?;
+        let amount = cwu_tron::parse_trx(amount)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .unstake(private_key.expose_secret(), amount, resource)
+            .await?;
+        Ok(audited(session, AuditOperation::Stake, &address, tx_id))
+    }
+
+    async fn withdraw_unstaked(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let (address, private_key) = tron_signer(session)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .withdraw_unstaked(private_key.expose_secret())
+            .await?;
+        Ok(audited(session, AuditOperation::Stake, &address, tx_id))
+    }
+
+    async fn cancel_unstaking(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let (address, private_key) = tron_signer(session)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .cancel_unstaking(private_key.expose_secret())
+            .await?;
+        Ok(audited(session, AuditOperation::Stake, &address, tx_id))
+    }
+
+    async fn delegations(
+        &self,
+        address: &NetworkAddress,
+        config: &CwuConfig,
+    ) -> Result<Vec<Delegation>> {
+        let NetworkAddress::Tron(address) = address else {
+            return Err(CwuServiceError::UnsupportedNetwork(address.network()));
+        };
+        Ok(Tron::new(config).await?.delegations(address).await?)
+    }
+
+    async fn delegatable(
+        &self,
+        address: &NetworkAddress,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<String> {
+        let NetworkAddress::Tron(address) = address else {
+            return Err(CwuServiceError::UnsupportedNetwork(address.network()));
+        };
+        let amount = Tron::new(config)
+            .await?
+            .delegatable(address, resource)
+            .await?;
+        Ok(cwu_tron::format_units(
+            amount.to_sun().max(0) as u128,
+            cwu_tron::TRX_DECIMALS,
+        ))
+    }
+
+    async fn delegate(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        amount: &str,
+        resource: Resource,
+        lock: Option<Duration>,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let NetworkAddress::Tron(receiver) = to else {
+            return Err(CwuServiceError::UnsupportedNetwork(to.network()));
+        };
+        let (_, private_key) = tron_signer(session)?;
+        let amount = cwu_tron::parse_trx(amount)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .delegate(
+                private_key.expose_secret(),
+                receiver,
+                amount,
+                resource,
+                lock,
+            )
+            .await?;
+        Ok(audited(session, AuditOperation::Delegate, to, tx_id))
+    }
+
+    async fn undelegate(
+        &self,
+        session: &WalletSession,
+        to: &NetworkAddress,
+        amount: &str,
+        resource: Resource,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let NetworkAddress::Tron(receiver) = to else {
+            return Err(CwuServiceError::UnsupportedNetwork(to.network()));
+        };
+        let (_, private_key) = tron_signer(session)?;
+        let amount = cwu_tron::parse_trx(amount)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .undelegate(private_key.expose_secret(), receiver, amount, resource)
+            .await?;
+        Ok(audited(session, AuditOperation::Delegate, to, tx_id))
+    }
+
+    async fn witnesses(&self, count: usize, config: &CwuConfig) -> Result<Vec<Witness>> {
+        Ok(Tron::new(config).await?.witnesses(count).await?)
+    }
+
+    async fn voting(&self, address: &NetworkAddress, config: &CwuConfig) -> Result<Voting> {
+        let NetworkAddress::Tron(address) = address else {
+            return Err(CwuServiceError::UnsupportedNetwork(address.network()));
+        };
+        Ok(Tron::new(config).await?.voting(address).await?)
+    }
+
+    async fn vote(
+        &self,
+        session: &WalletSession,
+        votes: &[(NetworkAddress, u64)],
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let votes = votes
+            .iter()
+            .map(|(witness, count)| match witness {
+                NetworkAddress::Tron(witness) => Ok((witness.clone(), *count)),
+                witness => Err(CwuServiceError::UnsupportedNetwork(witness.network())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (address, private_key) = tron_signer(session)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .vote(private_key.expose_secret(), &votes)
+            .await?;
+        Ok(audited(session, AuditOperation::Vote, &address, tx_id))
+    }
+
+    async fn claim_rewards(
+        &self,
+        session: &WalletSession,
+        config: &CwuConfig,
+    ) -> Result<SentTransaction> {
+        let (address, private_key) = tron_signer(session)?;
+        let tx_id = Tron::new(config)
+            .await?
+            .claim_rewards(private_key.expose_secret())
+            .await?;
+        Ok(audited(session, AuditOperation::Vote, &address, tx_id))
+    }
+}
+
+/// The Tron address of the wallet of an unlocked session, with its signing key.
+fn tron_signer(session: &WalletSession) -> Result<(NetworkAddress, SecretBytes)> {
+    let wallet = session.wallet();
+    let address = wallet
+        .addresses()
+        .get(&Network::Tron)
+        .cloned()
+        .ok_or(CwuServiceError::AddressNotFound)?;
+    let private_key = session.with_keys(|keys| Ok(wallet.signing_key(keys, Network::Tron)?))?;
+    Ok((address, private_key))
+}
+
+/// Records a broadcast transaction in the audit log of a session.
+///
+/// The transaction is already sent, so a failure to record it is returned with the id.
+fn audited(
+    session: &WalletSession,
+    operation: AuditOperation,
+    address: &NetworkAddress,
+    tx_id: String,
+) -> SentTransaction {
+    let audit_error = session
+        .wallet()
+        .audit(
+            AuditEvent::new(operation)
+                .with_address(address)
+                .with_tx_id(&tx_id),
+        )
+        .err()
+        .map(|e| e.to_string());
+    SentTransaction { tx_id, audit_error }
+}
+
+/// The recipient of a transaction read from a file.
+fn recipient(details: &TransactionDetails) -> Result<NetworkAddress> {
+    match details.network {
+        Network::Tron => Ok(NetworkAddress::Tron(
+            details.to.parse().map_err(TronError::from)?,
+        )),
+        network => Err(CwuServiceError::UnsupportedNetwork(network)),
     }
 }
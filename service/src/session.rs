@@ -0,0 +1,196 @@
+use crate::{CwuServiceError, Result};
+use cwu_security_utils::{Keyfile, SecretString};
+use cwu_wallet::{EncryptedWallet, WalletKeys};
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// An open wallet that can be unlocked for a while.
+///
+/// Unlocking derives the wallet keys once and keeps them in memory, so operations on the
+/// wallet don't ask for the master password again. The keys are wiped when the session is
+/// locked: explicitly, after `idle_timeout` without use, or when the session is dropped.
+/// A background thread enforces the timeout even if the session isn't used anymore.
+pub struct WalletSession {
+    wallet: EncryptedWallet,
+    idle_timeout: Duration,
+    state: Arc<(Mutex<SessionState>, Condvar)>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    keys: Option<WalletKeys>,
+    deadline: Option<Instant>,
+    watchdog: bool,
+    closed: bool,
+}
+
+impl SessionState {
+    fn lock(&mut self) {
+        self.keys = None;
+        self.deadline = None;
+    }
+
+    /// Locks the session if the deadline has passed.
+    fn expire(&mut self) {
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.lock();
+        }
+    }
+}
+
+impl WalletSession {
+    /// Starts a locked session.
+    pub fn new(wallet: EncryptedWallet, idle_timeout: Duration) -> Self {
+        Self {
+            wallet,
+            idle_timeout,
+            state: Arc::new((Mutex::new(SessionState::default()), Condvar::new())),
+        }
+    }
+
+    pub fn wallet(&self) -> &EncryptedWallet {
+        &self.wallet
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Derives the wallet keys and keeps them until the session is locked.
+    pub fn unlock(&self, master_password: &SecretString, keyfile: Option<&Keyfile>) -> Result<()> {
+        let keys = self.wallet.unlock(master_password, keyfile)?;
+
+        let mut state = self.state();
+        state.keys = Some(keys);
+        state.deadline = Some(Instant::now() + self.idle_timeout);
+        if !state.watchdog {
+            state.watchdog = true;
+            let watched = Arc::clone(&self.state);
+            thread::spawn(move || watchdog(&watched));
+        }
+        drop(state);
+        self.state.1.notify_all();
+        Ok(())
+    }
+
+    /// Wipes the wallet keys.
+    pub fn lock(&self) {
+        self.state().lock();
+        self.state.1.notify_all();
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.remaining().is_some()
+    }
+
+    /// The time left before the session locks itself, `None` if it is locked.
+    pub fn remaining(&self) -> Option<Duration> {
+        let mut state = self.state();
+        state.expire();
+        state
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Runs `f` with the wallet keys, and restarts the idle timeout.
+    pub(crate) fn with_keys<T>(&self, f: impl FnOnce(&WalletKeys) -> Result<T>) -> Result<T> {
+        let mut state = self.state();
+        state.expire();
+        let keys = state.keys.as_ref().ok_or(CwuServiceError::SessionLocked)?;
+        let result = f(keys);
+        state.deadline = Some(Instant::now() + self.idle_timeout);
+        result
+    }
+
+    fn state(&self) -> MutexGuard<'_, SessionState> {
+        // The state stays consistent even if a holder panicked: keys are set or wiped at once.
+        self.state.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for WalletSession {
+    fn drop(&mut self) {
+        let mut state = self.state();
+        state.lock();
+        state.closed = true;
+        drop(state);
+        self.state.1.notify_all();
+    }
+}
+
+/// Waits for the deadline of the session and locks it, unless it is locked or used first.
+fn watchdog(shared: &(Mutex<SessionState>, Condvar)) {
+    let (mutex, condvar) = shared;
+    let mut state = mutex.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        state.expire();
+        let Some(deadline) = state.deadline.filter(|_| !state.closed) else {
+            state.watchdog = false;
+            return;
+        };
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        state = condvar
+            .wait_timeout(state, timeout)
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER_PASSWORD: &str = "TtWId4h$fm1y#(Nn";
+    const MNEMONIC: &str =
+        "fiber jazz upper cruel betray fence series suit habit ski crowd project";
+
+    fn open_session(idle_timeout: Duration) -> WalletSession {
+        let master_password = SecretString::from(MASTER_PASSWORD);
+        let wallet =
+            EncryptedWallet::open("../wallet/src/tests/test1", &master_password, None).unwrap();
+        WalletSession::new(wallet, idle_timeout)
+    }
+
+    #[test]
+    fn test_session_unlock_and_lock() {
+        let session = open_session(Duration::from_secs(60));
+        assert!(!session.is_unlocked());
+        assert!(matches!(
+            session.with_keys(|_| Ok(())),
+            Err(CwuServiceError::SessionLocked)
+        ));
+
+        session
+            .unlock(&SecretString::from(MASTER_PASSWORD), None)
+            .unwrap();
+        assert!(session.remaining().unwrap() <= Duration::from_secs(60));
+        let phrase = session
+            .with_keys(|keys| Ok(session.wallet().backup_with_keys(keys)?))
+            .unwrap();
+        assert_eq!(phrase.mnemonic.expose_secret(), MNEMONIC);
+
+        session.lock();
+        assert!(!session.is_unlocked());
+        assert!(session.with_keys(|_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_session_locks_when_idle() {
+        let session = open_session(Duration::from_millis(200));
+        session
+            .unlock(&SecretString::from(MASTER_PASSWORD), None)
+            .unwrap();
+        assert!(session.is_unlocked());
+
+        thread::sleep(Duration::from_millis(400));
+        // The watchdog wiped the keys on its own.
+        assert!(session.state().keys.is_none());
+        assert!(!session.is_unlocked());
+    }
+}
@@ -10,6 +10,28 @@ pub struct BlockchainUrls {
     pub block_explorer: Option<String>,
     pub tx_api: Option<String>,
     pub usdt_smart_contract_address: Option<String>,
+    /// The most a smart contract call, e.g. a USDT transfer, may burn in fees, in the
+    /// smallest unit of the native coin (sun on Tron).
+    pub fee_limit: Option<i64>,
+    /// Transactions expected to burn more than this in fees, in the smallest unit of the
+    /// native coin, are flagged before they are sent.
+    pub fee_warning_threshold: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+pub struct SessionSettings {
+    /// An unlocked wallet is locked again after this many seconds without use.
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: 300,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -17,6 +39,8 @@ pub struct CwuConfig {
     pub network_mode: String,
     pub ether: BlockchainUrls,
     pub tron: BlockchainUrls,
+    #[serde(default)]
+    pub session: SessionSettings,
 }
 
 pub type Result<T> = std::result::Result<T, ConfigError>;
@@ -0,0 +1,110 @@
+//! # Account resources
+//!
+//! Besides TRX, a Tron account has energy and bandwidth to pay for transactions, from the
+//! TRX it stakes and, for bandwidth, a small free daily amount. An address only exists on
+//! chain once activated, by the first TRX sent to it.
+
+use crate::{TRX_DECIMALS, format_units};
+use cwu_model::TronAccount;
+use tronic::domain::{
+    account::{Account, AccountResourceUsage, AccountStatus},
+    trx::Trx,
+};
+
+/// The TRX and resources of an account, with the resources it has used.
+pub(crate) fn tron_account(account: &Account, usage: &AccountResourceUsage) -> TronAccount {
+    let staked = account
+        .frozen_v2
+        .iter()
+        .map(|frozen| frozen.amount)
+        .sum::<Trx>()
+        + account.delegated_frozen_v2_balance_for_bandwidth
+        + account
+            .account_resource
+            .delegated_frozen_v2_balance_for_energy;
+    let left = |limit: i64, used: i64| (limit - used).max(0) as u64;
+    TronAccount {
+        trx: trx(account.balance),
+        staked: trx(staked),
+        energy: left(usage.energy_limit, usage.energy_used),
+        energy_limit: usage.energy_limit.max(0) as u64,
+        bandwidth: left(usage.free_net_limit, usage.free_net_used)
+            + left(usage.net_limit, usage.net_used),
+        bandwidth_limit: (usage.free_net_limit + usage.net_limit).max(0) as u64,
+        activated: account.status() != AccountStatus::NotExists,
+    }
+}
+
+fn trx(amount: Trx) -> String {
+    format_units(amount.to_sun().max(0) as u128, TRX_DECIMALS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+    use tronic::domain::{
+        account::{AccountResource, FreezeV2},
+        contract::ResourceCode,
+    };
+
+    #[test]
+    fn test_tron_account() {
+        let account = Account {
+            balance: Trx::from_sun(12_500_000),
+            frozen_v2: vec![
+                FreezeV2 {
+                    freeze_type: ResourceCode::Energy,
+                    amount: Trx::from_sun(100_000_000),
+                },
+                FreezeV2 {
+                    freeze_type: ResourceCode::Bandwidth,
+                    amount: Trx::from_sun(5_000_000),
+                },
+            ],
+            delegated_frozen_v2_balance_for_bandwidth: Trx::from_sun(1_000_000),
+            account_resource: AccountResource {
+                delegated_frozen_v2_balance_for_energy: Trx::from_sun(500_000),
+                ..Default::default()
+            },
+            create_time: OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+            ..Default::default()
+        };
+        let usage = AccountResourceUsage {
+            free_net_used: 100,
+            free_net_limit: 600,
+            net_used: 20,
+            net_limit: 10,
+            asset_net_used: Default::default(),
+            asset_net_limit: Default::default(),
+            total_net_limit: 43_200_000_000,
+            total_net_weight: 26_000_000_000,
+            total_tron_power_weight: 50_000_000_000,
+            tron_power_used: 0,
+            tron_power_limit: 106,
+            energy_used: 1_000,
+            energy_limit: 65_000,
+            total_energy_limit: 180_000_000_000,
+            total_energy_weight: 19_000_000_000,
+            storage_used: 0,
+            storage_limit: 0,
+        };
+
+        assert_eq!(
+            tron_account(&account, &usage),
+            TronAccount {
+                trx: "12.5".to_string(),
+                staked: "106.5".to_string(),
+                energy: 64_000,
+                energy_limit: 65_000,
+                bandwidth: 500,
+                bandwidth_limit: 610,
+                activated: true,
+            }
+        );
+
+        let unactivated = tron_account(&Account::default(), &usage);
+        assert!(!unactivated.activated);
+        assert_eq!(unactivated.trx, "0");
+    }
+}
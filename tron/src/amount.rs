@@ -0,0 +1,102 @@
+use crate::{Result, TronError};
+use tronic::{contracts::token::usdt::Usdt, domain::trx::Trx};
+
+/// The number of decimals of TRX, 1 TRX = 1,000,000 sun.
+pub const TRX_DECIMALS: u32 = 6;
+
+/// The number of decimals of the TRC20 USDT token.
+pub const USDT_DECIMALS: u32 = 6;
+
+/// Parses a decimal amount, e.g. `12.5`, into base units with `decimals` decimals.
+///
+/// The conversion is exact: amounts with more decimals than the token has are rejected
+/// rather than rounded.
+pub fn parse_units(amount: &str, decimals: u32) -> Result<i64> {
+    let invalid = || TronError::InvalidAmount(amount.to_string());
+    let amount = amount.trim();
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty())
+        || !is_digits(whole)
+        || !is_digits(fraction)
+        || fraction.len() > decimals as usize
+    {
+        return Err(invalid());
+    }
+
+    let scale = 10i64.pow(decimals);
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<i64>().map_err(|_| invalid())?
+    };
+    let fraction = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<width$}", width = decimals as usize)
+            .parse::<i64>()
+            .map_err(|_| invalid())?
+    };
+    let units = whole
+        .checked_mul(scale)
+        .and_then(|units| units.checked_add(fraction))
+        .ok_or_else(invalid)?;
+    if units == 0 {
+        return Err(invalid());
+    }
+    Ok(units)
+}
+
+/// Formats an amount in base units with `decimals` decimals, e.g. `12.5`, the inverse of
+/// [`parse_units`].
+pub fn format_units(units: u128, decimals: u32) -> String {
+    let scale = 10u128.pow(decimals);
+    let fraction = format!("{:0width$}", units % scale, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (units / scale).to_string()
+    } else {
+        format!("{}.{fraction}", units / scale)
+    }
+}
+
+/// Parses a positive TRX amount, e.g. `12.5`.
+pub fn parse_trx(amount: &str) -> Result<Trx> {
+    Ok(Trx::from_sun(parse_units(amount, TRX_DECIMALS)?))
+}
+
+/// Parses a positive USDT amount, e.g. `12.5`.
+pub fn parse_usdt(amount: &str) -> Result<Usdt> {
+    Ok(Usdt::from(parse_units(amount, USDT_DECIMALS)? as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_units("1", 6).unwrap(), 1_000_000);
+        assert_eq!(parse_units("12.5", 6).unwrap(), 12_500_000);
+        assert_eq!(parse_units(".000001", 6).unwrap(), 1);
+        assert_eq!(parse_trx(" 0.01 ").unwrap(), Trx::from_sun(10_000));
+        assert_eq!(parse_usdt("2.5").unwrap(), Usdt::from(2_500_000u64));
+
+        assert_eq!(format_units(12_500_000, 6), "12.5");
+        assert_eq!(format_units(1, 6), "0.000001");
+        assert_eq!(format_units(3_000_000, 6), "3");
+
+        for invalid in [
+            "",
+            ".",
+            "0",
+            "-1",
+            "1.0000001",
+            "1e3",
+            "1,5",
+            "99999999999999",
+        ] {
+            assert!(parse_units(invalid, 6).is_err(), "{invalid}");
+        }
+    }
+}
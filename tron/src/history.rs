@@ -0,0 +1,658 @@
+//! # Transaction History
+//!
+//! The TronGrid v1 API at `tx_api` lists the transactions of an account and its TRC20
+//! transfers as two streams, newest first and paged with a fingerprint. They are merged into
+//! one list of TRX and USDT transfers, which can be cached so that pages already fetched are
+//! not fetched again. Storing the cache is left to the caller: it tells the address.
+//!
+//! The streams are paged independently, so the merged list is only complete down to the
+//! oldest entry of the stream fetched the least far back: older transfers are held back
+//! until the other stream catches up.
+
+use crate::{
+    Result, TronError,
+    amount::{TRX_DECIMALS, format_units},
+};
+use cwu_core_address::TronAddress;
+use cwu_model::{Asset, Direction, Network, Transfer, TransferStatus};
+use cwu_settings::CwuConfig;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::collections::BTreeMap;
+
+/// How many entries are fetched at once, TronGrid allows up to 200.
+const PAGE_LIMIT: u32 = 50;
+
+/// The selector of the TRC20 `transfer(address,uint256)` function.
+const TRANSFER_SELECTOR: &str = "a9059cbb";
+
+/// The transfer history of a Tron address.
+pub struct TronHistory {
+    http: reqwest::Client,
+    tx_api: String,
+    usdt_smart_contract_address: TronAddress,
+    address: TronAddress,
+    cache: HistoryCache,
+}
+
+impl TronHistory {
+    /// Opens the history of `address`, with the transfers `cached` from a previous
+    /// [`TronHistory::cache`].
+    pub fn open(config: &CwuConfig, address: &TronAddress, cached: Option<&str>) -> Result<Self> {
+        let tx_api = tx_api(config)?;
+        let usdt_smart_contract_address = config
+            .tron
+            .usdt_smart_contract_address
+            .as_ref()
+            .ok_or(TronError::UsdtSmartContractAddressIsNotSet)?
+            .parse()?;
+        // The cache only saves requests: one that can't be read is rebuilt from the API.
+        let cache = cached
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default();
+        Ok(Self {
+            http: reqwest::Client::new(),
+            tx_api,
+            usdt_smart_contract_address,
+            address: address.clone(),
+            cache,
+        })
+    }
+
+    /// The transfers known without gaps, newest first.
+    pub fn transfers(&self) -> Vec<Transfer> {
+        self.cache.transfers()
+    }
+
+    /// Whether older transfers are left to fetch.
+    pub fn has_more(&self) -> bool {
+        self.cache.transactions.has_more() || self.cache.trc20_transfers.has_more()
+    }
+
+    /// Fetches the newest transfers.
+    ///
+    /// When they don't reach the cached ones, the transfers in between are unknown and the
+    /// cache is dropped.
+    pub async fn refresh(&mut self) -> Result<()> {
+        let transactions = self.fetch_transactions(None).await?;
+        let trc20_transfers = self.fetch_trc20_transfers(None).await?;
+        let cache = &self.cache;
+        let gap = [
+            (&cache.transactions, &transactions),
+            (&cache.trc20_transfers, &trc20_transfers),
+        ]
+        .into_iter()
+        .any(|(stream, page)| stream.started && page.next.is_some() && !cache.overlaps(page));
+        if gap {
+            self.cache = HistoryCache::default();
+        }
+
+        if !self.cache.transactions.started {
+            self.cache.transactions.advance(&transactions);
+        }
+        if !self.cache.trc20_transfers.started {
+            self.cache.trc20_transfers.advance(&trc20_transfers);
+        }
+        self.cache.merge(transactions);
+        self.cache.merge(trc20_transfers);
+        Ok(())
+    }
+
+    /// Fetches the next page of the stream that limits how far back the transfers go.
+    pub async fn load_more(&mut self) -> Result<()> {
+        let (transactions, trc20_transfers) =
+            (&self.cache.transactions, &self.cache.trc20_transfers);
+        if transactions.has_more() && transactions.horizon() >= trc20_transfers.horizon() {
+            let page = self.fetch_transactions(transactions.next.clone()).await?;
+            self.cache.transactions.advance(&page);
+            self.cache.merge(page);
+        } else if trc20_transfers.has_more() {
+            let page = self
+                .fetch_trc20_transfers(trc20_transfers.next.clone())
+                .await?;
+            self.cache.trc20_transfers.advance(&page);
+            self.cache.merge(page);
+        }
+        Ok(())
+    }
+
+    async fn fetch_transactions(&self, fingerprint: Option<String>) -> Result<Page> {
+        let response = self.get("transactions", fingerprint, Vec::new()).await?;
+        Ok(parse_transactions(
+            response,
+            &self.address,
+            &self.usdt_smart_contract_address,
+        ))
+    }
+
+    async fn fetch_trc20_transfers(&self, fingerprint: Option<String>) -> Result<Page> {
+        let query = vec![(
+            "contract_address",
+            self.usdt_smart_contract_address.to_string(),
+        )];
+        let response = self.get("transactions/trc20", fingerprint, query).await?;
+        Ok(parse_trc20_transfers(response, &self.address))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        fingerprint: Option<String>,
+        mut query: Vec<(&str, String)>,
+    ) -> Result<ApiResponse<T>> {
+        query.push(("limit", PAGE_LIMIT.to_string()));
+        query.push(("only_confirmed", "true".to_string()));
+        query.extend(fingerprint.map(|fingerprint| ("fingerprint", fingerprint)));
+        let response = self
+            .http
+            .get(format!(
+                "{}/v1/accounts/{}/{path}",
+                self.tx_api, self.address
+            ))
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response)
+    }
+
+    /// The transfers fetched so far, to [`TronHistory::open`] the history again later.
+    pub fn cache(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.cache)?)
+    }
+}
+
+/// The base URL of the HTTP API of the network.
+pub(crate) fn tx_api(config: &CwuConfig) -> Result<String> {
+    Ok(config
+        .tron
+        .tx_api
+        .as_ref()
+        .ok_or(TronError::TxApiIsNotSet)?
+        .trim_end_matches('/')
+        .to_string())
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryCache {
+    /// Newest first.
+    transfers: Vec<Transfer>,
+    /// The fees of the smart contract calls of the address, by transaction id.
+    fees: BTreeMap<String, i64>,
+    transactions: Stream,
+    trc20_transfers: Stream,
+}
+
+impl HistoryCache {
+    fn transfers(&self) -> Vec<Transfer> {
+        let horizon = self
+            .transactions
+            .horizon()
+            .max(self.trc20_transfers.horizon());
+        self.transfers
+            .iter()
+            .filter(|transfer| transfer.timestamp >= horizon)
+            .map(|transfer| {
+                let mut transfer = transfer.clone();
+                if transfer.direction == Direction::Out && transfer.fee.is_none() {
+                    transfer.fee = self.fees.get(&transfer.tx_id).map(|fee| trx(*fee));
+                }
+                transfer
+            })
+            .collect()
+    }
+
+    fn overlaps(&self, page: &Page) -> bool {
+        page.tx_ids.iter().any(|tx_id| {
+            self.fees.contains_key(tx_id)
+                || self
+                    .transfers
+                    .iter()
+                    .any(|transfer| &transfer.tx_id == tx_id)
+        })
+    }
+
+    fn merge(&mut self, page: Page) {
+        for transfer in page.transfers {
+            let known = self.transfers.iter().any(|known| {
+                known.tx_id == transfer.tx_id
+                    && known.asset == transfer.asset
+                    && known.direction == transfer.direction
+            });
+            if !known {
+                self.transfers.push(transfer);
+            }
+        }
+        self.transfers.sort_by(|a, b| {
+            b.timestamp
+                .cmp(&a.timestamp)
+                .then_with(|| a.tx_id.cmp(&b.tx_id))
+        });
+        self.fees.extend(page.fees);
+    }
+}
+
+/// How far back one stream of the API was fetched.
+#[derive(Default, Serialize, Deserialize)]
+struct Stream {
+    started: bool,
+    /// The fingerprint of the next page, `None` once the stream is exhausted.
+    next: Option<String>,
+    /// The timestamp of the oldest entry fetched.
+    oldest: Option<u64>,
+}
+
+impl Stream {
+    fn has_more(&self) -> bool {
+        !self.started || self.next.is_some()
+    }
+
+    /// The timestamp from which all the entries of the stream are known.
+    fn horizon(&self) -> u64 {
+        if self.has_more() {
+            self.oldest.unwrap_or(u64::MAX)
+        } else {
+            0
+        }
+    }
+
+    fn advance(&mut self, page: &Page) {
+        self.started = true;
+        self.next = page.next.clone();
+        self.oldest = self.oldest.into_iter().chain(page.oldest).min();
+    }
+}
+
+/// A page of a stream, parsed.
+#[derive(Default)]
+struct Page {
+    transfers: Vec<Transfer>,
+    fees: Vec<(String, i64)>,
+    /// All the transactions of the page, transfers or not.
+    tx_ids: Vec<String>,
+    oldest: Option<u64>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    #[serde(default = "Vec::new")]
+    data: Vec<T>,
+    #[serde(default)]
+    meta: ApiMeta,
+}
+
+#[derive(Default, Deserialize)]
+struct ApiMeta {
+    fingerprint: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiTransaction {
+    #[serde(rename = "txID", default)]
+    tx_id: String,
+    #[serde(default)]
+    block_timestamp: u64,
+    #[serde(default)]
+    ret: Vec<ApiResult>,
+    raw_data: Option<ApiRawData>,
+}
+
+#[derive(Deserialize)]
+struct ApiResult {
+    #[serde(rename = "contractRet")]
+    contract_ret: Option<String>,
+    #[serde(default)]
+    fee: i64,
+}
+
+#[derive(Deserialize)]
+struct ApiRawData {
+    #[serde(default)]
+    contract: Vec<ApiContract>,
+}
+
+#[derive(Deserialize)]
+struct ApiContract {
+    #[serde(rename = "type")]
+    kind: String,
+    parameter: ApiParameter,
+}
+
+#[derive(Deserialize)]
+struct ApiParameter {
+    value: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct ApiTransferContract {
+    amount: i64,
+    owner_address: TronAddress,
+    to_address: TronAddress,
+}
+
+#[derive(Deserialize)]
+struct ApiTriggerSmartContract {
+    contract_address: TronAddress,
+    #[serde(default)]
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct ApiTrc20Transfer {
+    transaction_id: String,
+    token_info: ApiTokenInfo,
+    block_timestamp: u64,
+    from: TronAddress,
+    to: TronAddress,
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct ApiTokenInfo {
+    decimals: u32,
+}
+
+/// Parses the transactions of `address`: TRX transfers, and the fees of smart contract calls.
+///
+/// Successful USDT transfers are listed by the TRC20 stream, failed ones only show up here.
+fn parse_transactions(
+    response: ApiResponse<ApiTransaction>,
+    address: &TronAddress,
+    usdt_smart_contract_address: &TronAddress,
+) -> Page {
+    let mut page = Page {
+        next: response.meta.fingerprint,
+        ..Page::default()
+    };
+    for transaction in response.data {
+        // Internal transactions are listed too, without a transaction id.
+        let contract = transaction
+            .raw_data
+            .and_then(|raw_data| raw_data.contract.into_iter().next());
+        let Some(contract) = contract.filter(|_| !transaction.tx_id.is_empty()) else {
+            continue;
+        };
+        let timestamp = transaction.block_timestamp / 1000;
+        page.oldest = page.oldest.into_iter().chain([timestamp]).min();
+        page.tx_ids.push(transaction.tx_id.clone());
+
+        let result = transaction.ret.first();
+        let fee = result.map_or(0, |result| result.fee);
+        let status = match result.and_then(|result| result.contract_ret.as_deref()) {
+            None | Some("SUCCESS") => TransferStatus::Success,
+            Some(error) => TransferStatus::Failed(error.to_string()),
+        };
+        match contract.kind.as_str() {
+            "TransferContract" => {
+                let Ok(value) =
+                    serde_json::from_value::<ApiTransferContract>(contract.parameter.value)
+                else {
+                    continue;
+                };
+                let outgoing = &value.owner_address == address;
+                page.transfers.push(Transfer {
+                    network: Network::Tron,
+                    tx_id: transaction.tx_id,
+                    direction: if outgoing {
+                        Direction::Out
+                    } else {
+                        Direction::In
+                    },
+                    asset: Asset::Native,
+                    amount: format_units(value.amount as u128, TRX_DECIMALS),
+                    counterparty: if outgoing {
+                        value.to_address
+                    } else {
+                        value.owner_address
+                    }
+                    .to_string(),
+                    fee: outgoing.then(|| trx(fee)),
+                    status,
+                    timestamp,
+                });
+            }
+            "TriggerSmartContract" => {
+                if let TransferStatus::Failed(_) = status
+                    && let Ok(value) =
+                        serde_json::from_value::<ApiTriggerSmartContract>(contract.parameter.value)
+                    && &value.contract_address == usdt_smart_contract_address
+                    && let Some((to, units)) = decode_transfer(&value.data)
+                {
+                    page.transfers.push(Transfer {
+                        network: Network::Tron,
+                        tx_id: transaction.tx_id.clone(),
+                        direction: Direction::Out,
+                        asset: Asset::Usdt,
+                        amount: format_units(units, crate::USDT_DECIMALS),
+                        counterparty: to.to_string(),
+                        fee: Some(trx(fee)),
+                        status,
+                        timestamp,
+                    });
+                }
+                page.fees.push((transaction.tx_id, fee));
+            }
+            _ => {}
+        }
+    }
+    page
+}
+
+/// Parses the USDT transfers in and out of `address`, which all succeeded.
+fn parse_trc20_transfers(response: ApiResponse<ApiTrc20Transfer>, address: &TronAddress) -> Page {
+    let mut page = Page {
+        next: response.meta.fingerprint,
+        ..Page::default()
+    };
+    for transfer in response.data {
+        let timestamp = transfer.block_timestamp / 1000;
+        page.oldest = page.oldest.into_iter().chain([timestamp]).min();
+        page.tx_ids.push(transfer.transaction_id.clone());
+        let Ok(units) = transfer.value.parse::<u128>() else {
+            continue;
+        };
+        if transfer.kind != "Transfer" {
+            continue;
+        }
+        let outgoing = &transfer.from == address;
+        page.transfers.push(Transfer {
+            network: Network::Tron,
+            tx_id: transfer.transaction_id,
+            direction: if outgoing {
+                Direction::Out
+            } else {
+                Direction::In
+            },
+            asset: Asset::Usdt,
+            amount: format_units(units, transfer.token_info.decimals),
+            counterparty: if outgoing { transfer.to } else { transfer.from }.to_string(),
+            // The fee of outgoing transfers comes from the transactions stream.
+            fee: None,
+            status: TransferStatus::Success,
+            timestamp,
+        });
+    }
+    page
+}
+
+fn trx(sun: i64) -> String {
+    format_units(sun as u128, TRX_DECIMALS)
+}
+
+/// Decodes the call data of a TRC20 `transfer(address,uint256)` into its recipient and amount.
+pub(crate) fn decode_transfer(data: &str) -> Option<(TronAddress, u128)> {
+    let arguments = data.strip_prefix(TRANSFER_SELECTOR)?;
+    if arguments.len() < 128 || !arguments.is_ascii() {
+        return None;
+    }
+    let mut to = hex::decode(&arguments[24..64]).ok()?;
+    to.insert(0, 0x41);
+    // Amounts beyond 128 bits aren't USDT amounts.
+    if arguments[64..96].bytes().any(|digit| digit != b'0') {
+        return None;
+    }
+    let units = u128::from_str_radix(&arguments[96..128], 16).ok()?;
+    Some((TronAddress::try_from(to).ok()?, units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP";
+    const OTHER: &str = "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX";
+    const USDT: &str = "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t";
+
+    fn address(address: &str) -> TronAddress {
+        address.parse().unwrap()
+    }
+
+    fn hex(address: &str) -> String {
+        hex::encode(self::address(address).to_bytes())
+    }
+
+    fn transactions_page() -> Page {
+        let response = serde_json::from_value(serde_json::json!({
+            "data": [
+                {
+                    "txID": "aa",
+                    "block_timestamp": 1_700_000_300_000u64,
+                    "ret": [{"contractRet": "OUT_OF_ENERGY", "fee": 8_000_000}],
+                    "raw_data": {"contract": [{
+                        "type": "TriggerSmartContract",
+                        "parameter": {"value": {
+                            "owner_address": hex(ADDRESS),
+                            "contract_address": hex(USDT),
+                            "data": format!(
+                                "{TRANSFER_SELECTOR}{:0>64}{:064x}",
+                                &hex(OTHER)[2..],
+                                2_500_000
+                            ),
+                        }},
+                    }]},
+                },
+                {
+                    "txID": "bb",
+                    "block_timestamp": 1_700_000_200_000u64,
+                    "ret": [{"contractRet": "SUCCESS", "fee": 13_000_000}],
+                    "raw_data": {"contract": [{
+                        "type": "TriggerSmartContract",
+                        "parameter": {"value": {
+                            "owner_address": hex(ADDRESS),
+                            "contract_address": hex(USDT),
+                        }},
+                    }]},
+                },
+                {"internal_tx_id": "cc", "block_timestamp": 1_700_000_150_000u64},
+                {
+                    "txID": "dd",
+                    "block_timestamp": 1_700_000_100_000u64,
+                    "ret": [{"contractRet": "SUCCESS"}],
+                    "raw_data": {"contract": [{
+                        "type": "TransferContract",
+                        "parameter": {"value": {
+                            "amount": 12_500_000,
+                            "owner_address": hex(OTHER),
+                            "to_address": hex(ADDRESS),
+                        }},
+                    }]},
+                },
+            ],
+            "meta": {"fingerprint": "next"},
+        }))
+        .unwrap();
+        parse_transactions(response, &address(ADDRESS), &address(USDT))
+    }
+
+    fn trc20_transfers_page() -> Page {
+        let response = serde_json::from_value(serde_json::json!({
+            "data": [
+                {
+                    "transaction_id": "bb",
+                    "token_info": {"symbol": "USDT", "decimals": 6},
+                    "block_timestamp": 1_700_000_200_000u64,
+                    "from": ADDRESS,
+                    "to": OTHER,
+                    "type": "Transfer",
+                    "value": "3000000",
+                },
+            ],
+            "meta": {},
+        }))
+        .unwrap();
+        parse_trc20_transfers(response, &address(ADDRESS))
+    }
+
+    #[test]
+    fn test_parse_transactions() {
+        let page = transactions_page();
+        assert_eq!(page.tx_ids, ["aa", "bb", "dd"]);
+        assert_eq!(page.oldest, Some(1_700_000_100));
+        assert_eq!(page.next.as_deref(), Some("next"));
+        assert_eq!(
+            page.fees,
+            [
+                ("aa".to_string(), 8_000_000),
+                ("bb".to_string(), 13_000_000)
+            ]
+        );
+
+        let [failed, received] = page.transfers.as_slice() else {
+            panic!("{:?}", page.transfers);
+        };
+        assert_eq!(failed.asset, Asset::Usdt);
+        assert_eq!(failed.amount, "2.5");
+        assert_eq!(failed.counterparty, OTHER);
+        assert_eq!(
+            failed.status,
+            TransferStatus::Failed("OUT_OF_ENERGY".to_string())
+        );
+        assert_eq!(received.direction, Direction::In);
+        assert_eq!(received.amount, "12.5");
+        assert_eq!(received.counterparty, OTHER);
+        assert_eq!(received.fee, None);
+    }
+
+    #[test]
+    fn test_history_cache_merges_streams() {
+        let mut cache = HistoryCache::default();
+        let transactions = transactions_page();
+        let trc20_transfers = trc20_transfers_page();
+        cache.transactions.advance(&transactions);
+        cache.trc20_transfers.advance(&trc20_transfers);
+        cache.merge(transactions);
+        cache.merge(trc20_transfers);
+
+        // The TRC20 stream is exhausted, the transactions one is known down to "dd".
+        let transfers = cache.transfers();
+        let tx_ids: Vec<_> = transfers.iter().map(|t| t.tx_id.as_str()).collect();
+        assert_eq!(tx_ids, ["aa", "bb", "dd"]);
+        assert_eq!(transfers[1].amount, "3");
+        assert_eq!(transfers[1].fee.as_deref(), Some("13"));
+        assert!(cache.overlaps(&trc20_transfers_page()));
+
+        // Merging a page twice doesn't duplicate transfers.
+        cache.merge(transactions_page());
+        assert_eq!(cache.transfers().len(), 3);
+    }
+
+    #[test]
+    fn test_history_is_held_back_by_the_slowest_stream() {
+        let mut cache = HistoryCache::default();
+        let transactions = transactions_page();
+        cache.transactions.advance(&transactions);
+        cache.merge(transactions);
+        assert!(cache.transfers().is_empty());
+
+        let mut trc20_transfers = trc20_transfers_page();
+        trc20_transfers.next = Some("next".to_string());
+        cache.trc20_transfers.advance(&trc20_transfers);
+        cache.merge(trc20_transfers);
+        let tx_ids: Vec<_> = cache.transfers().into_iter().map(|t| t.tx_id).collect();
+        assert_eq!(tx_ids, ["aa", "bb"]);
+    }
+}
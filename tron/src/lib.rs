@@ -1,7 +1,19 @@
+mod account;
+mod amount;
+mod history;
+mod offline;
+mod raw;
 mod result;
+mod staking;
+mod tracker;
 mod tron;
+mod voting;
 
+pub use amount::{TRX_DECIMALS, USDT_DECIMALS, format_units, parse_trx, parse_units, parse_usdt};
+pub use history::TronHistory;
+pub use offline::OfflineTransaction;
 pub use result::{Result, TronError};
+pub use tracker::TronTracker;
 pub use tron::Tron;
 
 #[cfg(test)]
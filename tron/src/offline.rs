@@ -0,0 +1,340 @@
+//! # Offline Transactions
+//!
+//! A transaction is built on an online machine, written unsigned to a file, carried to an
+//! offline machine holding the wallet to be signed, and carried back to be broadcast.
+//!
+//! The file holds the transaction serialized by `tronic` next to a human-readable
+//! description of it. Every time a file is read, the id of the transaction is recomputed
+//! from its raw data, encoded again as the protocol encodes it, and the description is
+//! decoded again from its contract, so a file altered on the way is refused rather than
+//! signed or broadcast.
+
+use crate::{
+    Result, TRX_DECIMALS, TronError, USDT_DECIMALS, format_units,
+    history::decode_transfer,
+    raw::encode_raw,
+    tron::{from_tronic, usdt_contract},
+};
+use cwu_core_address::TronAddress;
+use cwu_model::{Asset, Network, TransactionDetails};
+use cwu_settings::CwuConfig;
+use k256::ecdsa::{SigningKey, signature::hazmat::PrehashSigner};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fs, path::Path};
+use tronic::{
+    client::{
+        Client,
+        pending::{ManualSigning, PendingTransaction},
+    },
+    domain::{Hash32, contract::ContractType, transaction::Transaction, trx::Trx},
+    provider::{TronProvider, mock::MockProvider},
+    signer::LocalSigner,
+};
+
+/// The version of the file format.
+const VERSION: u32 = 1;
+
+/// A transaction on its way between the online and the offline machine.
+#[derive(Debug)]
+pub struct OfflineTransaction {
+    details: TransactionDetails,
+    serialized: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OfflineTransactionFile {
+    version: u32,
+    details: TransactionDetails,
+    /// The hex encoded transaction, as serialized by `tronic`.
+    transaction: String,
+}
+
+impl OfflineTransaction {
+    /// Wraps a transaction built online, `usdt` being the configured USDT contract.
+    pub(crate) fn new<P: TronProvider>(
+        pending: &PendingTransaction<'_, P, LocalSigner, ManualSigning>,
+        usdt: &TronAddress,
+    ) -> Result<Self> {
+        Ok(Self {
+            details: details(&pending.transaction(), pending.txid(), usdt)?,
+            serialized: pending.serialize(),
+        })
+    }
+
+    pub fn details(&self) -> &TransactionDetails {
+        &self.details
+    }
+
+    /// Reads a transaction file, checking that the transaction matches its id and its
+    /// description. Doesn't need the network.
+    pub async fn read(path: &Path, config: &CwuConfig) -> Result<Self> {
+        let file: OfflineTransactionFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if file.version != VERSION {
+            return Err(invalid(format!("unsupported version {}", file.version)));
+        }
+        let serialized = hex::decode(&file.transaction)
+            .map_err(|_| invalid("the transaction isn't hex encoded"))?;
+        let details = verify(&serialized, &usdt_contract(config)?).await?;
+        if details != file.details {
+            return Err(invalid("the details don't match the transaction"));
+        }
+        Ok(Self {
+            details,
+            serialized,
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = OfflineTransactionFile {
+            version: VERSION,
+            details: self.details.clone(),
+            transaction: hex::encode(&self.serialized),
+        };
+        fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// Signs the transaction with the raw 32 bytes `private_key` of the sender. Doesn't need
+    /// the network.
+    pub async fn sign(&self, private_key: &[u8]) -> Result<Self> {
+        if self.details.signatures > 0 {
+            return Err(invalid("the transaction is already signed"));
+        }
+        let signing_key = SigningKey::from_slice(private_key)?;
+        let signer = from_tronic(&LocalSigner::from(signing_key.clone()).address())?;
+        if signer.to_string() != self.details.from {
+            return Err(invalid(format!(
+                "the transaction is sent from {}, not from {signer}",
+                self.details.from
+            )));
+        }
+
+        let client = offline_client().await;
+        let mut pending = self.pending(&client)?;
+        let signature = signing_key.sign_prehash(pending.txid().as_ref())?;
+        pending
+            .sign_tx_unchecked(
+                *signing_key.verifying_key(),
+                |_| async move { Ok(signature) },
+            )
+            .await?;
+        Ok(Self {
+            details: TransactionDetails {
+                signatures: pending.transaction().signature.len(),
+                ..self.details.clone()
+            },
+            serialized: pending.serialize(),
+        })
+    }
+
+    /// Checks that the transaction is signed, only by its sender.
+    pub(crate) fn verify_signatures<P: TronProvider>(
+        &self,
+        pending: &PendingTransaction<'_, P, LocalSigner, ManualSigning>,
+    ) -> Result<()> {
+        let signatures = pending.transaction().signature;
+        if signatures.is_empty() {
+            return Err(invalid("the transaction isn't signed"));
+        }
+        for signature in signatures {
+            let signer = from_tronic(&signature.recover_address(&pending.txid())?)?;
+            if signer.to_string() != self.details.from {
+                return Err(invalid(format!("the transaction is signed by {signer}")));
+            }
+        }
+        Ok(())
+    }
+
+    /// The transaction, bound to `client`.
+    pub(crate) fn pending<'a, P: TronProvider>(
+        &self,
+        client: &'a Client<P, LocalSigner>,
+    ) -> Result<PendingTransaction<'a, P, LocalSigner, ManualSigning>> {
+        Ok(PendingTransaction::try_deserialize(
+            client,
+            &self.serialized,
+        )?)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> TronError {
+    TronError::InvalidOfflineTransaction(reason.into())
+}
+
+/// A client for the offline steps, which never reach a node.
+async fn offline_client() -> Client<MockProvider, LocalSigner> {
+    Client::builder()
+        .provider(MockProvider::new().await)
+        .signer(LocalSigner::rand())
+        .build()
+}
+
+/// Checks that a serialized transaction has the id it claims and describes it.
+async fn verify(serialized: &[u8], usdt: &TronAddress) -> Result<TransactionDetails> {
+    let client = offline_client().await;
+    let pending = PendingTransaction::<_, _, ManualSigning>::try_deserialize(&client, serialized)?;
+    let transaction = pending.transaction();
+    let details = details(&transaction, pending.txid(), usdt)?;
+    // The id of a transaction is the hash of its encoded raw data, the part being signed.
+    if Sha256::digest(encode_raw(&transaction.raw)?)[..] != *pending.txid().as_ref() {
+        return Err(invalid("the transaction id doesn't match its content"));
+    }
+    Ok(details)
+}
+
+/// Describes a TRX transfer, or a USDT transfer through the `usdt` contract, the only
+/// transactions the wallet exports.
+fn details(
+    transaction: &Transaction,
+    tx_id: Hash32,
+    usdt: &TronAddress,
+) -> Result<TransactionDetails> {
+    let unsupported = || invalid("only TRX and USDT transfers are supported");
+    let [contract] = transaction.raw.contract.as_slice() else {
+        return Err(unsupported());
+    };
+    let (from, to, asset, amount, fee_limit) = match &contract.contract_type {
+        ContractType::TransferContract(transfer) => (
+            from_tronic(&transfer.owner_address)?,
+            from_tronic(&transfer.to_address)?,
+            Asset::Native,
+            format_units(sun(transfer.amount)?, TRX_DECIMALS),
+            None,
+        ),
+        ContractType::TriggerSmartContract(call)
+            if from_tronic(&call.contract_address)? == *usdt && call.call_value == Trx::ZERO =>
+        {
+            let (to, units) = decode_transfer(&call.data).ok_or_else(unsupported)?;
+            (
+                from_tronic(&call.owner_address)?,
+                to,
+                Asset::Usdt,
+                format_units(units, USDT_DECIMALS),
+                Some(format_units(sun(transaction.raw.fee_limit)?, TRX_DECIMALS)),
+            )
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(TransactionDetails {
+        network: Network::Tron,
+        tx_id: hex::encode(tx_id.as_ref()),
+        from: from.to_string(),
+        to: to.to_string(),
+        asset,
+        amount,
+        fee_limit,
+        expires_at: u64::try_from(transaction.raw.expiration.unix_timestamp())
+            .map_err(|_| invalid("negative expiration"))?,
+        signatures: transaction.signature.len(),
+    })
+}
+
+fn sun(amount: Trx) -> Result<u128> {
+    u128::try_from(amount.to_sun()).map_err(|_| invalid("negative amount"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::block_provider::{PRIVATE_KEY, block_client, owner};
+    use crate::tron::to_tronic;
+    use time::OffsetDateTime;
+    use tronic::domain::contract::{Contract, TransferContract};
+
+    /// An unsigned transfer of 1.5 TRX from the address of `PRIVATE_KEY`, built and
+    /// serialized by `tronic`.
+    async fn unsigned_transfer(to: &TronAddress) -> Vec<u8> {
+        let client = block_client().await;
+        let owner = to_tronic(&owner()).unwrap();
+        let transfer = Contract {
+            contract_type: ContractType::TransferContract(TransferContract {
+                owner_address: owner,
+                to_address: to_tronic(to).unwrap(),
+                amount: 1_500_000.into(),
+            }),
+            ..Default::default()
+        };
+        let block = client.provider().get_now_block().await.unwrap();
+        let mut transaction = Transaction::new(transfer, &block, Default::default());
+        transaction.raw.timestamp = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        transaction.raw.expiration = OffsetDateTime::from_unix_timestamp(1_700_043_200).unwrap();
+        PendingTransaction::<_, _, ManualSigning>::new(&client, transaction, owner, Trx::ZERO, true)
+            .await
+            .unwrap()
+            .serialize()
+    }
+
+    #[tokio::test]
+    async fn test_offline_signing() {
+        let to: TronAddress = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP".parse().unwrap();
+        let usdt: TronAddress = "TXYZopYRdj2D9XRtbG411XZZ3kM5VkAeBf".parse().unwrap();
+        let serialized = unsigned_transfer(&to).await;
+        let details = verify(&serialized, &usdt).await.unwrap();
+        assert_eq!(details.from, owner().to_string());
+        assert_eq!(details.to, to.to_string());
+        assert_eq!(details.asset, Asset::Native);
+        assert_eq!(details.amount, "1.5");
+        assert_eq!(details.fee_limit, None);
+        assert_eq!(details.expires_at, 1_700_043_200);
+        assert_eq!(details.signatures, 0);
+
+        let unsigned = OfflineTransaction {
+            details,
+            serialized,
+        };
+        assert!(unsigned.sign(&[8; 32]).await.is_err());
+        let signed = unsigned.sign(&PRIVATE_KEY).await.unwrap();
+        assert_eq!(signed.details.signatures, 1);
+        assert_eq!(signed.details.tx_id, unsigned.details.tx_id);
+        assert!(signed.sign(&PRIVATE_KEY).await.is_err());
+        assert_eq!(
+            verify(&signed.serialized, &usdt).await.unwrap(),
+            signed.details
+        );
+
+        let client = offline_client().await;
+        assert!(
+            unsigned
+                .verify_signatures(&unsigned.pending(&client).unwrap())
+                .is_err()
+        );
+        signed
+            .verify_signatures(&signed.pending(&client).unwrap())
+            .unwrap();
+
+        // The amount ends the contract, right before the timestamp.
+        let mut tampered = unsigned.serialized.clone();
+        let amount = tampered.len() - 8;
+        tampered[amount] ^= 1;
+        assert!(verify(&tampered, &usdt).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_details_must_match_the_transaction() {
+        let dir = std::env::temp_dir().join(format!("cwu-offline-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("unsigned.json");
+        let config = CwuConfig::test_new("src/tests/testing.toml");
+        let to = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP".parse().unwrap();
+        let serialized = unsigned_transfer(&to).await;
+        let transaction = OfflineTransaction {
+            details: verify(&serialized, &usdt_contract(&config).unwrap())
+                .await
+                .unwrap(),
+            serialized,
+        };
+
+        transaction.write(&path).unwrap();
+        let read = OfflineTransaction::read(&path, &config).await.unwrap();
+        assert_eq!(read.details(), transaction.details());
+
+        let mut file: OfflineTransactionFile =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        file.details.amount = "1500".to_string();
+        fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+        assert!(OfflineTransaction::read(&path, &config).await.is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+//! # Raw Transactions
+//!
+//! The raw data of a transaction is the part being signed, and its id is the hash of the
+//! raw data as the protocol encodes it. `tronic` doesn't expose that encoding, so it is
+//! mirrored here for the transactions the wallet builds.
+
+use crate::{Result, TronError};
+use prost::Message as _;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use tronic::{
+    client::{
+        Client,
+        pending::{ManualSigning, PendingTransaction},
+    },
+    domain::{contract::ContractType, transaction::RawTransaction, trx::Trx},
+    provider::TronProvider,
+    signer::LocalSigner,
+};
+
+/// The length of what `tronic` serializes ahead of a transaction.
+const SERIALIZED_HEADER_LEN: usize = 32 + 21 + 8 + 1;
+
+/// Sets the fee limit of an unsigned smart contract call to `limit`, the most it may burn,
+/// refusing the call when the fee limit `tronic` set from its estimated energy is above it.
+///
+/// `tronic` has no setter for the fee limit, so the transaction is serialized again with
+/// the new one and its id computed again.
+pub(crate) fn set_fee_limit<'a, P: TronProvider>(
+    client: &'a Client<P, LocalSigner>,
+    pending: &PendingTransaction<'_, P, LocalSigner, ManualSigning>,
+    limit: Trx,
+) -> Result<PendingTransaction<'a, P, LocalSigner, ManualSigning>> {
+    let mut transaction = pending.transaction();
+    if transaction.raw.fee_limit > limit {
+        return Err(TronError::FeeLimitExceeded {
+            required: transaction.raw.fee_limit,
+            limit,
+        });
+    }
+    if !transaction.signature.is_empty() {
+        return Err(unsupported("the transaction is already signed"));
+    }
+    transaction.raw.fee_limit = limit;
+    let raw_data = encode_raw(&transaction.raw)?;
+    // The id, the owner, the additional fee and whether TRX may pay for resources come
+    // before the encoded transaction.
+    let mut serialized = pending.serialize();
+    serialized.truncate(SERIALIZED_HEADER_LEN);
+    serialized[..32].copy_from_slice(&Sha256::digest(&raw_data));
+    serialized.extend(protocol::Transaction { raw_data }.encode_to_vec());
+    Ok(PendingTransaction::try_deserialize(client, &serialized)?)
+}
+
+fn unsupported(reason: impl Into<String>) -> TronError {
+    TronError::UnsupportedTransaction(reason.into())
+}
+
+/// Encodes the raw data of a transaction as `tronic` does to compute its id.
+pub(crate) fn encode_raw(raw: &RawTransaction) -> Result<Vec<u8>> {
+    if !raw.auths.is_empty() {
+        return Err(unsupported("authorities aren't supported"));
+    }
+    let contract = raw
+        .contract
+        .iter()
+        .map(|contract| {
+            let (name, value) = match &contract.contract_type {
+                ContractType::TransferContract(transfer) => (
+                    "TransferContract",
+                    protocol::TransferContract {
+                        owner_address: transfer.owner_address.as_bytes().to_vec(),
+                        to_address: transfer.to_address.as_bytes().to_vec(),
+                        amount: transfer.amount.to_sun(),
+                    }
+                    .encode_to_vec(),
+                ),
+                ContractType::TriggerSmartContract(call) => (
+                    "TriggerSmartContract",
+                    protocol::TriggerSmartContract {
+                        owner_address: call.owner_address.as_bytes().to_vec(),
+                        contract_address: call.contract_address.as_bytes().to_vec(),
+                        call_value: call.call_value.to_sun(),
+                        data: call.data.clone().to_bytes_vec(),
+                        call_token_value: call.call_token_value.to_sun(),
+                        token_id: call.token_id,
+                    }
+                    .encode_to_vec(),
+                ),
+                _ => {
+                    return Err(unsupported(
+                        "only TRX transfers and smart contract calls are supported",
+                    ));
+                }
+            };
+            Ok(protocol::Contract {
+                r#type: contract.contract_type.id(),
+                parameter: Some(protocol::Any {
+                    type_url: format!("type.googleapis.com/protocol.{name}"),
+                    value,
+                }),
+                provider: contract.provider.clone(),
+                contract_name: contract.contract_name.clone().into(),
+                permission_id: contract.permission_id,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(protocol::Raw {
+        ref_block_bytes: raw.ref_block_bytes.into(),
+        ref_block_num: raw.ref_block_num,
+        ref_block_hash: raw.ref_block_hash.into(),
+        expiration: millis(raw.expiration),
+        data: raw.data.clone().into(),
+        contract,
+        scripts: raw.scripts.clone(),
+        timestamp: millis(raw.timestamp),
+        fee_limit: raw.fee_limit.to_sun(),
+    }
+    .encode_to_vec())
+}
+
+/// A time as the protocol holds it, in milliseconds since the epoch.
+fn millis(time: OffsetDateTime) -> i64 {
+    (time.unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+/// The protobuf messages of the Tron protocol making up the raw data of the transactions
+/// the wallet builds.
+mod protocol {
+    /// An unsigned transaction, its raw data already encoded.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(super) struct Transaction {
+        #[prost(bytes = "vec", tag = "1")]
+        pub raw_data: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(super) struct Raw {
+        #[prost(bytes = "vec", tag = "1")]
+        pub ref_block_bytes: Vec<u8>,
+        #[prost(int64, tag = "3")]
+        pub ref_block_num: i64,
+        #[prost(bytes = "vec", tag = "4")]
+        pub ref_block_hash: Vec<u8>,
+        #[prost(int64, tag = "8")]
+        pub expiration: i64,
+        #[prost(bytes = "vec", tag = "10")]
+        pub data: Vec<u8>,
+        #[prost(message, repeated, tag = "11")]
+        pub contract: Vec<Contract>,
+        #[prost(bytes = "vec", tag = "12")]
+        pub scripts: Vec<u8>,
+        #[prost(int64, tag = "14")]
+        pub timestamp: i64,
+        #[prost(int64, tag = "18")]
+        pub fee_limit: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(super) struct Contract {
+        #[prost(int32, tag = "1")]
+        pub r#type: i32,
+        #[prost(message, optional, tag = "2")]
+        pub parameter: Option<Any>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub provider: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        pub contract_name: Vec<u8>,
+        #[prost(int32, tag = "5")]
+        pub permission_id: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(super) struct Any {
+        #[prost(string, tag = "1")]
+        pub type_url: String,
+        #[prost(bytes = "vec", tag = "2")]
+        pub value: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(super) struct TransferContract {
+        #[prost(bytes = "vec", tag = "1")]
+        pub owner_address: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub to_address: Vec<u8>,
+        #[prost(int64, tag = "3")]
+        pub amount: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(super) struct TriggerSmartContract {
+        #[prost(bytes = "vec", tag = "1")]
+        pub owner_address: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub contract_address: Vec<u8>,
+        #[prost(int64, tag = "3")]
+        pub call_value: i64,
+        #[prost(bytes = "vec", tag = "4")]
+        pub data: Vec<u8>,
+        #[prost(int64, tag = "5")]
+        pub call_token_value: i64,
+        #[prost(int64, tag = "6")]
+        pub token_id: i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::block_provider::{BlockProvider, block_client, owner};
+    use crate::tron::to_tronic;
+    use cwu_core_address::TronAddress;
+    use tronic::domain::{
+        contract::{Contract, TriggerSmartContract},
+        transaction::Transaction,
+    };
+
+    /// An unsigned transfer of 2.5 USDT through `usdt` from the address of `owner()`, its
+    /// fee limit set by `tronic` from the energy `BlockProvider` estimates.
+    async fn unsigned_usdt_transfer<'a>(
+        client: &'a Client<BlockProvider, LocalSigner>,
+        to: &TronAddress,
+        usdt: &TronAddress,
+    ) -> PendingTransaction<'a, BlockProvider, LocalSigner, ManualSigning> {
+        let owner = to_tronic(&owner()).unwrap();
+        let data = format!(
+            "a9059cbb{:0>64}{:064x}",
+            hex::encode(&to.to_bytes()[1..]),
+            2_500_000
+        );
+        let call = Contract {
+            contract_type: ContractType::TriggerSmartContract(TriggerSmartContract {
+                owner_address: owner,
+                contract_address: to_tronic(usdt).unwrap(),
+                data: hex::decode(data).unwrap().into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let block = client.provider().get_now_block().await.unwrap();
+        let transaction = Transaction::new(call, &block, Default::default());
+        PendingTransaction::new(client, transaction, owner, Trx::ZERO, true)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fee_limit_is_the_configured_one() {
+        let client = block_client().await;
+        let to: TronAddress = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP".parse().unwrap();
+        let usdt: TronAddress = "TXYZopYRdj2D9XRtbG411XZZ3kM5VkAeBf".parse().unwrap();
+        let pending = unsigned_usdt_transfer(&client, &to, &usdt).await;
+        // One and a half times the estimated 30 000 energy, at 210 sun each.
+        assert_eq!(
+            pending.transaction().raw.fee_limit,
+            Trx::from_sun(9_450_000)
+        );
+        assert!(matches!(
+            set_fee_limit(&client, &pending, Trx::from_sun(9_000_000)),
+            Err(TronError::FeeLimitExceeded { .. })
+        ));
+
+        let limited = set_fee_limit(&client, &pending, Trx::from_sun(100_000_000)).unwrap();
+        let transaction = limited.transaction();
+        assert_eq!(transaction.raw.fee_limit, Trx::from_sun(100_000_000));
+        assert_eq!(
+            transaction.raw.contract[0].contract_type,
+            pending.transaction().raw.contract[0].contract_type
+        );
+        assert_ne!(limited.txid(), pending.txid());
+        assert_eq!(
+            Sha256::digest(encode_raw(&transaction.raw).unwrap())[..],
+            *limited.txid().as_ref()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use tronic::domain::trx::Trx;
+
 #[derive(thiserror::Error, Debug)]
 pub enum TronError {
     #[error("Tronic error: {0}")]
@@ -9,8 +11,38 @@ pub enum TronError {
     #[error("An error occurred: {0}")]
     Err(Box<dyn std::error::Error + Send + Sync + 'static>),
 
+    #[error("Invalid amount: '{0}'")]
+    InvalidAmount(String),
+
+    #[error("The transaction may burn up to {required} in fees, above the fee limit of {limit}")]
+    FeeLimitExceeded { required: Trx, limit: Trx },
+
+    #[error("Unsupported transaction: {0}")]
+    UnsupportedTransaction(String),
+
+    #[error("Transaction API URL is not set")]
+    TxApiIsNotSet,
+
+    #[error("Transaction API error: {0}")]
+    TxApiError(#[from] reqwest::Error),
+
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("{0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    #[error("Invalid offline transaction: {0}")]
+    InvalidOfflineTransaction(String),
+
+    #[error("{0}")]
+    SignatureError(#[from] k256::ecdsa::Error),
+
     #[error("USDT smart contract address is not set")]
     UsdtSmartContractAddressIsNotSet,
+
+    #[error("{0}")]
+    AddressError(#[from] cwu_core_address::AddressError),
 }
 
 impl From<Box<dyn std::error::Error + Send + Sync + 'static>> for TronError {
@@ -0,0 +1,262 @@
+//! # Stake 2.0
+//!
+//! TRX staked for bandwidth or energy gives the account that resource every day instead of
+//! burning TRX for it. Unstaking is not immediate: the TRX stops giving resources at once,
+//! but can only be withdrawn back to the balance once unlocked, 14 days later on mainnet.
+//! An account has at most 32 amounts being unstaked at a time.
+//!
+//! Staked resources can also be delegated to other addresses, which use them as their own,
+//! e.g. a treasury paying the energy of deposit addresses. A delegation can be locked for
+//! a while, during which the owner can't take the resource back.
+
+use crate::{Result, TRX_DECIMALS, format_units, tron::from_tronic};
+use cwu_core_address::TronAddress;
+use cwu_model::{Delegation, Network, Resource, Staking, Unstaking};
+use serde::Deserialize;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tronic::domain::{
+    account::{Account, DelegatedResource, UnFreezeV2},
+    contract::ResourceCode,
+    trx::Trx,
+};
+
+/// How long a block takes to produce.
+const BLOCK_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Sums what an account stakes and unstakes, as of `now`.
+pub(crate) fn staking(account: &Account, now: OffsetDateTime) -> Staking {
+    let staked = |resource| {
+        account
+            .frozen_v2
+            .iter()
+            .filter(|frozen| frozen.freeze_type == resource)
+            .map(|frozen| frozen.amount)
+            .sum()
+    };
+    let (unlocked, mut locked): (Vec<&UnFreezeV2>, Vec<_>) = account
+        .unfrozen_v2
+        .iter()
+        .partition(|unfrozen| unfrozen.unfreeze_expire_time <= now);
+    locked.sort_by_key(|unfrozen| unfrozen.unfreeze_expire_time);
+    let unstaking = locked
+        .into_iter()
+        .filter_map(|unfrozen| {
+            Some(Unstaking {
+                resource: resource(unfrozen.unfreeze_type)?,
+                amount: trx(unfrozen.unfreeze_amount),
+                unlocks_at: u64::try_from(unfrozen.unfreeze_expire_time.unix_timestamp()).ok()?,
+            })
+        })
+        .collect();
+    Staking {
+        network: Network::Tron,
+        bandwidth: trx(staked(ResourceCode::Bandwidth)),
+        energy: trx(staked(ResourceCode::Energy)),
+        delegated_bandwidth: trx(account.delegated_frozen_v2_balance_for_bandwidth),
+        delegated_energy: trx(account
+            .account_resource
+            .delegated_frozen_v2_balance_for_energy),
+        unstaking,
+        withdrawable: trx(unlocked
+            .into_iter()
+            .map(|unfrozen| unfrozen.unfreeze_amount)
+            .sum()),
+    }
+}
+
+/// The delegations of a list of delegated resources, each delegating bandwidth, energy or
+/// both, as of `now`.
+pub(crate) fn delegations(
+    resources: &[DelegatedResource],
+    now: OffsetDateTime,
+) -> Result<Vec<Delegation>> {
+    let mut delegations = Vec::new();
+    for delegated in resources {
+        for (resource, amount, expires) in [
+            (
+                Resource::Bandwidth,
+                delegated.frozen_balance_for_bandwidth,
+                delegated.expire_time_for_bandwidth,
+            ),
+            (
+                Resource::Energy,
+                delegated.frozen_balance_for_energy,
+                delegated.expire_time_for_energy,
+            ),
+        ] {
+            if amount <= Trx::ZERO {
+                continue;
+            }
+            delegations.push(Delegation {
+                from: from_tronic(&delegated.from)?.to_string(),
+                to: from_tronic(&delegated.to)?.to_string(),
+                resource,
+                amount: trx(amount),
+                locked_until: (expires > now)
+                    .then(|| u64::try_from(expires.unix_timestamp()).ok())
+                    .flatten(),
+            });
+        }
+    }
+    Ok(delegations)
+}
+
+/// The lock period of a delegation, which the node counts in blocks: `tronic` passes the
+/// whole seconds of the duration it is given through as that count.
+pub(crate) fn lock_period(lock: Duration) -> time::Duration {
+    let blocks = lock.as_secs().div_ceil(BLOCK_INTERVAL.as_secs());
+    time::Duration::seconds(i64::try_from(blocks).unwrap_or(i64::MAX))
+}
+
+#[derive(Deserialize)]
+struct ApiDelegatableSize {
+    /// Absent when nothing can be delegated.
+    #[serde(default)]
+    max_size: i64,
+}
+
+/// The most TRX staked for `resource` that `owner` can delegate, asked through the HTTP API
+/// at `tx_api`: the gRPC client doesn't expose it.
+pub(crate) async fn delegatable(
+    http: &reqwest::Client,
+    tx_api: &str,
+    owner: &TronAddress,
+    resource: Resource,
+) -> Result<Trx> {
+    let size: ApiDelegatableSize = http
+        .post(format!("{tx_api}/wallet/getcandelegatedmaxsize"))
+        .json(&serde_json::json!({
+            "owner_address": owner.to_string(),
+            "type": resource_code(resource) as i32,
+            "visible": true,
+        }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(Trx::from_sun(size.max_size))
+}
+
+pub(crate) fn resource_code(resource: Resource) -> ResourceCode {
+    match resource {
+        Resource::Bandwidth => ResourceCode::Bandwidth,
+        Resource::Energy => ResourceCode::Energy,
+    }
+}
+
+/// The resource of a Stake 2.0 code, `None` for the legacy tron power.
+fn resource(code: ResourceCode) -> Option<Resource> {
+    match code {
+        ResourceCode::Bandwidth => Some(Resource::Bandwidth),
+        ResourceCode::Energy => Some(Resource::Energy),
+        ResourceCode::TronPower => None,
+    }
+}
+
+fn trx(amount: Trx) -> String {
+    format_units(amount.to_sun().max(0) as u128, TRX_DECIMALS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tron::to_tronic;
+    use tronic::domain::account::FreezeV2;
+
+    #[test]
+    fn test_staking() {
+        let at = |timestamp| OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
+        let unfrozen = |resource, sun, unlocks_at| UnFreezeV2 {
+            unfreeze_type: resource,
+            unfreeze_amount: Trx::from_sun(sun),
+            unfreeze_expire_time: at(unlocks_at),
+        };
+        let account = Account {
+            frozen_v2: vec![
+                FreezeV2 {
+                    freeze_type: ResourceCode::Bandwidth,
+                    amount: Trx::from_sun(0),
+                },
+                FreezeV2 {
+                    freeze_type: ResourceCode::Energy,
+                    amount: Trx::from_sun(1_500_000_000),
+                },
+                FreezeV2 {
+                    freeze_type: ResourceCode::TronPower,
+                    amount: Trx::from_sun(7_000_000),
+                },
+            ],
+            unfrozen_v2: vec![
+                unfrozen(ResourceCode::Energy, 30_000_000, 1_700_900_000),
+                unfrozen(ResourceCode::Bandwidth, 2_500_000, 1_700_000_000),
+                unfrozen(ResourceCode::Energy, 10_000_000, 1_700_100_000),
+                unfrozen(ResourceCode::Bandwidth, 1_000_000, 1_700_500_000),
+            ],
+            delegated_frozen_v2_balance_for_bandwidth: Trx::from_sun(250_000),
+            ..Default::default()
+        };
+
+        let staking = staking(&account, at(1_700_100_000));
+        assert_eq!(staking.bandwidth, "0");
+        assert_eq!(staking.energy, "1500");
+        assert_eq!(staking.delegated_bandwidth, "0.25");
+        assert_eq!(staking.delegated_energy, "0");
+        assert_eq!(staking.withdrawable, "12.5");
+        assert_eq!(
+            staking.unstaking,
+            vec![
+                Unstaking {
+                    resource: Resource::Bandwidth,
+                    amount: "1".to_string(),
+                    unlocks_at: 1_700_500_000,
+                },
+                Unstaking {
+                    resource: Resource::Energy,
+                    amount: "30".to_string(),
+                    unlocks_at: 1_700_900_000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delegations() {
+        let at = |timestamp| OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
+        let address = |address: &str| to_tronic(&address.parse().unwrap()).unwrap();
+        let treasury = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP";
+        let deposit = "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX";
+        let resources = [DelegatedResource {
+            from: address(treasury),
+            to: address(deposit),
+            frozen_balance_for_bandwidth: Trx::from_sun(0),
+            frozen_balance_for_energy: Trx::from_sun(2_000_000_000),
+            expire_time_for_energy: at(1_700_086_400),
+            ..Default::default()
+        }];
+
+        let locked = delegations(&resources, at(1_700_000_000)).unwrap();
+        assert_eq!(
+            locked,
+            vec![Delegation {
+                from: treasury.to_string(),
+                to: deposit.to_string(),
+                resource: Resource::Energy,
+                amount: "2000".to_string(),
+                locked_until: Some(1_700_086_400),
+            }]
+        );
+        let unlocked = delegations(&resources, at(1_700_086_400)).unwrap();
+        assert_eq!(unlocked[0].locked_until, None);
+
+        assert_eq!(
+            lock_period(Duration::from_secs(86_400)),
+            time::Duration::seconds(28_800)
+        );
+        assert_eq!(
+            lock_period(Duration::from_secs(4)),
+            time::Duration::seconds(2)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! A provider building transactions without a node, for the tests of the transactions the
+//! wallet builds.
+
+use crate::tron::from_tronic;
+use cwu_core_address::TronAddress;
+use k256::ecdsa::SigningKey;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use tronic::{
+    client::Client,
+    contracts::AbiEncode,
+    domain::{
+        Hash32, RecoverableSignature, account, address,
+        block::{BlockExtention, BlockHeader, RawBlockHeader},
+        contract::{
+            AccountPermissionUpdateContract, FreezeBalanceV2Contract, TriggerSmartContract,
+            UnfreezeBalanceV2Contract,
+        },
+        transaction::{self, Transaction},
+        trx::Trx,
+    },
+    provider::{TronProvider, mock::MockProvider},
+    signer::LocalSigner,
+};
+
+pub(crate) const PRIVATE_KEY: [u8; 32] = [7; 32];
+
+type TronicResult<T> = std::result::Result<T, tronic::error::Error>;
+
+/// Builds transactions from a fixed block and a fixed energy estimate, which
+/// `MockProvider` doesn't serve, and fails every other call like it.
+pub(crate) struct BlockProvider(MockProvider);
+
+#[async_trait::async_trait]
+impl TronProvider for BlockProvider {
+    async fn trasnfer_contract(
+        &self,
+        owner: address::TronAddress,
+        to: address::TronAddress,
+        amount: Trx,
+    ) -> TronicResult<transaction::TransactionExtention> {
+        self.0.trasnfer_contract(owner, to, amount).await
+    }
+    async fn trigger_smart_contract<A: AbiEncode + Send>(
+        &self,
+        owner: address::TronAddress,
+        contract: address::TronAddress,
+        call: A,
+    ) -> TronicResult<transaction::TransactionExtention> {
+        self.0.trigger_smart_contract(owner, contract, call).await
+    }
+    async fn broadcast_transaction(&self, transaction: Transaction) -> TronicResult<()> {
+        self.0.broadcast_transaction(transaction).await
+    }
+    async fn estimate_energy(&self, contract: TriggerSmartContract) -> TronicResult<i64> {
+        self.0.estimate_energy(contract).await
+    }
+    async fn get_account(&self, address: address::TronAddress) -> TronicResult<account::Account> {
+        self.0.get_account(address).await
+    }
+    async fn get_account_resources(
+        &self,
+        address: address::TronAddress,
+    ) -> TronicResult<account::AccountResourceUsage> {
+        self.0.get_account_resources(address).await
+    }
+    async fn trigger_constant_contract(
+        &self,
+        _: TriggerSmartContract,
+    ) -> TronicResult<transaction::TransactionExtention> {
+        Ok(transaction::TransactionExtention {
+            transaction: None,
+            txid: Hash32::default(),
+            constant_result: Vec::new(),
+            energy_used: 30_000,
+            energy_penalty: 0,
+        })
+    }
+    async fn get_now_block(&self) -> TronicResult<BlockExtention> {
+        let (signature, recovery_id) = SigningKey::from_slice(&PRIVATE_KEY)
+            .unwrap()
+            .sign_prehash_recoverable(&[0; 32])
+            .unwrap();
+        Ok(BlockExtention {
+            transactions: Vec::new(),
+            block_header: BlockHeader {
+                raw_data: RawBlockHeader {
+                    timestamp: OffsetDateTime::UNIX_EPOCH,
+                    tx_trie_root: Hash32::default(),
+                    parent_hash: Hash32::default(),
+                    number: 0x1234,
+                    witness_id: 0,
+                    witness_address: address::TronAddress::ZERO,
+                    version: 0,
+                    account_state_root: Hash32::default(),
+                },
+                witness_signature: RecoverableSignature::new(signature, recovery_id),
+            },
+            blockid: Hash32::from([0xab; 32]),
+        })
+    }
+    async fn account_permission_update(
+        &self,
+        contract: AccountPermissionUpdateContract,
+    ) -> TronicResult<transaction::TransactionExtention> {
+        self.0.account_permission_update(contract).await
+    }
+    async fn get_transaction_by_id(&self, txid: Hash32) -> TronicResult<Transaction> {
+        self.0.get_transaction_by_id(txid).await
+    }
+    async fn get_transaction_info(
+        &self,
+        txid: Hash32,
+    ) -> TronicResult<transaction::TransactionInfo> {
+        self.0.get_transaction_info(txid).await
+    }
+    async fn chain_parameters(&self) -> TronicResult<HashMap<String, i64>> {
+        Ok(HashMap::from([("getEnergyFee".to_string(), 210)]))
+    }
+    async fn freeze_balance(
+        &self,
+        contract: FreezeBalanceV2Contract,
+    ) -> TronicResult<transaction::TransactionExtention> {
+        self.0.freeze_balance(contract).await
+    }
+    async fn unfreeze_balance(
+        &self,
+        contract: UnfreezeBalanceV2Contract,
+    ) -> TronicResult<transaction::TransactionExtention> {
+        self.0.unfreeze_balance(contract).await
+    }
+    async fn get_reward(&self, address: address::TronAddress) -> TronicResult<Trx> {
+        self.0.get_reward(address).await
+    }
+    async fn get_delegated_resource(
+        &self,
+        from: address::TronAddress,
+        to: address::TronAddress,
+    ) -> TronicResult<Vec<account::DelegatedResource>> {
+        self.0.get_delegated_resource(from, to).await
+    }
+    async fn get_delegated_resource_account(
+        &self,
+        address: address::TronAddress,
+    ) -> TronicResult<account::DelegatedResourceAccountIndex> {
+        self.0.get_delegated_resource_account(address).await
+    }
+}
+
+/// The address of `PRIVATE_KEY`.
+pub(crate) fn owner() -> TronAddress {
+    let signing_key = SigningKey::from_slice(&PRIVATE_KEY).unwrap();
+    from_tronic(&LocalSigner::from(signing_key).address()).unwrap()
+}
+
+/// A client building transactions from `BlockProvider`.
+pub(crate) async fn block_client() -> Client<BlockProvider, LocalSigner> {
+    Client::builder()
+        .provider(BlockProvider(MockProvider::new().await))
+        .signer(LocalSigner::rand())
+        .build()
+}
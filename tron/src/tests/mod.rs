@@ -1,3 +1,5 @@
+pub(crate) mod block_provider;
+
 use std::fs::File;
 use std::io::Read;
 // https://developers.tron.network/docs/getting-testnet-tokens-on-tron
@@ -0,0 +1,206 @@
+//! # Transaction Tracker
+//!
+//! A broadcast transaction is first seen by the full node once it is in a block, and by the
+//! solidity node once that block is solidified, about 19 blocks later, when it can't be rolled
+//! back anymore. Both are asked through the HTTP API at `tx_api`: the gRPC client only talks
+//! to the full node.
+
+use crate::{Result, history::tx_api};
+use cwu_model::TransactionStatus;
+use cwu_settings::CwuConfig;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// How often the nodes are asked, about once a block.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long a transaction is tracked, enough for it to be solidified.
+const TRACK_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// The selector of the Solidity `Error(string)` revert reason.
+const ERROR_SELECTOR: &str = "08c379a0";
+
+/// Follows broadcast transactions until they are solidified or reverted.
+pub struct TronTracker {
+    http: reqwest::Client,
+    tx_api: String,
+}
+
+impl TronTracker {
+    pub fn new(config: &CwuConfig) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::new(),
+            tx_api: tx_api(config)?,
+        })
+    }
+
+    /// The current status of the transaction with the hex encoded id `tx_id`.
+    pub async fn status(&self, tx_id: &str) -> Result<TransactionStatus> {
+        let info = self.transaction_info("wallet", tx_id).await?;
+        if info.id.is_none() {
+            return Ok(TransactionStatus::Pending);
+        }
+        let block = info.block_number;
+        if let Some(reason) = info.revert_reason() {
+            return Ok(TransactionStatus::Reverted { block, reason });
+        }
+        let solidified = self.transaction_info("walletsolidity", tx_id).await?;
+        Ok(if solidified.id.is_some() {
+            TransactionStatus::Solidified { block }
+        } else {
+            TransactionStatus::Confirmed { block }
+        })
+    }
+
+    /// Polls the status of `tx_id` until it is final, reporting each change to `on_status`,
+    /// and returns the last one, which is still pending or confirmed after a timeout.
+    pub async fn track(
+        &self,
+        tx_id: &str,
+        mut on_status: impl FnMut(&TransactionStatus),
+    ) -> Result<TransactionStatus> {
+        let deadline = Instant::now() + TRACK_TIMEOUT;
+        let mut last = None;
+        loop {
+            let status = self.status(tx_id).await?;
+            if last.as_ref() != Some(&status) {
+                on_status(&status);
+            }
+            if status.is_final() || Instant::now() >= deadline {
+                return Ok(status);
+            }
+            last = Some(status);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Asks the full node, `wallet`, or the solidity node, `walletsolidity`, for the receipt
+    /// of a transaction: the API answers `{}` for transactions the node doesn't know.
+    async fn transaction_info(&self, node: &str, tx_id: &str) -> Result<ApiTransactionInfo> {
+        let info = self
+            .http
+            .post(format!("{}/{node}/gettransactioninfobyid", self.tx_api))
+            .json(&serde_json::json!({ "value": tx_id }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(info)
+    }
+}
+
+#[derive(Default, Deserialize)]
+struct ApiTransactionInfo {
+    id: Option<String>,
+    #[serde(rename = "blockNumber", default)]
+    block_number: u64,
+    #[serde(rename = "contractResult", default)]
+    contract_result: Vec<String>,
+    #[serde(default)]
+    receipt: ApiReceipt,
+    /// `FAILED` for failed transactions, absent otherwise.
+    result: Option<String>,
+    /// A hex encoded message explaining a failure.
+    #[serde(rename = "resMessage")]
+    res_message: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct ApiReceipt {
+    /// The result of smart contract calls, e.g. `SUCCESS`, `REVERT` or `OUT_OF_ENERGY`.
+    result: Option<String>,
+}
+
+impl ApiTransactionInfo {
+    /// Why the transaction failed, `None` when it succeeded.
+    fn revert_reason(&self) -> Option<String> {
+        let code = self
+            .receipt
+            .result
+            .as_deref()
+            .filter(|result| *result != "SUCCESS");
+        if code.is_none() && self.result.as_deref() != Some("FAILED") {
+            return None;
+        }
+        let message = self
+            .contract_result
+            .first()
+            .and_then(|result| decode_error(result))
+            .or_else(|| {
+                let message = hex::decode(self.res_message.as_ref()?).ok()?;
+                String::from_utf8(message)
+                    .ok()
+                    .filter(|message| !message.is_empty())
+            });
+        Some(match (code, message) {
+            (Some(code), Some(message)) => format!("{code}, {message}"),
+            (Some(code), None) => code.to_string(),
+            (None, Some(message)) => message,
+            (None, None) => "FAILED".to_string(),
+        })
+    }
+}
+
+/// Decodes the message of a Solidity `Error(string)` revert.
+fn decode_error(contract_result: &str) -> Option<String> {
+    let data = hex::decode(contract_result.strip_prefix(ERROR_SELECTOR)?).ok()?;
+    // The offset of the string, then its length and its bytes.
+    let length = usize::try_from(u64::from_be_bytes(data.get(56..64)?.try_into().ok()?)).ok()?;
+    let message = data.get(64..64usize.checked_add(length)?)?;
+    String::from_utf8(message.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(json: serde_json::Value) -> ApiTransactionInfo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_revert_reason() {
+        let success = info(serde_json::json!({
+            "id": "aa",
+            "blockNumber": 100,
+            "contractResult": [""],
+            "receipt": {"result": "SUCCESS", "energy_usage_total": 14_650},
+        }));
+        assert_eq!(success.revert_reason(), None);
+
+        // TRX transfers have no smart contract result.
+        let transfer = info(serde_json::json!({"id": "aa", "receipt": {"net_usage": 268}}));
+        assert_eq!(transfer.revert_reason(), None);
+
+        let out_of_energy = info(serde_json::json!({
+            "id": "aa",
+            "receipt": {"result": "OUT_OF_ENERGY"},
+            "result": "FAILED",
+            "resMessage": hex::encode("Not enough energy for 'SSTORE' operation executing"),
+        }));
+        assert_eq!(
+            out_of_energy.revert_reason().as_deref(),
+            Some("OUT_OF_ENERGY, Not enough energy for 'SSTORE' operation executing")
+        );
+
+        let message = "SafeMath: subtraction overflow";
+        let reverted = info(serde_json::json!({
+            "id": "aa",
+            "contractResult": [format!(
+                "{ERROR_SELECTOR}{:064x}{:064x}{:0<128}",
+                32,
+                message.len(),
+                hex::encode(message)
+            )],
+            "receipt": {"result": "REVERT"},
+            "result": "FAILED",
+        }));
+        assert_eq!(
+            reverted.revert_reason().as_deref(),
+            Some("REVERT, SafeMath: subtraction overflow")
+        );
+
+        assert_eq!(info(serde_json::json!({})).id, None);
+    }
+}
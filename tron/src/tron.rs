@@ -1,25 +1,59 @@
-use crate::TronError;
+use crate::{
+    OfflineTransaction, TronError,
+    account::tron_account,
+    history::tx_api,
+    raw::set_fee_limit,
+    staking::{delegatable, delegations, lock_period, resource_code, staking},
+    voting::{voting, witnesses},
+};
+use cwu_core_address::TronAddress;
+use cwu_model::{Delegation, FeeEstimate, Resource, Staking, TronAccount, Voting, Witness};
 use cwu_settings::CwuConfig;
-use std::str::FromStr;
+use time::OffsetDateTime;
 use tronic::{
-    client::Client,
+    client::{
+        Client,
+        pending::{AutoSigning, ManualSigning, PendingTransaction},
+    },
     contracts::{
         token::usdt::Usdt,
         trc20::{Trc20Calls, Trc20Contract},
     },
-    domain::{address::TronAddress, trx::Trx},
-    provider::grpc::GrpcProvider,
+    domain::{
+        Message,
+        account::AccountStatus,
+        address,
+        contract::{
+            Contract, ContractType, ResourceCode, Vote, VoteWitnessContract,
+            WithdrawBalanceContract,
+        },
+        transaction::Transaction,
+        trx::Trx,
+    },
+    provider::{TronProvider, grpc::GrpcProvider},
     signer::LocalSigner,
 };
 
-pub struct Tron<'a> {
+pub struct Tron {
     client: Client<GrpcProvider, LocalSigner>,
     // TRC20-based USDT smart contract address
-    usdt_smart_contract_address: &'a str,
+    usdt_smart_contract_address: TronAddress,
+    // The most TRX a smart contract call may burn
+    fee_limit: Trx,
+    // For the few calls only the HTTP API answers
+    http: reqwest::Client,
+    tx_api: Option<String>,
 }
 
-impl<'a> Tron<'a> {
-    pub async fn new(config: &'a CwuConfig) -> crate::Result<Self> {
+/// The fee limit of smart contract calls, in sun, when the configuration doesn't set one.
+const DEFAULT_FEE_LIMIT: i64 = 100_000_000;
+
+/// How long an exported transaction stays valid, to be carried to the offline machine and
+/// back. Tron accepts up to 24 hours.
+const OFFLINE_EXPIRATION: time::Duration = time::Duration::hours(12);
+
+impl Tron {
+    pub async fn new(config: &CwuConfig) -> crate::Result<Self> {
         let client = Client::builder()
             .provider(
                 GrpcProvider::builder()
@@ -28,41 +62,527 @@ impl<'a> Tron<'a> {
             )
             .signer(LocalSigner::rand())
             .build();
-        let usdt_smart_contract_address = config
-            .tron
-            .usdt_smart_contract_address
-            .as_ref()
-            .ok_or(TronError::UsdtSmartContractAddressIsNotSet)?;
+        let usdt_smart_contract_address = usdt_contract(config)?;
+        let fee_limit = config.tron.fee_limit.unwrap_or(DEFAULT_FEE_LIMIT);
         Ok(Self {
             client,
             usdt_smart_contract_address,
+            fee_limit: Trx::from_sun(fee_limit),
+            http: reqwest::Client::new(),
+            tx_api: tx_api(config).ok(),
         })
     }
 
-    pub async fn trx_balance(&self, address: &str) -> crate::Result<Trx> {
-        let tron_address = TronAddress::from_str(address)?;
+    pub async fn trx_balance(&self, address: &TronAddress) -> crate::Result<Trx> {
         let balance = self
             .client
             .trx_balance()
-            .address(tron_address)
+            .address(to_tronic(address)?)
             .get()
             .await?;
         Ok(balance)
     }
 
-    pub async fn usdt_balance(&self, address: &str) -> crate::Result<Usdt> {
+    pub async fn usdt_balance(&self, address: &TronAddress) -> crate::Result<Usdt> {
         let usdt_contract_tron_address =
-            Trc20Contract::<Usdt>::new(TronAddress::from_str(self.usdt_smart_contract_address)?);
-        let tron_address = TronAddress::from_str(address)?;
+            Trc20Contract::<Usdt>::new(to_tronic(&self.usdt_smart_contract_address)?);
         let balance = self
             .client
             .trc20_balance_of()
             .contract(usdt_contract_tron_address)
-            .owner(tron_address)
+            .owner(to_tronic(address)?)
             .get()
             .await?;
         Ok(balance)
     }
+
+    /// The TRX and resources of `address`, and whether it is activated.
+    pub async fn account(&self, address: &TronAddress) -> crate::Result<TronAccount> {
+        let address = to_tronic(address)?;
+        let provider = self.client.provider();
+        let account = provider.get_account(address).await?;
+        let usage = provider.get_account_resources(address).await?;
+        Ok(tron_account(&account, &usage))
+    }
+
+    /// Whether `address` exists on chain: sending TRX to an address that doesn't activates
+    /// it, which burns 1 TRX more.
+    pub async fn is_activated(&self, address: &TronAddress) -> crate::Result<bool> {
+        let status = self.client.check_account(to_tronic(address)?).await?;
+        Ok(status != AccountStatus::NotExists)
+    }
+
+    /// Sends `amount` TRX to `to`, signed with the raw 32 bytes `private_key` of the sender,
+    /// and returns the hex encoded transaction id.
+    ///
+    /// Bandwidth the sender doesn't have is paid for in TRX.
+    pub async fn send_trx(
+        &self,
+        private_key: &[u8],
+        to: &TronAddress,
+        amount: Trx,
+    ) -> crate::Result<String> {
+        let txid = self
+            .signing_client(private_key)?
+            .send_trx()
+            .to(to_tronic(to)?)
+            .amount(amount)
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// Transfers `amount` USDT to `to` through the configured TRC20 contract, signed with
+    /// the raw 32 bytes `private_key` of the sender, and returns the hex encoded transaction
+    /// id.
+    ///
+    /// Energy the sender doesn't have is paid for in TRX. The fee limit of the transaction
+    /// is the configured `fee_limit`, and the transfer is refused before being signed when
+    /// its estimated energy would cost more.
+    pub async fn send_usdt(
+        &self,
+        private_key: &[u8],
+        to: &TronAddress,
+        amount: Usdt,
+    ) -> crate::Result<String> {
+        let signer = LocalSigner::from_bytes(private_key)?;
+        let pending = self
+            .client
+            .trc20_transfer()
+            .contract(Trc20Contract::<Usdt>::new(to_tronic(
+                &self.usdt_smart_contract_address,
+            )?))
+            .owner(signer.address())
+            .to(to_tronic(to)?)
+            .amount(amount)
+            .can_spend_trx_for_fee(true)
+            .build::<ManualSigning>()
+            .await?;
+        let mut pending = set_fee_limit(&self.client, &pending, self.fee_limit)?;
+        pending.sign(&signer, &()).await?;
+        let txid = pending.broadcast().await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// Builds an unsigned transaction sending `amount` TRX from `from` to `to`, to be signed
+    /// offline.
+    pub async fn export_trx(
+        &self,
+        from: &TronAddress,
+        to: &TronAddress,
+        amount: Trx,
+    ) -> crate::Result<OfflineTransaction> {
+        let pending = self
+            .client
+            .send_trx()
+            .owner(to_tronic(from)?)
+            .to(to_tronic(to)?)
+            .amount(amount)
+            .can_spend_trx_for_fee(true)
+            .build::<ManualSigning>()
+            .await?
+            .set_expiration(OFFLINE_EXPIRATION)
+            .await?;
+        OfflineTransaction::new(&pending, &self.usdt_smart_contract_address)
+    }
+
+    /// Builds an unsigned transaction transferring `amount` USDT from `from` to `to`, to be
+    /// signed offline, with the same fee limit checks as [`Tron::send_usdt`].
+    pub async fn export_usdt(
+        &self,
+        from: &TronAddress,
+        to: &TronAddress,
+        amount: Usdt,
+    ) -> crate::Result<OfflineTransaction> {
+        let pending = self
+            .client
+            .trc20_transfer()
+            .contract(Trc20Contract::<Usdt>::new(to_tronic(
+                &self.usdt_smart_contract_address,
+            )?))
+            .owner(to_tronic(from)?)
+            .to(to_tronic(to)?)
+            .amount(amount)
+            .can_spend_trx_for_fee(true)
+            .build::<ManualSigning>()
+            .await?;
+        let pending = set_fee_limit(&self.client, &pending, self.fee_limit)?
+            .set_expiration(OFFLINE_EXPIRATION)
+            .await?;
+        OfflineTransaction::new(&pending, &self.usdt_smart_contract_address)
+    }
+
+    /// Broadcasts a transaction signed offline, once its signatures are checked to be the
+    /// sender's, and returns the hex encoded transaction id.
+    pub async fn broadcast_offline(
+        &self,
+        transaction: &OfflineTransaction,
+    ) -> crate::Result<String> {
+        let pending = transaction.pending(&self.client)?;
+        transaction.verify_signatures(&pending)?;
+        let txid = pending.broadcast().await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// What `address` stakes for bandwidth and energy, and the TRX it is unstaking.
+    pub async fn staking(&self, address: &TronAddress) -> crate::Result<Staking> {
+        let account = self
+            .client
+            .provider()
+            .get_account(to_tronic(address)?)
+            .await?;
+        Ok(staking(&account, OffsetDateTime::now_utc()))
+    }
+
+    /// Stakes `amount` TRX for `resource`, signed with the raw 32 bytes `private_key` of the
+    /// owner, and returns the hex encoded transaction id.
+    pub async fn stake(
+        &self,
+        private_key: &[u8],
+        amount: Trx,
+        resource: Resource,
+    ) -> crate::Result<String> {
+        let txid = self
+            .signing_client(private_key)?
+            .freeze_balance()
+            .amount(amount)
+            .resource(resource_code(resource))
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// Starts unstaking `amount` TRX staked for `resource`, signed with the raw 32 bytes
+    /// `private_key` of the owner, and returns the hex encoded transaction id.
+    pub async fn unstake(
+        &self,
+        private_key: &[u8],
+        amount: Trx,
+        resource: Resource,
+    ) -> crate::Result<String> {
+        let txid = self
+            .signing_client(private_key)?
+            .unfreeze_balance()
+            .amount(amount)
+            .resource(resource_code(resource))
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// Withdraws the unstaked TRX which is unlocked back to the balance, signed with the raw
+    /// 32 bytes `private_key` of the owner, and returns the hex encoded transaction id.
+    pub async fn withdraw_unstaked(&self, private_key: &[u8]) -> crate::Result<String> {
+        let client = self.signing_client(private_key)?;
+        let owner = LocalSigner::from_bytes(private_key)?.address();
+        // The node withdraws every unlocked amount, but `tronic` checks that one is unlocked
+        // for the resource it is given.
+        let now = OffsetDateTime::now_utc();
+        let resource = client
+            .provider()
+            .get_account(owner)
+            .await?
+            .unfrozen_v2
+            .iter()
+            .find(|unfrozen| unfrozen.unfreeze_expire_time <= now)
+            .map_or(ResourceCode::Bandwidth, |unfrozen| unfrozen.unfreeze_type);
+        let txid = client
+            .withdraw_unfreeze()
+            .resource(resource)
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// Cancels every unstaking in progress, signed with the raw 32 bytes `private_key` of the
+    /// owner, and returns the hex encoded transaction id. The TRX still locked is staked
+    /// again, the unlocked TRX is withdrawn.
+    pub async fn cancel_unstaking(&self, private_key: &[u8]) -> crate::Result<String> {
+        let txid = self
+            .signing_client(private_key)?
+            .cancel_all_unfreeze()
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// The resources `address` delegates to other addresses, and those delegated to it.
+    pub async fn delegations(&self, address: &TronAddress) -> crate::Result<Vec<Delegation>> {
+        let address = to_tronic(address)?;
+        let provider = self.client.provider();
+        let index = provider.get_delegated_resource_account(address).await?;
+        let mut resources = Vec::new();
+        for to in index.to_accounts {
+            resources.extend(provider.get_delegated_resource(address, to).await?);
+        }
+        for from in index.from_accounts {
+            resources.extend(provider.get_delegated_resource(from, address).await?);
+        }
+        let mut delegations = delegations(&resources, OffsetDateTime::now_utc())?;
+        delegations.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        Ok(delegations)
+    }
+
+    /// The most TRX staked for `resource` that `owner` can delegate.
+    pub async fn delegatable(&self, owner: &TronAddress, resource: Resource) -> crate::Result<Trx> {
+        let tx_api = self.tx_api.as_deref().ok_or(TronError::TxApiIsNotSet)?;
+        delegatable(&self.http, tx_api, owner, resource).await
+    }
+
+    /// Delegates `amount` TRX staked for `resource` to `to`, signed with the raw 32 bytes
+    /// `private_key` of the owner, and returns the hex encoded transaction id. When `lock`
+    /// is given, the resource can't be taken back before it has passed.
+    pub async fn delegate(
+        &self,
+        private_key: &[u8],
+        to: &TronAddress,
+        amount: Trx,
+        resource: Resource,
+        lock: Option<std::time::Duration>,
+    ) -> crate::Result<String> {
+        let txid = self
+            .signing_client(private_key)?
+            .delegate()
+            .receiver(to_tronic(to)?)
+            .amount(amount)
+            .resource(resource_code(resource))
+            .maybe_lock_period(lock.map(lock_period))
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// Takes back `amount` TRX staked for `resource` and delegated to `to`, signed with the
+    /// raw 32 bytes `private_key` of the owner, and returns the hex encoded transaction id.
+    pub async fn undelegate(
+        &self,
+        private_key: &[u8],
+        to: &TronAddress,
+        amount: Trx,
+        resource: Resource,
+    ) -> crate::Result<String> {
+        let txid = self
+            .signing_client(private_key)?
+            .undelegate()
+            .receiver(to_tronic(to)?)
+            .amount(amount)
+            .resource(resource_code(resource))
+            .can_spend_trx_for_fee(true)
+            .build::<AutoSigning>()
+            .await?
+            .broadcast(&())
+            .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// The `count` witnesses with the most votes, the most voted first.
+    pub async fn witnesses(&self, count: usize) -> crate::Result<Vec<Witness>> {
+        let tx_api = self.tx_api.as_deref().ok_or(TronError::TxApiIsNotSet)?;
+        witnesses(&self.http, tx_api, count).await
+    }
+
+    /// The votes `address` casts, and the rewards they earned.
+    pub async fn voting(&self, address: &TronAddress) -> crate::Result<Voting> {
+        let address = to_tronic(address)?;
+        let provider = self.client.provider();
+        let account = provider.get_account(address).await?;
+        let tron_power = provider
+            .get_account_resources(address)
+            .await?
+            .tron_power_limit;
+        let reward = provider.get_reward(address).await?;
+        voting(&account, tron_power, reward, OffsetDateTime::now_utc())
+    }
+
+    /// Votes for witnesses, `votes` being how many votes each gets, signed with the raw
+    /// 32 bytes `private_key` of the owner, and returns the hex encoded transaction id. The
+    /// votes replace all the previous ones.
+    pub async fn vote(
+        &self,
+        private_key: &[u8],
+        votes: &[(TronAddress, u64)],
+    ) -> crate::Result<String> {
+        let owner = LocalSigner::from_bytes(private_key)?.address();
+        let votes = votes
+            .iter()
+            .map(|(witness, count)| {
+                Ok(Vote {
+                    vote_address: to_tronic(witness)?,
+                    vote_count: i64::try_from(*count)
+                        .map_err(|_| TronError::InvalidAmount(count.to_string()))?,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+        self.broadcast_contract(
+            private_key,
+            ContractType::VoteWitnessContract(VoteWitnessContract {
+                owner_address: owner,
+                votes,
+                ..Default::default()
+            }),
+        )
+        .await
+    }
+
+    /// Claims the rewards the votes earned back to the balance, signed with the raw 32 bytes
+    /// `private_key` of the owner, and returns the hex encoded transaction id.
+    pub async fn claim_rewards(&self, private_key: &[u8]) -> crate::Result<String> {
+        let owner = LocalSigner::from_bytes(private_key)?.address();
+        self.broadcast_contract(
+            private_key,
+            ContractType::WithdrawBalanceContract(WithdrawBalanceContract {
+                owner_address: owner,
+            }),
+        )
+        .await
+    }
+
+    /// Estimates the fee of sending `amount` TRX from `from` to `to`.
+    pub async fn estimate_trx_transfer(
+        &self,
+        from: &TronAddress,
+        to: &TronAddress,
+        amount: Trx,
+    ) -> crate::Result<FeeEstimate> {
+        let pending = self
+            .client
+            .send_trx()
+            .owner(to_tronic(from)?)
+            .to(to_tronic(to)?)
+            .amount(amount)
+            .build::<AutoSigning>()
+            .await?;
+        self.estimate(&pending, from).await
+    }
+
+    /// Estimates the fee of transferring `amount` USDT from `from` to `to`, the energy of
+    /// the call being simulated on the contract.
+    pub async fn estimate_usdt_transfer(
+        &self,
+        from: &TronAddress,
+        to: &TronAddress,
+        amount: Usdt,
+    ) -> crate::Result<FeeEstimate> {
+        let pending = self
+            .client
+            .trc20_transfer()
+            .contract(Trc20Contract::<Usdt>::new(to_tronic(
+                &self.usdt_smart_contract_address,
+            )?))
+            .owner(to_tronic(from)?)
+            .to(to_tronic(to)?)
+            .amount(amount)
+            .build::<AutoSigning>()
+            .await?;
+        self.estimate(&pending, from).await
+    }
+
+    /// Prices the resources of an unsigned transaction against the current prices and the
+    /// resources of its `owner`.
+    async fn estimate<M>(
+        &self,
+        pending: &PendingTransaction<'_, GrpcProvider, LocalSigner, M>,
+        owner: &TronAddress,
+    ) -> crate::Result<FeeEstimate> {
+        let resources = self
+            .client
+            .provider()
+            .get_account_resources(to_tronic(owner)?)
+            .await?;
+        let required = pending.estimate_transaction().await?.will_consume;
+        let energy_price = self.client.energy_price().await?;
+        let bandwidth_price = self.client.bandwidth_price().await?;
+        Ok(FeeEstimate {
+            bandwidth: required.bandwidth,
+            energy: required.energy,
+            available_bandwidth: (resources.free_net_limit - resources.free_net_used)
+                .max(resources.net_limit - resources.net_used),
+            available_energy: resources.energy_limit - resources.energy_used,
+            bandwidth_price: bandwidth_price.to_sun(),
+            energy_price: energy_price.to_sun(),
+            extra_fee: required.trx.to_sun(),
+        })
+    }
+
+    /// Signs and broadcasts a transaction of `contract`, built here for the contracts
+    /// `tronic` has no builder for, and returns the hex encoded transaction id.
+    async fn broadcast_contract(
+        &self,
+        private_key: &[u8],
+        contract: ContractType,
+    ) -> crate::Result<String> {
+        let client = self.signing_client(private_key)?;
+        let owner = LocalSigner::from_bytes(private_key)?.address();
+        let latest_block = client.provider().get_now_block().await?;
+        let transaction = Transaction::new(
+            Contract {
+                contract_type: contract,
+                ..Default::default()
+            },
+            &latest_block,
+            Message::default(),
+        );
+        let txid = PendingTransaction::<_, _, AutoSigning>::new(
+            &client,
+            transaction,
+            owner,
+            Trx::ZERO,
+            true,
+        )
+        .await?
+        .broadcast(&())
+        .await?;
+        Ok(hex::encode(Vec::<u8>::from(txid)))
+    }
+
+    /// A client signing with the raw 32 bytes `private_key`.
+    fn signing_client(
+        &self,
+        private_key: &[u8],
+    ) -> crate::Result<Client<GrpcProvider, LocalSigner>> {
+        Ok(self
+            .client
+            .clone()
+            .with_signer(LocalSigner::from_bytes(private_key)?))
+    }
+}
+
+/// The configured TRC20 USDT contract.
+pub(crate) fn usdt_contract(config: &CwuConfig) -> crate::Result<TronAddress> {
+    Ok(config
+        .tron
+        .usdt_smart_contract_address
+        .as_ref()
+        .ok_or(TronError::UsdtSmartContractAddressIsNotSet)?
+        .parse()?)
+}
+
+/// Converts an address into the `tronic` representation expected by the client.
+pub(crate) fn to_tronic(address: &TronAddress) -> crate::Result<address::TronAddress> {
+    Ok(address::TronAddress::new(address.to_bytes())?)
+}
+
+/// Converts an address of the `tronic` client back.
+pub(crate) fn from_tronic(address: &address::TronAddress) -> crate::Result<TronAddress> {
+    Ok(TronAddress::from_bytes(address.as_bytes())?)
 }
 
 #[cfg(test)]
@@ -84,37 +604,27 @@ mod tests {
     async fn test_get_trx_balance_success() {
         let config = setup_test_environment();
         let tron = Tron::new(&config).await.unwrap();
-        let balance = tron.trx_balance("TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX").await;
+        let address = "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX".parse().unwrap();
+        let balance = tron.trx_balance(&address).await;
         assert!(balance.is_ok());
     }
 
-    #[tokio::test]
-    async fn test_get_trx_balance_fail() {
-        let config = setup_test_environment();
-        let tron = Tron::new(&config).await.unwrap();
-        let balance = tron.trx_balance("invalid-address").await;
-        assert!(balance.is_err());
-        let error = balance.err().unwrap();
-        assert_eq!(error.to_string(), "Error: bad address");
+    #[test]
+    fn test_invalid_address_is_rejected_at_parse_time() {
+        assert!("invalid-address".parse::<TronAddress>().is_err());
+        assert!(
+            "TMTpzDaQrCVsE1efSyCnsENcbBj2oUThyX"
+                .parse::<TronAddress>()
+                .is_err()
+        );
     }
 
     #[tokio::test]
     async fn test_get_usdt_balance_success() {
         let config = setup_test_environment();
         let tron = Tron::new(&config).await.unwrap();
-        let balance = tron
-            .usdt_balance("TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP")
-            .await;
+        let address = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP".parse().unwrap();
+        let balance = tron.usdt_balance(&address).await;
         assert!(balance.is_ok());
     }
-
-    #[tokio::test]
-    async fn test_get_usdt_balance_fail() {
-        let config = setup_test_environment();
-        let tron = Tron::new(&config).await.unwrap();
-        let balance = tron.usdt_balance("invalid-address").await;
-        assert!(balance.is_err());
-        let error = balance.err().unwrap();
-        assert_eq!(error.to_string(), "Error: bad address");
-    }
 }
@@ -0,0 +1,188 @@
+//! # Voting
+//!
+//! Staking TRX also gives tron power, one vote per staked TRX, which earns rewards only once
+//! cast for witnesses: the 27 super representatives producing the blocks and the partners
+//! ranked after them. Each witness keeps its brokerage of the rewards and shares the rest
+//! among its voters. A vote replaces all the previous ones, and the rewards are claimed back
+//! to the balance at most once a day.
+
+use crate::{Result, TRX_DECIMALS, format_units, tron::from_tronic};
+use cwu_core_address::TronAddress;
+use cwu_model::{Network, Vote, Voting, Witness};
+use futures::future::try_join_all;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use tronic::domain::{account::Account, trx::Trx};
+
+/// How often the rewards can be claimed.
+const CLAIM_INTERVAL: time::Duration = time::Duration::days(1);
+
+/// Sums the votes of an account with `tron_power` and the `reward` they earned, as of `now`.
+pub(crate) fn voting(
+    account: &Account,
+    tron_power: i64,
+    reward: Trx,
+    now: OffsetDateTime,
+) -> Result<Voting> {
+    let votes = account
+        .votes
+        .iter()
+        .map(|vote| {
+            Ok(Vote {
+                witness: from_tronic(&vote.vote_address)?.to_string(),
+                count: vote.vote_count.max(0) as u64,
+            })
+        })
+        .collect::<Result<_>>()?;
+    let claimable_at = account.latest_withdraw_time + CLAIM_INTERVAL;
+    Ok(Voting {
+        network: Network::Tron,
+        tron_power: tron_power.max(0) as u64,
+        votes,
+        reward: format_units(reward.to_sun().max(0) as u128, TRX_DECIMALS),
+        claimable_at: (claimable_at > now)
+            .then(|| u64::try_from(claimable_at.unix_timestamp()).ok())
+            .flatten(),
+    })
+}
+
+#[derive(Deserialize)]
+struct ApiWitnesses {
+    #[serde(default)]
+    witnesses: Vec<ApiWitness>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiWitness {
+    address: String,
+    #[serde(default)]
+    url: String,
+    /// Absent when the witness has no votes.
+    #[serde(default)]
+    vote_count: i64,
+    #[serde(default)]
+    is_jobs: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiBrokerage {
+    brokerage: u8,
+}
+
+/// The `count` witnesses with the most votes, asked through the HTTP API at `tx_api`: the
+/// gRPC client doesn't expose them. Their brokerages are asked all at once.
+pub(crate) async fn witnesses(
+    http: &reqwest::Client,
+    tx_api: &str,
+    count: usize,
+) -> Result<Vec<Witness>> {
+    let list: ApiWitnesses = http
+        .post(format!("{tx_api}/wallet/listwitnesses"))
+        .json(&serde_json::json!({ "visible": true }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    try_join_all(
+        ranked(list.witnesses, count)
+            .into_iter()
+            .map(|witness| async move {
+                let address: TronAddress = witness.address.parse()?;
+                Ok(Witness {
+                    brokerage: brokerage(http, tx_api, &address).await?,
+                    address: address.to_string(),
+                    url: witness.url,
+                    votes: witness.vote_count.max(0) as u64,
+                    active: witness.is_jobs,
+                })
+            }),
+    )
+    .await
+}
+
+/// The percentage of the rewards `witness` keeps.
+async fn brokerage(http: &reqwest::Client, tx_api: &str, witness: &TronAddress) -> Result<u8> {
+    let brokerage: ApiBrokerage = http
+        .post(format!("{tx_api}/wallet/getBrokerage"))
+        .json(&serde_json::json!({
+            "address": witness.to_string(),
+            "visible": true,
+        }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(brokerage.brokerage)
+}
+
+/// The `count` witnesses with the most votes, the most voted first.
+fn ranked(mut witnesses: Vec<ApiWitness>, count: usize) -> Vec<ApiWitness> {
+    witnesses.sort_by_key(|witness| std::cmp::Reverse(witness.vote_count));
+    witnesses.truncate(count);
+    witnesses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tron::to_tronic;
+    use tronic::domain::account;
+
+    #[test]
+    fn test_voting() {
+        let at = |timestamp| OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
+        let witness = "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP";
+        let account = Account {
+            votes: vec![account::Vote {
+                vote_address: to_tronic(&witness.parse().unwrap()).unwrap(),
+                vote_count: 1_500,
+            }],
+            latest_withdraw_time: at(1_700_000_000),
+            ..Default::default()
+        };
+
+        let claiming = voting(
+            &account,
+            2_000,
+            Trx::from_sun(12_500_000),
+            at(1_700_050_000),
+        )
+        .unwrap();
+        assert_eq!(claiming.tron_power, 2_000);
+        assert_eq!(
+            claiming.votes,
+            vec![Vote {
+                witness: witness.to_string(),
+                count: 1_500,
+            }]
+        );
+        assert_eq!(claiming.reward, "12.5");
+        assert_eq!(claiming.claimable_at, Some(1_700_086_400));
+
+        let claimable = voting(&account, 2_000, Trx::ZERO, at(1_700_086_400)).unwrap();
+        assert_eq!(claimable.claimable_at, None);
+    }
+
+    #[test]
+    fn test_ranked() {
+        let list: ApiWitnesses = serde_json::from_str(
+            r#"{"witnesses": [
+                {"address": "41a1e81654258bf14f63feb2e8d1380075d45b0dac", "voteCount": 10,
+                 "url": "https://a.example"},
+                {"address": "TQnjctUA8Lue5ggrY39BouA3r6CLgxfPVP", "voteCount": 30,
+                 "url": "https://b.example", "isJobs": true},
+                {"address": "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let ranked = ranked(list.witnesses, 2);
+        let urls: Vec<_> = ranked.iter().map(|witness| witness.url.as_str()).collect();
+        assert_eq!(urls, ["https://b.example", "https://a.example"]);
+        assert!(ranked[0].is_jobs);
+        assert!(ranked[1].address.parse::<TronAddress>().is_ok());
+    }
+}
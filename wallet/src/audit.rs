@@ -0,0 +1,336 @@
+//! # Audit Log
+//!
+//! Every wallet keeps an append-only log of the operations performed with it: opening,
+//! backups, signing and sending. Each line of the log holds its sequence number, the hash
+//! of the previous line and the entry itself, encrypted with AES-256-GCM under a key
+//! derived from the wallet secrets. The sequence number and the previous hash are
+//! authenticated too, so editing, reordering or removing a line breaks the chain.
+//!
+//! The key is only available once the wallet is unlocked: without the master password the
+//! log can be neither read nor forged. Logs are named after a key identifier rather than
+//! the wallet, so a decoy wallet keeps its own log that can't be linked to the real one.
+//!
+//! Dropping lines from the end of the log leaves a valid, shorter chain. Keep the number
+//! of entries reported by [`EncryptedWallet::verify_audit_log`] somewhere else to detect
+//! that as well.
+
+use crate::{EncryptedWallet, Result, wallet::wallet_file_name};
+use cwu_core_address::NetworkAddress;
+use cwu_model::Network;
+use cwu_security_utils::{SecretBytes, decrypt_with_key, encrypt_with_key};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{Display, Formatter},
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const AUDIT_KEY_DOMAIN: &[u8] = b"cwu-audit-key";
+const AUDIT_LOG_ID_DOMAIN: &[u8] = b"cwu-audit-log-id";
+const AUDIT_LOG_SUFFIX: &str = ".cwu-audit.jsonl";
+
+/// The previous hash of the first line.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOperation {
+    Open,
+    Backup,
+    ExportBackup,
+    Sign,
+    Send,
+    ImportKey,
+    ExportXpub,
+    Stake,
+    Delegate,
+    Vote,
+}
+
+impl Display for AuditOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AuditOperation::Open => "Open",
+            AuditOperation::Backup => "Backup",
+            AuditOperation::ExportBackup => "Export backup",
+            AuditOperation::Sign => "Sign",
+            AuditOperation::Send => "Send",
+            AuditOperation::ImportKey => "Import key",
+            AuditOperation::ExportXpub => "Export extended public keys",
+            AuditOperation::Stake => "Stake",
+            AuditOperation::Delegate => "Delegate",
+            AuditOperation::Vote => "Vote",
+        };
+        f.write_str(s)
+    }
+}
+
+/// An operation to record, with the network, address and transaction it concerns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    operation: AuditOperation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    network: Option<Network>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tx_id: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(operation: AuditOperation) -> Self {
+        Self {
+            operation,
+            network: None,
+            address: None,
+            tx_id: None,
+        }
+    }
+
+    pub fn with_address(mut self, address: &NetworkAddress) -> Self {
+        self.network = Some(address.network());
+        self.address = Some(address.to_string());
+        self
+    }
+
+    pub fn with_tx_id(mut self, tx_id: &str) -> Self {
+        self.tx_id = Some(tx_id.to_string());
+        self
+    }
+
+    pub fn operation(&self) -> AuditOperation {
+        self.operation
+    }
+
+    pub fn network(&self) -> Option<Network> {
+        self.network
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn tx_id(&self) -> Option<&str> {
+        self.tx_id.as_deref()
+    }
+}
+
+/// A recorded event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch.
+    timestamp: u64,
+    #[serde(flatten)]
+    event: AuditEvent,
+}
+
+impl AuditEntry {
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn event(&self) -> &AuditEvent {
+        &self.event
+    }
+}
+
+/// A line of the log file.
+#[derive(Debug, Serialize, Deserialize)]
+struct AuditLine {
+    seq: u64,
+    prev_hash: String,
+    /// The encrypted [`AuditEntry`].
+    entry: String,
+}
+
+impl AuditLine {
+    /// The sequence number and the previous hash are bound to the encrypted entry.
+    fn aad(seq: u64, prev_hash: &str) -> Vec<u8> {
+        format!("{seq}:{prev_hash}").into_bytes()
+    }
+}
+
+/// A problem found while verifying an audit log. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditProblem {
+    /// The line can't be parsed.
+    Malformed { line: usize },
+    /// The sequence number doesn't match the position: lines were removed or reordered.
+    OutOfSequence { line: usize, seq: u64 },
+    /// The previous hash doesn't match the previous line: lines were removed or edited.
+    BrokenChain { line: usize },
+    /// The entry can't be authenticated with this wallet's key: the line was edited.
+    Tampered { line: usize },
+}
+
+impl Display for AuditProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditProblem::Malformed { line } => write!(f, "Line {line} is malformed"),
+            AuditProblem::OutOfSequence { line, seq } => {
+                write!(f, "Line {line} has sequence number {seq}")
+            }
+            AuditProblem::BrokenChain { line } => {
+                write!(f, "Line {line} does not follow the previous line")
+            }
+            AuditProblem::Tampered { line } => write!(f, "Line {line} was modified"),
+        }
+    }
+}
+
+/// The result of [`EncryptedWallet::verify_audit_log`].
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    entries: Vec<AuditEntry>,
+    problems: Vec<AuditProblem>,
+}
+
+impl AuditReport {
+    /// The entries that could be authenticated, in log order.
+    pub fn entries(&self) -> &[AuditEntry] {
+        self.entries.as_slice()
+    }
+
+    pub fn problems(&self) -> &[AuditProblem] {
+        self.problems.as_slice()
+    }
+
+    pub fn is_intact(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl EncryptedWallet {
+    /// Appends an event to the audit log of this wallet.
+    pub fn audit(&self, event: AuditEvent) -> Result<()> {
+        let key = self.audit_key();
+        let path = self.audit_log_path(&key);
+        let lines = read_lines(&path)?;
+
+        let seq = lines.len() as u64;
+        let prev_hash = lines
+            .last()
+            .map_or(GENESIS_HASH.to_string(), |l| line_hash(l));
+        let entry = AuditEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            event,
+        };
+        let entry = encrypt_with_key(
+            serde_json::to_string(&entry)?.as_bytes(),
+            &key,
+            &AuditLine::aad(seq, &prev_hash),
+        )?;
+        let line = serde_json::to_string(&AuditLine {
+            seq,
+            prev_hash,
+            entry,
+        })?;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(format!("{line}\n").as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Reads the audit log of this wallet, checking every line against the chain.
+    ///
+    /// A wallet that was never audited has an empty, intact log.
+    pub fn verify_audit_log(&self) -> Result<AuditReport> {
+        let key = self.audit_key();
+        let lines = read_lines(&self.audit_log_path(&key))?;
+
+        let mut report = AuditReport::default();
+        for (index, line) in lines.iter().enumerate() {
+            let number = index + 1;
+            let Ok(audit_line) = serde_json::from_str::<AuditLine>(line) else {
+                report
+                    .problems
+                    .push(AuditProblem::Malformed { line: number });
+                continue;
+            };
+            if audit_line.seq != index as u64 {
+                report.problems.push(AuditProblem::OutOfSequence {
+                    line: number,
+                    seq: audit_line.seq,
+                });
+            }
+            let expected_hash = match index {
+                0 => GENESIS_HASH.to_string(),
+                _ => line_hash(&lines[index - 1]),
+            };
+            if audit_line.prev_hash != expected_hash {
+                report
+                    .problems
+                    .push(AuditProblem::BrokenChain { line: number });
+            }
+
+            let aad = AuditLine::aad(audit_line.seq, &audit_line.prev_hash);
+            let entry = decrypt_with_key(&audit_line.entry, &key, &aad)
+                .ok()
+                .and_then(|json| serde_json::from_str::<AuditEntry>(json.expose_secret()).ok());
+            match entry {
+                Some(entry) => report.entries.push(entry),
+                None => report
+                    .problems
+                    .push(AuditProblem::Tampered { line: number }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// The key protecting the audit log.
+    fn audit_key(&self) -> SecretBytes {
+        self.local_key(AUDIT_KEY_DOMAIN)
+    }
+
+    /// A key for the local files of the wallet, separated by `domain`.
+    ///
+    /// It is derived from the encrypted mnemonic, which is itself only readable once the
+    /// wallet file is unlocked, so no extra key derivation is needed.
+    pub(crate) fn local_key(&self, domain: &[u8]) -> SecretBytes {
+        let mnemonic = self.mnemonic();
+        let mut mac = HmacSha256::new_from_slice(domain).expect("HMAC accepts keys of any size");
+        mac.update(mnemonic.ciphertext_b64.as_bytes());
+        mac.update(mnemonic.salt_phc.as_bytes());
+        mac.update(mnemonic.nonce_b64.as_bytes());
+        SecretBytes::from(mac.finalize().into_bytes().to_vec())
+    }
+
+    /// The log file, next to the wallet file. It is named after a public identifier of
+    /// the key rather than after the wallet.
+    fn audit_log_path(&self, key: &SecretBytes) -> PathBuf {
+        let mut mac =
+            HmacSha256::new_from_slice(key.expose_secret()).expect("HMAC accepts keys of any size");
+        mac.update(AUDIT_LOG_ID_DOMAIN);
+        let id = hex::encode(&mac.finalize().into_bytes()[..8]);
+
+        let wallet_file = wallet_file_name(self.name());
+        let dir = Path::new(&wallet_file).parent().unwrap_or(Path::new(""));
+        dir.join(format!("{id}{AUDIT_LOG_SUFFIX}"))
+    }
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn line_hash(line: &str) -> String {
+    hex::encode(Sha256::digest(line.as_bytes()))
+}
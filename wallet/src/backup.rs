@@ -0,0 +1,213 @@
+//! # Backup Bundles
+//!
+//! A backup bundle is a self-contained copy of a wallet, encrypted under its own backup
+//! password instead of the everyday master password (and keyfile). Restoring a bundle
+//! writes a fresh wallet file, protected by a new master password.
+
+use crate::{
+    EncryptedWallet, RecoveryPhrase, Result, WalletError,
+    import::parse_private_key,
+    network::key_pair_from_secret_key,
+    wallet::{derive_key_pairs, ensure_strong, write_wallet},
+};
+use bip39::Mnemonic;
+use cwu_core_address::{NetworkAddress, network_addresses};
+use cwu_model::Network;
+use cwu_security_utils::{EncryptedPayload, Keyfile, SecretString, decrypt, encrypt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use zeroize::Zeroize;
+
+const BACKUP_FORMAT: &str = "cwu-backup";
+const BACKUP_VERSION: u32 = 1;
+
+/// The on-disk bundle: only the format and the version are in plaintext.
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupBundle {
+    format: String,
+    version: u32,
+    payload: EncryptedPayload,
+}
+
+/// The encrypted part of the bundle.
+#[derive(Serialize, Deserialize)]
+struct BackupContents {
+    wallet: BackupWallet,
+    /// Hex encoded SHA-256 of the serialized `wallet`.
+    checksum: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupWallet {
+    name: String,
+    mnemonic: String,
+    passphrase: String,
+    #[serde(with = "network_addresses")]
+    addresses: HashMap<Network, NetworkAddress>,
+    /// The hex encoded private keys imported into the wallet, which the mnemonic can't
+    /// recover.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    imported_keys: BTreeMap<Network, String>,
+    /// Seconds since the Unix epoch.
+    created_at: u64,
+}
+
+impl Drop for BackupWallet {
+    fn drop(&mut self) {
+        self.mnemonic.zeroize();
+        self.passphrase.zeroize();
+        self.imported_keys.values_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+impl BackupWallet {
+    fn checksum(&self) -> Result<String> {
+        let json = SecretString::from(serde_json::to_string(self)?);
+        Ok(hex::encode(Sha256::digest(json.expose_secret().as_bytes())))
+    }
+}
+
+/// A wallet restored from a backup bundle.
+#[derive(Debug)]
+pub struct RestoredWallet {
+    pub name: String,
+    pub master_password: SecretString,
+}
+
+impl EncryptedWallet {
+    /// Exports the wallet into a backup bundle at `path`, encrypted under `backup_password`.
+    ///
+    /// The backup password must be strong: it is the only thing protecting the bundle.
+    pub fn export_backup(
+        &self,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        backup_password: &SecretString,
+        path: impl AsRef<Path>,
+    ) -> Result<()> {
+        ensure_strong(backup_password)?;
+
+        let RecoveryPhrase {
+            mnemonic,
+            passphrase,
+        } = self.backup(master_password, keyfile)?;
+        let mut imported_keys = BTreeMap::new();
+        for &network in Network::iter().filter(|&&network| self.is_imported(network)) {
+            let key_pair = self.key_pair(master_password, keyfile, network)?;
+            imported_keys.insert(network, key_pair.private_key().to_string());
+        }
+        let wallet = BackupWallet {
+            name: self.name().to_string(),
+            mnemonic: mnemonic.expose_secret().to_string(),
+            passphrase: passphrase.expose_secret().to_string(),
+            addresses: self.addresses().clone(),
+            imported_keys,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        };
+        let contents = BackupContents {
+            checksum: wallet.checksum()?,
+            wallet,
+        };
+        let contents_json = SecretString::from(serde_json::to_string(&contents)?);
+        let bundle = BackupBundle {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            payload: encrypt(contents_json.expose_secret(), backup_password)?,
+        };
+
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        file.write_all(serde_json::to_string_pretty(&bundle)?.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Verifies a backup bundle and restores it as a new wallet file.
+    ///
+    /// The wallet is saved as `wallet_name`, or under its original name if `None`, and
+    /// is never written over an existing wallet. Like [`EncryptedWallet::create`], a random
+    /// master password is generated unless one is given.
+    pub fn import_backup(
+        path: impl AsRef<Path>,
+        backup_password: &SecretString,
+        wallet_name: Option<&str>,
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<RestoredWallet> {
+        let bundle: BackupBundle = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if bundle.format != BACKUP_FORMAT {
+            return Err(WalletError::InvalidBackup("unknown format".to_string()));
+        }
+        if bundle.version != BACKUP_VERSION {
+            return Err(WalletError::UnsupportedVersion(bundle.version));
+        }
+
+        let contents_json = decrypt(&bundle.payload, backup_password)?;
+        let contents: BackupContents = serde_json::from_str(contents_json.expose_secret())?;
+        drop(contents_json);
+        let wallet = &contents.wallet;
+        if wallet.checksum()? != contents.checksum {
+            return Err(WalletError::InvalidBackup("checksum mismatch".to_string()));
+        }
+
+        // Make sure the secrets still produce the addresses the wallet was known by. Networks
+        // added after the backup was made have no address to compare with.
+        let mnemonic = Mnemonic::parse(wallet.mnemonic.as_str())?;
+        let passphrase = SecretString::from(wallet.passphrase.as_str());
+        let mut key_pairs = derive_key_pairs(&mnemonic, &passphrase)?;
+        key_pairs.retain(|(network, _)| !wallet.imported_keys.contains_key(network));
+        for (&network, private_key) in &wallet.imported_keys {
+            let secret_key = parse_private_key(&SecretString::from(private_key.as_str()))?;
+            key_pairs.push((network, key_pair_from_secret_key(network, &secret_key)?));
+        }
+        for (network, key_pair) in key_pairs {
+            let Some(address) = wallet.addresses.get(&network) else {
+                continue;
+            };
+            if address != &key_pair.network_address(network)? {
+                return Err(WalletError::InvalidBackup(format!(
+                    "address mismatch for network {network}"
+                )));
+            }
+        }
+
+        let name = match wallet_name {
+            Some(name) => name.to_string(),
+            None => plain_name(&wallet.name)?.to_string(),
+        };
+        let master_password = write_wallet(&name, &mnemonic, passphrase, master_password, keyfile)?;
+        for (&network, private_key) in &wallet.imported_keys {
+            let private_key = SecretString::from(private_key.as_str());
+            EncryptedWallet::import_key(&name, &master_password, keyfile, network, &private_key)?;
+        }
+
+        Ok(RestoredWallet {
+            name,
+            master_password,
+        })
+    }
+}
+
+/// The wallet name stored in a bundle, which must not be taken for a path elsewhere.
+pub(crate) fn plain_name(name: &str) -> Result<&str> {
+    if name.is_empty()
+        || name == "."
+        || name.contains("..")
+        || name.contains(['/', '\\'])
+        || Path::new(name).is_absolute()
+    {
+        return Err(WalletError::InvalidBackup(format!(
+            "invalid wallet name '{name}'"
+        )));
+    }
+    Ok(name)
+}
@@ -0,0 +1,65 @@
+//! # Wallet Cache
+//!
+//! Data fetched for a wallet, e.g. its transaction history, can be cached next to the wallet
+//! file. Like the audit log, every entry is encrypted under a key derived from the wallet
+//! secrets and named after a key identifier, so neither the content nor the file name tells
+//! which wallet or address it belongs to, and a decoy wallet keeps its own entries.
+//!
+//! An entry is written to a temporary file first and renamed over the old one, so an
+//! interrupted write leaves the previous entry in place.
+
+use crate::{EncryptedWallet, Result, wallet::wallet_file_name};
+use cwu_security_utils::{SecretBytes, SecretString, decrypt_with_key, encrypt_with_key};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const CACHE_KEY_DOMAIN: &[u8] = b"cwu-cache-key";
+const CACHE_SUFFIX: &str = ".cwu-cache";
+
+type HmacSha256 = Hmac<Sha256>;
+
+impl EncryptedWallet {
+    /// The cached entry `name`, `None` when there is none or it can't be read: a cache only
+    /// saves requests.
+    pub fn read_cache(&self, name: &str) -> Option<SecretString> {
+        let key = self.local_key(CACHE_KEY_DOMAIN);
+        let sealed = fs::read_to_string(self.cache_path(&key, name)).ok()?;
+        decrypt_with_key(sealed.trim(), &key, name.as_bytes()).ok()
+    }
+
+    /// Caches `contents` as the entry `name`, replacing the previous one.
+    pub fn write_cache(&self, name: &str, contents: &str) -> Result<()> {
+        let key = self.local_key(CACHE_KEY_DOMAIN);
+        let path = self.cache_path(&key, name);
+        let sealed = encrypt_with_key(contents.as_bytes(), &key, name.as_bytes())?;
+
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temp_path)?;
+        file.write_all(sealed.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// The file of the entry `name`, next to the wallet file.
+    fn cache_path(&self, key: &SecretBytes, name: &str) -> PathBuf {
+        let mut mac =
+            HmacSha256::new_from_slice(key.expose_secret()).expect("HMAC accepts keys of any size");
+        mac.update(name.as_bytes());
+        let id = hex::encode(&mac.finalize().into_bytes()[..8]);
+
+        let wallet_file = wallet_file_name(self.name());
+        let dir = Path::new(&wallet_file).parent().unwrap_or(Path::new(""));
+        dir.join(format!("{id}{CACHE_SUFFIX}"))
+    }
+}
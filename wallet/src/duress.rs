@@ -0,0 +1,90 @@
+//! # Duress Passwords
+//!
+//! A wallet file can hold a second, decoy wallet opened by a duress password. Both live in
+//! the encrypted slots of the same file, so nothing in the file tells whether a decoy exists,
+//! and opening either takes the same time.
+//!
+//! The plaintext header of a wallet with a decoy describes the decoy: anyone looking at the
+//! file without a password only sees the low-value wallet. The decoy is generated like any
+//! wallet and nothing records which slot holds it, so even the duress password doesn't
+//! tell the decoy from a wallet.
+
+use crate::{
+    EncryptedWallet, Result, WalletError,
+    file::{Slot, WalletFile},
+    wallet::{EncryptedWalletV1, generate_mnemonic, new_password, wallet_file_name},
+};
+use cwu_security_utils::{Keyfile, SecretString};
+use serde_json::to_string_pretty;
+
+impl EncryptedWallet {
+    /// Adds a decoy wallet, opened by `duress_password`, to an existing wallet file and
+    /// returns the duress password.
+    ///
+    /// The decoy is a brand-new wallet with its own mnemonic, generated like the wallet: fund
+    /// it with a small amount to make it plausible. An existing decoy is replaced. When
+    /// `duress_password` is `None`, a random one is generated.
+    ///
+    /// From then on the plaintext header describes the decoy: whatever reads the wallet
+    /// without a password, like the wallet list, watch-only addresses or balances, shows the
+    /// decoy, not the wallet.
+    ///
+    /// Nothing in the decoy tells it from a wallet, so whichever wallet `master_password`
+    /// opens is kept: given the duress password, the decoy becomes the wallet and the wallet
+    /// it hid is dropped.
+    pub fn add_decoy(
+        wallet_name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        duress_password: Option<SecretString>,
+    ) -> Result<SecretString> {
+        if duress_password
+            .as_ref()
+            .is_some_and(|duress| duress.expose_secret() == master_password.expose_secret())
+        {
+            return Err(WalletError::SameDuressPassword);
+        }
+
+        let path = wallet_file_name(wallet_name);
+        let wallet_file = WalletFile::read(&path)?;
+        wallet_file.keyfile_requirement().check(keyfile)?;
+        let wallet_json = wallet_file.decrypt(master_password, keyfile)?;
+        let wallet: EncryptedWalletV1 = serde_json::from_str(wallet_json.expose_secret())?;
+
+        let words = wallet.mnemonic(master_password, keyfile)?;
+        let (mnemonic, passphrase) = generate_mnemonic(words.language(), words.word_count())?;
+        drop(words);
+        let duress_password = new_password(duress_password, &passphrase)?;
+        if duress_password.expose_secret() == master_password.expose_secret() {
+            return Err(WalletError::SameDuressPassword);
+        }
+        let decoy = EncryptedWalletV1::encrypt(
+            wallet.name(),
+            &mnemonic,
+            passphrase,
+            &duress_password,
+            keyfile,
+        )?;
+        let decoy_json = SecretString::from(to_string_pretty(&decoy)?);
+
+        // Keep the creation time, so the header doesn't tell when the decoy was added.
+        let created_at = wallet_file.header().map(|header| header.created_at());
+        let wallet_file = WalletFile::new(
+            decoy.header(created_at),
+            keyfile,
+            &[
+                Slot {
+                    plaintext: &wallet_json,
+                    password: master_password,
+                },
+                Slot {
+                    plaintext: &decoy_json,
+                    password: &duress_password,
+                },
+            ],
+        )?;
+        wallet_file.write(path)?;
+
+        Ok(duress_password)
+    }
+}
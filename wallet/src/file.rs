@@ -0,0 +1,356 @@
+use crate::{Result, WalletError, WalletHeader};
+use cwu_security_utils::{
+    EncryptedPayload, EncryptionError, Keyfile, SecretString, decrypt_with_aad, encrypt_with_aad,
+};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+const CURRENT_FILE_VERSION: u32 = 4;
+
+/// The number of encrypted slots in a wallet file: the wallet and, optionally, a decoy.
+pub(crate) const SLOT_COUNT: usize = 2;
+
+/// Slot plaintexts are padded to a multiple of this size, so they all have the same length.
+///
+/// One more block is kept free, so a slot can be updated in place without growing.
+const SLOT_PADDING: usize = 1024;
+
+/// The on-disk representation of a wallet.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum WalletFile {
+    V4(WalletFileV4),
+    V3(WalletFileV3),
+    V2(WalletFileV2),
+    /// Legacy files: a bare encrypted payload, protected by the master password only.
+    V1(EncryptedPayload),
+}
+
+/// A wallet file with several encrypted slots, each opened by its own password.
+///
+/// Every file has [`SLOT_COUNT`] slots in random order. Unused slots are filled with
+/// random data, so a file with a decoy wallet looks exactly like one without.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WalletFileV4 {
+    version: u32,
+    header: WalletHeader,
+    keyfile_required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keyfile_fingerprint: Option<String>,
+    slots: Vec<EncryptedPayload>,
+}
+
+/// A wallet file with a plaintext, integrity-protected header.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WalletFileV3 {
+    version: u32,
+    header: WalletHeader,
+    keyfile_required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keyfile_fingerprint: Option<String>,
+    payload: EncryptedPayload,
+}
+
+/// A wallet file with a plaintext envelope describing how to open the payload.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WalletFileV2 {
+    version: u32,
+    keyfile_required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keyfile_fingerprint: Option<String>,
+    payload: EncryptedPayload,
+}
+
+/// Everything in the plaintext part of a wallet file that the payload is bound to.
+#[derive(Serialize)]
+struct AuthenticatedData<'a> {
+    version: u32,
+    header: &'a WalletHeader,
+    keyfile_required: bool,
+    keyfile_fingerprint: Option<&'a str>,
+}
+
+/// The plaintext of a slot and the password it is encrypted with.
+pub(crate) struct Slot<'a> {
+    pub(crate) plaintext: &'a SecretString,
+    pub(crate) password: &'a SecretString,
+}
+
+impl WalletFile {
+    /// Builds a wallet file out of up to [`SLOT_COUNT`] slots, all bound to the header.
+    pub(crate) fn new(
+        header: WalletHeader,
+        keyfile: Option<&Keyfile>,
+        slots: &[Slot<'_>],
+    ) -> Result<Self> {
+        assert!(
+            !slots.is_empty() && slots.len() <= SLOT_COUNT,
+            "Invalid number of slots"
+        );
+        let keyfile_fingerprint = keyfile.map(Keyfile::fingerprint);
+        let aad = authenticated_data(
+            CURRENT_FILE_VERSION,
+            &header,
+            keyfile.is_some(),
+            keyfile_fingerprint.as_deref(),
+        )?;
+
+        // A decrypted slot comes with its padding, which is dropped before padding again.
+        let plaintexts: Vec<&str> = slots
+            .iter()
+            .map(|slot| slot.plaintext.expose_secret().trim_end())
+            .collect();
+        let padded_len = plaintexts
+            .iter()
+            .map(|plaintext| plaintext.len())
+            .max()
+            .unwrap_or_default()
+            .next_multiple_of(SLOT_PADDING)
+            + SLOT_PADDING;
+        let mut payloads = Vec::with_capacity(SLOT_COUNT);
+        for (slot, plaintext) in slots.iter().zip(plaintexts) {
+            // Trailing whitespace is ignored by the JSON parser.
+            let mut padded = String::with_capacity(padded_len);
+            padded.push_str(plaintext);
+            padded.extend(std::iter::repeat_n(' ', padded_len - plaintext.len()));
+            let padded = SecretString::from(padded);
+            payloads.push(encrypt_with_aad(
+                padded.expose_secret(),
+                slot.password,
+                keyfile,
+                &aad,
+            )?);
+        }
+        while payloads.len() < SLOT_COUNT {
+            payloads.push(EncryptedPayload::random_like(&payloads[0])?);
+        }
+        payloads.shuffle(&mut rand::rng());
+
+        Ok(WalletFile::V4(WalletFileV4 {
+            version: CURRENT_FILE_VERSION,
+            header,
+            keyfile_required: keyfile.is_some(),
+            keyfile_fingerprint,
+            slots: payloads,
+        }))
+    }
+
+    pub(crate) fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let file = serde_json::from_str(&fs::read_to_string(path)?)?;
+        match &file {
+            WalletFile::V4(v4) if v4.version != 4 => {
+                Err(WalletError::UnsupportedVersion(v4.version))
+            }
+            WalletFile::V3(v3) if v3.version != 3 => {
+                Err(WalletError::UnsupportedVersion(v3.version))
+            }
+            WalletFile::V2(v2) if v2.version != 2 => {
+                Err(WalletError::UnsupportedVersion(v2.version))
+            }
+            _ => Ok(file),
+        }
+    }
+
+    /// Replaces the wallet file at `path`.
+    ///
+    /// The file holds the only copy of the keys, so it is written next to the old one first
+    /// and then renamed over it: a crash never leaves a truncated wallet behind.
+    pub(crate) fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temp_path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Writes a new wallet file, failing with [`std::io::ErrorKind::AlreadyExists`] rather
+    /// than replacing an existing one.
+    pub(crate) fn write_new(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Decrypts the slot opened by `password`.
+    ///
+    /// Every slot is tried, whichever matches, so opening the wallet and opening the decoy
+    /// take the same time.
+    pub(crate) fn decrypt(
+        &self,
+        password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<SecretString> {
+        let aad = self.aad()?;
+        let mut opened = None;
+        let mut error = None;
+        for payload in self.payloads() {
+            match decrypt_with_aad(payload, password, keyfile, &aad) {
+                Ok(plaintext) if opened.is_none() => opened = Some(plaintext),
+                Ok(_) => {}
+                Err(e) => error = Some(e),
+            }
+        }
+        match (opened, error) {
+            (Some(plaintext), _) => Ok(plaintext),
+            // Only the keys differ, so a decryption failure means a wrong password.
+            (None, Some(EncryptionError::Error(_))) => Err(WalletError::WrongPassword),
+            (None, Some(e)) => Err(e.into()),
+            (None, None) => Err(WalletError::InvalidWalletFile("no slots".to_string())),
+        }
+    }
+
+    /// Replaces the plaintext of the slot opened by `password`, keeping the header and the
+    /// other slots as they are.
+    ///
+    /// The new plaintext is padded to the size of the old one, and has to fit in it: a slot
+    /// larger than the others would tell which one holds the wallet.
+    pub(crate) fn replace_slot(
+        mut self,
+        password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        plaintext: &SecretString,
+    ) -> Result<Self> {
+        let aad = self.aad()?;
+        let WalletFile::V4(v4) = &mut self else {
+            return Err(WalletError::InvalidWalletFile(
+                "only files with slots can be updated in place".to_string(),
+            ));
+        };
+        let (index, old_plaintext) = v4
+            .slots
+            .iter()
+            .enumerate()
+            .find_map(|(index, payload)| {
+                decrypt_with_aad(payload, password, keyfile, &aad)
+                    .ok()
+                    .map(|plaintext| (index, plaintext))
+            })
+            .ok_or(WalletError::WrongPassword)?;
+
+        let padded_len = old_plaintext.len();
+        if plaintext.len() > padded_len {
+            return Err(WalletError::InvalidWalletFile(
+                "the wallet outgrew its slot".to_string(),
+            ));
+        }
+        let mut padded = String::with_capacity(padded_len);
+        padded.push_str(plaintext.expose_secret());
+        padded.extend(std::iter::repeat_n(' ', padded_len - plaintext.len()));
+        let padded = SecretString::from(padded);
+        v4.slots[index] = encrypt_with_aad(padded.expose_secret(), password, keyfile, &aad)?;
+        Ok(self)
+    }
+
+    fn payloads(&self) -> &[EncryptedPayload] {
+        match self {
+            WalletFile::V4(v4) => v4.slots.as_slice(),
+            WalletFile::V3(v3) => std::slice::from_ref(&v3.payload),
+            WalletFile::V2(v2) => std::slice::from_ref(&v2.payload),
+            WalletFile::V1(payload) => std::slice::from_ref(payload),
+        }
+    }
+
+    /// The plaintext header, legacy files don't have one.
+    pub(crate) fn header(&self) -> Option<&WalletHeader> {
+        match self {
+            WalletFile::V4(v4) => Some(&v4.header),
+            WalletFile::V3(v3) => Some(&v3.header),
+            _ => None,
+        }
+    }
+
+    /// Whether the header always describes the wallet in the file.
+    ///
+    /// With slots, the header describes the decoy wallet when there is one.
+    pub(crate) fn header_describes_payload(&self) -> bool {
+        matches!(self, WalletFile::V3(_))
+    }
+
+    /// The associated data the payloads were encrypted with.
+    fn aad(&self) -> Result<Vec<u8>> {
+        match self {
+            WalletFile::V4(v4) => authenticated_data(
+                v4.version,
+                &v4.header,
+                v4.keyfile_required,
+                v4.keyfile_fingerprint.as_deref(),
+            ),
+            WalletFile::V3(v3) => authenticated_data(
+                v3.version,
+                &v3.header,
+                v3.keyfile_required,
+                v3.keyfile_fingerprint.as_deref(),
+            ),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    pub(crate) fn keyfile_requirement(&self) -> KeyfileRequirement {
+        let (keyfile_required, keyfile_fingerprint) = match self {
+            WalletFile::V4(v4) => (v4.keyfile_required, &v4.keyfile_fingerprint),
+            WalletFile::V3(v3) => (v3.keyfile_required, &v3.keyfile_fingerprint),
+            WalletFile::V2(v2) => (v2.keyfile_required, &v2.keyfile_fingerprint),
+            WalletFile::V1(_) => return KeyfileRequirement::None,
+        };
+        match keyfile_required {
+            true => KeyfileRequirement::Required(keyfile_fingerprint.clone()),
+            false => KeyfileRequirement::None,
+        }
+    }
+}
+
+fn authenticated_data(
+    version: u32,
+    header: &WalletHeader,
+    keyfile_required: bool,
+    keyfile_fingerprint: Option<&str>,
+) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&AuthenticatedData {
+        version,
+        header,
+        keyfile_required,
+        keyfile_fingerprint,
+    })?)
+}
+
+/// Whether a wallet needs a keyfile, and which one (by fingerprint) when it is known.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) enum KeyfileRequirement {
+    #[default]
+    None,
+    Required(Option<String>),
+}
+
+impl KeyfileRequirement {
+    pub(crate) fn is_required(&self) -> bool {
+        matches!(self, KeyfileRequirement::Required(_))
+    }
+
+    /// Checks the provided keyfile against the one the wallet was created with, so a
+    /// missing or wrong keyfile is reported as such rather than as a failed decryption.
+    pub(crate) fn check(&self, keyfile: Option<&Keyfile>) -> Result<()> {
+        match (self, keyfile) {
+            (KeyfileRequirement::Required(_), None) => Err(WalletError::KeyfileRequired),
+            (KeyfileRequirement::None, Some(_)) => Err(WalletError::UnexpectedKeyfile),
+            (KeyfileRequirement::Required(Some(fingerprint)), Some(keyfile))
+                if keyfile.fingerprint() != *fingerprint =>
+            {
+                Err(WalletError::KeyfileMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use cwu_core_address::{NetworkAddress, network_addresses};
+use cwu_model::Network;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The public part of a wallet, stored in plaintext next to the encrypted payload.
+///
+/// It can be read without the master password, e.g. to list wallets or to watch balances.
+/// The header is bound to the payload as AES-GCM associated data, so any change to it is
+/// detected when the wallet is unlocked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletHeader {
+    name: String,
+    /// The version of the encrypted wallet data.
+    version: u32,
+    networks: Vec<Network>,
+    #[serde(with = "network_addresses")]
+    addresses: BTreeMap<Network, NetworkAddress>,
+    /// Seconds since the Unix epoch.
+    created_at: u64,
+}
+
+impl WalletHeader {
+    pub(crate) fn new(
+        name: &str,
+        version: u32,
+        addresses: BTreeMap<Network, NetworkAddress>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            version,
+            networks: addresses.keys().copied().collect(),
+            addresses,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub(crate) fn with_created_at(mut self, created_at: u64) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn networks(&self) -> &[Network] {
+        self.networks.as_slice()
+    }
+
+    pub fn addresses(&self) -> &BTreeMap<Network, NetworkAddress> {
+        &self.addresses
+    }
+
+    pub fn address(&self, network: Network) -> Option<&NetworkAddress> {
+        self.addresses.get(&network)
+    }
+
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+}
@@ -0,0 +1,78 @@
+//! # Imported Keys
+//!
+//! A wallet derives one key pair per network from its mnemonic, and any of them can be
+//! replaced with an imported private key, e.g. one found by a vanity search. Imported keys
+//! can't be recovered from the mnemonic: they are carried in backup bundles instead.
+//!
+//! When the wallet file has a decoy, the header and the decoy slot are left untouched, so
+//! the header keeps describing the decoy.
+
+use crate::{
+    EncryptedWallet, Result, WalletError,
+    file::{Slot, WalletFile},
+    network::key_pair_from_secret_key,
+    wallet::{EncryptedWalletV1, wallet_file_name},
+};
+use cwu_core_address::NetworkAddress;
+use cwu_model::Network;
+use cwu_security_utils::{Keyfile, SecretString};
+use k256::SecretKey;
+use serde_json::to_string_pretty;
+use zeroize::Zeroizing;
+
+impl EncryptedWallet {
+    /// Replaces the key pair of `network` with a hex encoded private key, and returns the
+    /// new address of the wallet on that network.
+    ///
+    /// The header is rebuilt when it describes the wallet opened by `master_password`, which
+    /// is also the case of a decoy: importing a key with the duress password drops the
+    /// wallet the decoy hid.
+    pub fn import_key(
+        wallet_name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+        network: Network,
+        private_key: &SecretString,
+    ) -> Result<NetworkAddress> {
+        let secret_key = parse_private_key(private_key)?;
+        let key_pair = key_pair_from_secret_key(network, &secret_key)?;
+
+        let path = wallet_file_name(wallet_name);
+        let wallet_file = WalletFile::read(&path)?;
+        wallet_file.keyfile_requirement().check(keyfile)?;
+        let wallet_json = wallet_file.decrypt(master_password, keyfile)?;
+        let mut wallet: EncryptedWalletV1 = serde_json::from_str(wallet_json.expose_secret())?;
+
+        // Without a decoy, the header describes this wallet and is rebuilt with it.
+        let describes_wallet = wallet_file
+            .header()
+            .is_none_or(|header| header.addresses() == &wallet.sorted_addresses());
+        let address = wallet.import_key_pair(network, &key_pair, master_password, keyfile)?;
+        let wallet_json = SecretString::from(to_string_pretty(&wallet)?);
+
+        let wallet_file = if describes_wallet {
+            let created_at = wallet_file.header().map(|header| header.created_at());
+            WalletFile::new(
+                wallet.header(created_at),
+                keyfile,
+                &[Slot {
+                    plaintext: &wallet_json,
+                    password: master_password,
+                }],
+            )?
+        } else {
+            wallet_file.replace_slot(master_password, keyfile, &wallet_json)?
+        };
+        wallet_file.write(path)?;
+
+        Ok(address)
+    }
+}
+
+pub(crate) fn parse_private_key(private_key: &SecretString) -> Result<SecretKey> {
+    let bytes = Zeroizing::new(
+        hex::decode(private_key.expose_secret().trim().trim_start_matches("0x"))
+            .map_err(|_| WalletError::InvalidPrivateKey)?,
+    );
+    SecretKey::from_slice(&bytes).map_err(|_| WalletError::InvalidPrivateKey)
+}
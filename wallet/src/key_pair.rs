@@ -1,4 +1,7 @@
 use crate::Result;
+use cwu_core_address::NetworkAddress;
+use cwu_model::Network;
+use cwu_security_utils::SecretString;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 use zeroize::Zeroize;
@@ -42,7 +45,12 @@ impl KeyPair {
         self.address.as_str()
     }
 
-    pub(crate) fn serialize(&self) -> Result<String> {
-        Ok(to_string_pretty(&self)?)
+    /// The typed address, for the network the key pair was derived for.
+    pub(crate) fn network_address(&self, network: Network) -> Result<NetworkAddress> {
+        Ok(NetworkAddress::parse(network, self.address())?)
+    }
+
+    pub(crate) fn serialize(&self) -> Result<SecretString> {
+        Ok(to_string_pretty(&self)?.into())
     }
 }
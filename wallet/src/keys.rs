@@ -0,0 +1,80 @@
+//! # Wallet Keys
+//!
+//! Every secret of a wallet is encrypted under its own key, derived from the master
+//! password (and keyfile) with Argon2. [`WalletKeys`] holds those derived keys, so an
+//! unlocked wallet can decrypt its secrets again without the master password.
+
+use crate::{EncryptedWallet, RecoveryPhrase, Result, WalletError, key_pair::KeyPair};
+use cwu_model::Network;
+use cwu_security_utils::{
+    EncryptedPayload, Keyfile, SecretBytes, SecretString, decrypt_with_derived_key,
+    derive_payload_key,
+};
+use std::collections::HashMap;
+
+/// The derived keys of an unlocked wallet, wiped from memory on drop.
+#[derive(Debug)]
+pub struct WalletKeys {
+    /// Keyed by the salt of the payload they decrypt.
+    keys: HashMap<String, SecretBytes>,
+}
+
+impl WalletKeys {
+    pub(crate) fn decrypt(&self, payload: &EncryptedPayload) -> Result<SecretString> {
+        let key = self
+            .keys
+            .get(&payload.salt_phc)
+            .ok_or(WalletError::WalletLocked)?;
+        Ok(decrypt_with_derived_key(payload, key)?)
+    }
+}
+
+impl EncryptedWallet {
+    /// Derives the keys of every secret of the wallet.
+    ///
+    /// The master password is checked by decrypting the mnemonic.
+    pub fn unlock(
+        &self,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<WalletKeys> {
+        self.keyfile_requirement().check(keyfile)?;
+        let mut keys = HashMap::new();
+        for payload in [self.mnemonic(), self.passphrase()]
+            .into_iter()
+            .chain(self.key_pairs().values())
+        {
+            let key = derive_payload_key(payload, master_password, keyfile)?;
+            keys.insert(payload.salt_phc.clone(), key);
+        }
+
+        let keys = WalletKeys { keys };
+        keys.decrypt(self.mnemonic())?;
+        Ok(keys)
+    }
+
+    /// Same as [`EncryptedWallet::backup`], with the keys of the unlocked wallet.
+    pub fn backup_with_keys(&self, keys: &WalletKeys) -> Result<RecoveryPhrase> {
+        Ok(RecoveryPhrase {
+            mnemonic: keys.decrypt(self.mnemonic())?,
+            passphrase: keys.decrypt(self.passphrase())?,
+        })
+    }
+
+    /// The raw 32 bytes private key of `network`, to sign transactions with.
+    pub fn signing_key(&self, keys: &WalletKeys, network: Network) -> Result<SecretBytes> {
+        let encrypted_key_pair = self
+            .key_pairs()
+            .get(&network)
+            .ok_or(WalletError::NotFoundKeyPair(network))?;
+        let key_pair_str = keys.decrypt(encrypted_key_pair)?;
+        let key_pair: KeyPair = serde_json::from_str(key_pair_str.expose_secret())?;
+        if key_pair.is_empty() {
+            return Err(WalletError::NotFoundKeyPair(network));
+        }
+        let private_key = hex::decode(key_pair.private_key())
+            .map(SecretBytes::from)
+            .map_err(|_| WalletError::InvalidPrivateKey)?;
+        Ok(private_key)
+    }
+}
@@ -1,11 +1,28 @@
+mod audit;
+mod backup;
+mod cache;
+mod duress;
+mod file;
+mod header;
+mod import;
 mod key_pair;
+mod keys;
 mod language;
 mod network;
 mod result;
+mod vanity;
 mod wallet;
+mod xpub;
 
+pub use audit::{AuditEntry, AuditEvent, AuditOperation, AuditProblem, AuditReport};
+pub use backup::RestoredWallet;
+pub use header::WalletHeader;
+pub use keys::WalletKeys;
+pub use network::{AddressEncoder, BitcoinDerivation};
 pub use result::{Result, WalletError};
-pub use wallet::EncryptedWallet;
+pub use vanity::{VanityMatch, VanityPattern, VanityProgress, VanitySource};
+pub use wallet::{EncryptedWallet, RecoveryPhrase};
+pub use xpub::{ExtendedPublicKey, WatchOnlyWallet};
 
 #[cfg(test)]
 mod tests;
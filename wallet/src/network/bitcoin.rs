@@ -0,0 +1,179 @@
+//! # Bitcoin Key Generation
+//!
+//! This module derives Bitcoin key pairs from a mnemonic phrase, for each of the standard
+//! derivation schemes: BIP44 (legacy P2PKH), BIP49 (nested SegWit P2SH-P2WPKH), BIP84
+//! (native SegWit P2WPKH) and BIP86 (Taproot P2TR). The path is `m/purpose'/coin'/0'/0/0`,
+//! with coin type `0'` on mainnet and `1'` on testnet. Wallets store the BIP84 address.
+
+use crate::{
+    EncryptedWallet, Result, WalletError, WalletKeys, key_pair::KeyPair, network::AddressEncoder,
+};
+use bip39::Mnemonic;
+use cwu_core_address::{BitcoinAddress, BitcoinAddressType, BitcoinFormat, BitcoinNetwork};
+use k256::SecretKey;
+use std::str::FromStr;
+use tiny_hderive::bip32::ExtendedPrivKey;
+use zeroize::Zeroizing;
+
+/// A Bitcoin derivation scheme, named after the BIP defining it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitcoinDerivation {
+    Bip44,
+    Bip49,
+    Bip84,
+    Bip86,
+}
+
+impl BitcoinDerivation {
+    pub const ALL_VARIANTS: [BitcoinDerivation; 4] = [
+        BitcoinDerivation::Bip44,
+        BitcoinDerivation::Bip49,
+        BitcoinDerivation::Bip84,
+        BitcoinDerivation::Bip86,
+    ];
+
+    pub fn purpose(&self) -> u32 {
+        match self {
+            BitcoinDerivation::Bip44 => 44,
+            BitcoinDerivation::Bip49 => 49,
+            BitcoinDerivation::Bip84 => 84,
+            BitcoinDerivation::Bip86 => 86,
+        }
+    }
+
+    pub fn address_type(&self) -> BitcoinAddressType {
+        match self {
+            BitcoinDerivation::Bip44 => BitcoinAddressType::P2pkh,
+            BitcoinDerivation::Bip49 => BitcoinAddressType::P2shP2wpkh,
+            BitcoinDerivation::Bip84 => BitcoinAddressType::P2wpkh,
+            BitcoinDerivation::Bip86 => BitcoinAddressType::P2tr,
+        }
+    }
+
+    /// The path of the first account, `m/purpose'/coin'/0'`.
+    pub(crate) fn account_path(&self, network: BitcoinNetwork) -> String {
+        let coin_type = match network {
+            BitcoinNetwork::Mainnet => 0,
+            BitcoinNetwork::Testnet => 1,
+        };
+        format!("m/{}'/{coin_type}'/0'", self.purpose())
+    }
+
+    fn path(&self, network: BitcoinNetwork) -> String {
+        format!("{}/0/0", self.account_path(network))
+    }
+
+    pub(crate) fn encoder(&self, network: BitcoinNetwork) -> AddressEncoder {
+        AddressEncoder::Bitcoin(BitcoinFormat::new(self.address_type(), network))
+    }
+}
+
+/// Generates a Bitcoin key pair from a mnemonic phrase.
+pub(crate) fn get_bitcoin_key_pair_from_mnemonic(
+    mnemonic: &Mnemonic,
+    passphrase: &str,
+    derivation: BitcoinDerivation,
+    network: BitcoinNetwork,
+) -> Result<KeyPair> {
+    // 1. Mnemonic to Seed
+    let seed = Zeroizing::new(mnemonic.to_seed(passphrase));
+
+    // 2. Hierarchical-Deterministic (HD) Wallet derivation
+    let key = ExtendedPrivKey::derive(seed.as_slice(), derivation.path(network).as_str())
+        .expect("Valid path");
+
+    // 3. Private and Public Keys
+    let secret = Zeroizing::new(key.secret());
+    let secret_key = SecretKey::from_slice(secret.as_slice())?;
+
+    // 4. Bitcoin Address
+    derivation.encoder(network).key_pair(&secret_key)
+}
+
+impl EncryptedWallet {
+    /// The first Bitcoin address of the wallet for a derivation scheme, BIP84 being the one
+    /// stored in the wallet.
+    pub fn bitcoin_address(
+        &self,
+        keys: &WalletKeys,
+        derivation: BitcoinDerivation,
+        network: BitcoinNetwork,
+    ) -> Result<BitcoinAddress> {
+        let mnemonic = keys.decrypt(self.mnemonic())?;
+        let mnemonic = Mnemonic::from_str(mnemonic.expose_secret())?;
+        let passphrase = keys.decrypt(self.passphrase())?;
+        let key_pair = get_bitcoin_key_pair_from_mnemonic(
+            &mnemonic,
+            passphrase.expose_secret(),
+            derivation,
+            network,
+        )?;
+        BitcoinAddress::from_str(key_pair.address()).map_err(WalletError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn address(derivation: BitcoinDerivation, network: BitcoinNetwork) -> String {
+        let mnemonic = Mnemonic::from_str(MNEMONIC).unwrap();
+        get_bitcoin_key_pair_from_mnemonic(&mnemonic, "", derivation, network)
+            .unwrap()
+            .address()
+            .to_string()
+    }
+
+    /// The test vectors of the BIPs defining each derivation scheme.
+    #[test]
+    fn test_bitcoin_key_derivation() {
+        for (derivation, network, expected_address) in [
+            (
+                BitcoinDerivation::Bip44,
+                BitcoinNetwork::Mainnet,
+                "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+            ),
+            (
+                BitcoinDerivation::Bip49,
+                BitcoinNetwork::Testnet,
+                "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2",
+            ),
+            (
+                BitcoinDerivation::Bip84,
+                BitcoinNetwork::Mainnet,
+                "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+            ),
+            (
+                BitcoinDerivation::Bip86,
+                BitcoinNetwork::Mainnet,
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+        ] {
+            assert_eq!(
+                address(derivation, network),
+                expected_address,
+                "{derivation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bitcoin_key_pair() {
+        let mnemonic = Mnemonic::from_str(MNEMONIC).unwrap();
+        let key_pair = get_bitcoin_key_pair_from_mnemonic(
+            &mnemonic,
+            "",
+            BitcoinDerivation::Bip84,
+            BitcoinNetwork::Mainnet,
+        )
+        .unwrap();
+
+        // BIP84, the WIF KyZpNDKnfs94vbrwhJneDi77V6jF64PWPF8x5cdJb8ifgg2DUc9d.
+        assert_eq!(
+            key_pair.private_key(),
+            "4604b4b710fe91f584fff084e1a9159fe4f8408fff380596a604948474ce4fa3"
+        );
+    }
+}
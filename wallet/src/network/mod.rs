@@ -1,3 +1,78 @@
+mod bitcoin;
 mod tron;
 
+use crate::{Result, key_pair::KeyPair};
+use cwu_core_address::{
+    Address, BitcoinAddress, BitcoinFormat, BitcoinNetwork, EthereumAddress, EthereumFormat,
+    NetworkAddress, TronAddress, TronFormat,
+};
+use cwu_model::Network;
+use k256::{PublicKey, SecretKey};
+
+pub use bitcoin::BitcoinDerivation;
+pub(crate) use bitcoin::get_bitcoin_key_pair_from_mnemonic;
 pub(crate) use tron::get_tron_key_pair_from_mnemonic;
+
+/// How a public key is turned into an address, shared by the signing and the watch-only
+/// wallets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressEncoder {
+    Ethereum,
+    Tron,
+    Bitcoin(BitcoinFormat),
+}
+
+impl AddressEncoder {
+    /// The encoder of the addresses stored in wallets, Bitcoin addresses being BIP84 ones.
+    pub fn wallet_default(network: Network) -> Self {
+        match network {
+            Network::Ethereum => AddressEncoder::Ethereum,
+            Network::Tron => AddressEncoder::Tron,
+            Network::Bitcoin => AddressEncoder::Bitcoin(BitcoinFormat::new(
+                BitcoinDerivation::Bip84.address_type(),
+                BitcoinNetwork::Mainnet,
+            )),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        match self {
+            AddressEncoder::Ethereum => Network::Ethereum,
+            AddressEncoder::Tron => Network::Tron,
+            AddressEncoder::Bitcoin(_) => Network::Bitcoin,
+        }
+    }
+
+    pub fn address(&self, public_key: &PublicKey) -> Result<NetworkAddress> {
+        let public_key = *public_key;
+        Ok(match self {
+            AddressEncoder::Ethereum => {
+                EthereumAddress::from_public_key(&public_key.into(), &EthereumFormat::Standard)?
+                    .into()
+            }
+            AddressEncoder::Tron => {
+                TronAddress::from_public_key(&public_key.into(), &TronFormat::Standard)?.into()
+            }
+            AddressEncoder::Bitcoin(format) => {
+                BitcoinAddress::from_public_key(&public_key.into(), format)?.into()
+            }
+        })
+    }
+
+    /// The key pair of a private key, with a hex encoded private key.
+    pub(crate) fn key_pair(&self, secret_key: &SecretKey) -> Result<KeyPair> {
+        let address = self.address(&secret_key.public_key())?;
+        Ok(KeyPair::new(
+            hex::encode(secret_key.to_bytes()),
+            address.to_string(),
+        ))
+    }
+}
+
+/// The key pair of a private key on `network`, Bitcoin addresses being BIP84 ones.
+pub(crate) fn key_pair_from_secret_key(
+    network: Network,
+    secret_key: &SecretKey,
+) -> Result<KeyPair> {
+    AddressEncoder::wallet_default(network).key_pair(secret_key)
+}
@@ -1,4 +1,5 @@
 use crate::language::InvalidLanguage;
+use cwu_core_address::AddressError;
 use cwu_model::Network;
 use cwu_security_utils::{EncryptionError, PasswordError};
 use k256::elliptic_curve;
@@ -26,11 +27,59 @@ pub enum WalletError {
     #[error("{0}")]
     EllipticCurveError(#[from] elliptic_curve::Error),
 
+    #[error("{0}")]
+    AddressError(#[from] AddressError),
+
     #[error("{0}")]
     UnsupportedVersion(u32),
 
     #[error("Not found key pair for network: {0}")]
     NotFoundKeyPair(Network),
+
+    #[error("This wallet requires a keyfile")]
+    KeyfileRequired,
+
+    #[error("The keyfile does not match this wallet")]
+    KeyfileMismatch,
+
+    #[error("This wallet does not use a keyfile")]
+    UnexpectedKeyfile,
+
+    #[error("Invalid backup bundle: {0}")]
+    InvalidBackup(String),
+
+    #[error("Wallet already exists: {0}")]
+    WalletExists(String),
+
+    #[error("Wallet '{0}' has no plaintext header, it has to be unlocked")]
+    NoHeader(String),
+
+    #[error("The wallet header does not match the encrypted wallet")]
+    HeaderMismatch,
+
+    #[error("Invalid wallet file: {0}")]
+    InvalidWalletFile(String),
+
+    #[error("The duress password must differ from the master password")]
+    SameDuressPassword,
+
+    #[error("Wrong password")]
+    WrongPassword,
+
+    #[error("The wallet is locked")]
+    WalletLocked,
+
+    #[error("Invalid private key, expected 32 hex encoded bytes")]
+    InvalidPrivateKey,
+
+    #[error("Invalid vanity pattern: {0}")]
+    InvalidVanityPattern(String),
+
+    #[error("Invalid extended public key: {0}")]
+    InvalidExtendedKey(String),
+
+    #[error("The {0} key was imported, it isn't derived from the mnemonic")]
+    ImportedKey(Network),
 }
 
 pub type Result<T> = std::result::Result<T, WalletError>;
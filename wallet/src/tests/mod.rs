@@ -1,28 +1,41 @@
-use crate::EncryptedWallet;
+use crate::backup::plain_name;
+use crate::file::WalletFile;
 use crate::key_pair::KeyPair;
+use crate::wallet::derive_key_pairs;
+use crate::{
+    AuditEvent, AuditOperation, AuditProblem, EncryptedWallet, RecoveryPhrase, WalletError,
+    WatchOnlyWallet,
+};
+use bip39::Mnemonic;
+use cwu_core_address::NetworkAddress;
 use cwu_model::Network;
+use cwu_security_utils::{Keyfile, PasswordError, SecretString};
 use std::collections::HashMap;
 
 #[test]
 fn test_open_wallet1() {
-    let master_password = "TtWId4h$fm1y#(Nn".to_string();
-    let wallet = EncryptedWallet::open("src/tests/test1", master_password.clone()).unwrap();
+    let master_password = SecretString::from("TtWId4h$fm1y#(Nn");
+    let wallet = EncryptedWallet::open("src/tests/test1", &master_password, None).unwrap();
 
     assert_eq!(wallet.name(), "test1");
     assert_eq!(
         wallet.addresses(),
         &HashMap::from([(
             Network::Tron,
-            "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX".to_string()
+            NetworkAddress::parse(Network::Tron, "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX").unwrap()
         )])
     );
     assert_eq!(
-        wallet.backup(master_password.clone()).unwrap(),
+        wallet
+            .backup(&master_password, None)
+            .unwrap()
+            .mnemonic
+            .expose_secret(),
         "fiber jazz upper cruel betray fence series suit habit ski crowd project"
     );
 
     let tron_key_pair = wallet
-        .key_pair(master_password.clone(), Network::Tron)
+        .key_pair(&master_password, None, Network::Tron)
         .unwrap();
 
     assert_eq!(
@@ -34,26 +47,124 @@ fn test_open_wallet1() {
     )
 }
 
+#[test]
+fn test_unlocked_wallet_keys() {
+    let master_password = SecretString::from("TtWId4h$fm1y#(Nn");
+    let wallet = EncryptedWallet::open("src/tests/test1", &master_password, None).unwrap();
+
+    assert!(
+        wallet
+            .unlock(&SecretString::from("wrong password"), None)
+            .is_err()
+    );
+    let keys = wallet.unlock(&master_password, None).unwrap();
+    assert_eq!(
+        wallet
+            .backup_with_keys(&keys)
+            .unwrap()
+            .mnemonic
+            .expose_secret(),
+        "fiber jazz upper cruel betray fence series suit habit ski crowd project"
+    );
+    assert_eq!(
+        hex::encode(
+            wallet
+                .signing_key(&keys, Network::Tron)
+                .unwrap()
+                .expose_secret()
+        ),
+        "b51cde5cc2875fb879d49dda436dbeda1c26f4941df9bf47849dcf73841b6b9e"
+    );
+    assert!(matches!(
+        wallet.signing_key(&keys, Network::Ethereum),
+        Err(WalletError::NotFoundKeyPair(Network::Ethereum))
+    ));
+
+    // Keys of another wallet don't fit.
+    let other_password = SecretString::from("CQLav?I4e]oLm7;J");
+    let other = EncryptedWallet::open("src/tests/test2", &other_password, None).unwrap();
+    let other_keys = other.unlock(&other_password, None).unwrap();
+    assert!(matches!(
+        wallet.backup_with_keys(&other_keys),
+        Err(WalletError::WalletLocked)
+    ));
+}
+
+#[test]
+fn test_backup_recovers_keys() {
+    let dir = std::env::temp_dir().join(format!("cwu-recovery-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("recoverable").to_string_lossy().to_string();
+    let master_password = SecretString::from("correct-horse-battery-staple-Wallet-42");
+    EncryptedWallet::create(
+        12,
+        "English",
+        &wallet_name,
+        Some(master_password.clone()),
+        None,
+    )
+    .unwrap();
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+
+    // The backup output alone, without the wallet file, derives the same keys.
+    let phrase = wallet.backup(&master_password, None).unwrap();
+    let mnemonic = Mnemonic::parse(phrase.mnemonic.expose_secret()).unwrap();
+    let key_pairs: HashMap<_, _> = derive_key_pairs(&mnemonic, &phrase.passphrase)
+        .unwrap()
+        .into_iter()
+        .collect();
+    for network in [Network::Tron, Network::Bitcoin] {
+        assert_eq!(
+            key_pairs.get(&network),
+            Some(&wallet.key_pair(&master_password, None, network).unwrap())
+        );
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_watch_only_wallet_matches_wallet() {
+    let master_password = SecretString::from("TtWId4h$fm1y#(Nn");
+    let wallet = EncryptedWallet::open("src/tests/test1", &master_password, None).unwrap();
+    let keys = wallet.unlock(&master_password, None).unwrap();
+
+    let xpub = wallet.account_xpub(&keys, Network::Tron).unwrap();
+    assert!(xpub.to_string().starts_with("xpub"));
+    let watch_only = WatchOnlyWallet::new(Network::Tron, &xpub.to_string()).unwrap();
+    assert_eq!(
+        Some(&watch_only.address(0).unwrap()),
+        wallet.addresses().get(&Network::Tron)
+    );
+    assert_ne!(
+        watch_only.address(1).unwrap(),
+        watch_only.address(0).unwrap()
+    );
+}
+
 #[test]
 fn test_open_wallet2() {
-    let master_password = "CQLav?I4e]oLm7;J".to_string();
-    let wallet = EncryptedWallet::open("src/tests/test2", master_password.clone()).unwrap();
+    let master_password = SecretString::from("CQLav?I4e]oLm7;J");
+    let wallet = EncryptedWallet::open("src/tests/test2", &master_password, None).unwrap();
 
     assert_eq!(wallet.name(), "test2");
     assert_eq!(
         wallet.addresses(),
         &HashMap::from([(
             Network::Tron,
-            "TQKzP25THvRXFWigHWaiNNbgsbXZ3D9Gj4".to_string()
+            NetworkAddress::parse(Network::Tron, "TQKzP25THvRXFWigHWaiNNbgsbXZ3D9Gj4").unwrap()
         )])
     );
     assert_eq!(
-        wallet.backup(master_password.clone()).unwrap(),
+        wallet
+            .backup(&master_password, None)
+            .unwrap()
+            .mnemonic
+            .expose_secret(),
         "option catalog claim zero waste nut congress wasp student frown desert route"
     );
 
     let tron_key_pair = wallet
-        .key_pair(master_password.clone(), Network::Tron)
+        .key_pair(&master_password, None, Network::Tron)
         .unwrap();
 
     assert_eq!(
@@ -64,3 +175,555 @@ fn test_open_wallet2() {
         )
     )
 }
+
+#[test]
+fn test_create_wallet_rejects_weak_password() {
+    let result = EncryptedWallet::create(
+        12,
+        "English",
+        "src/tests/weak",
+        Some(SecretString::from("Dragon2024!")),
+        None,
+    );
+
+    assert!(matches!(
+        result,
+        Err(WalletError::PasswordError(PasswordError::TooWeak(_)))
+    ));
+    assert!(!std::path::Path::new("src/tests/weak.cwu.json").exists());
+}
+
+#[test]
+fn test_wallet_with_keyfile() {
+    let dir = std::env::temp_dir().join(format!("cwu-keyfile-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("keyfile").to_string_lossy().to_string();
+    let keyfile = Keyfile::generate(dir.join("wallet.key")).unwrap();
+    let wrong_keyfile = Keyfile::from_bytes(b"not the keyfile");
+
+    let master_password =
+        EncryptedWallet::create(12, "English", &wallet_name, None, Some(&keyfile)).unwrap();
+    assert!(EncryptedWallet::requires_keyfile(&wallet_name).unwrap());
+
+    assert!(matches!(
+        EncryptedWallet::open(&wallet_name, &master_password, None),
+        Err(WalletError::KeyfileRequired)
+    ));
+    assert!(matches!(
+        EncryptedWallet::open(&wallet_name, &master_password, Some(&wrong_keyfile)),
+        Err(WalletError::KeyfileMismatch)
+    ));
+
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, Some(&keyfile)).unwrap();
+    assert!(wallet.uses_keyfile());
+    assert!(matches!(
+        wallet.backup(&master_password, None),
+        Err(WalletError::KeyfileRequired)
+    ));
+    let phrase = wallet.backup(&master_password, Some(&keyfile)).unwrap();
+    assert_eq!(phrase.mnemonic.expose_secret().split(' ').count(), 12);
+    assert!(
+        wallet
+            .key_pair(&master_password, Some(&keyfile), Network::Tron)
+            .is_ok()
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_legacy_wallet_does_not_require_keyfile() {
+    assert!(!EncryptedWallet::requires_keyfile("src/tests/test1").unwrap());
+    let master_password = SecretString::from("TtWId4h$fm1y#(Nn");
+    assert!(matches!(
+        EncryptedWallet::open(
+            "src/tests/test1",
+            &master_password,
+            Some(&Keyfile::from_bytes(b"key"))
+        ),
+        Err(WalletError::UnexpectedKeyfile)
+    ));
+}
+
+#[test]
+fn test_export_and_import_backup() {
+    let dir = std::env::temp_dir().join(format!("cwu-backup-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let bundle_path = dir.join("test1.cwu-backup");
+    let backup_password = SecretString::from("correct-horse-battery-staple-orbit");
+    let master_password = SecretString::from("TtWId4h$fm1y#(Nn");
+    let wallet = EncryptedWallet::open("src/tests/test1", &master_password, None).unwrap();
+
+    assert!(matches!(
+        wallet.export_backup(
+            &master_password,
+            None,
+            &SecretString::from("weak"),
+            &bundle_path
+        ),
+        Err(WalletError::PasswordError(PasswordError::TooWeak(_)))
+    ));
+    wallet
+        .export_backup(&master_password, None, &backup_password, &bundle_path)
+        .unwrap();
+
+    let restored_name = dir.join("restored").to_string_lossy().to_string();
+    assert!(
+        EncryptedWallet::import_backup(
+            &bundle_path,
+            &SecretString::from("wrong-backup-password"),
+            Some(&restored_name),
+            None,
+            None,
+        )
+        .is_err()
+    );
+    let restored = EncryptedWallet::import_backup(
+        &bundle_path,
+        &backup_password,
+        Some(&restored_name),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(restored.name, restored_name);
+
+    let restored_wallet =
+        EncryptedWallet::open(&restored_name, &restored.master_password, None).unwrap();
+    // The wallet predates Bitcoin support, the restored one gets a Bitcoin address too.
+    assert_eq!(
+        restored_wallet.addresses().get(&Network::Tron),
+        wallet.addresses().get(&Network::Tron)
+    );
+    assert_eq!(
+        restored_wallet
+            .addresses()
+            .get(&Network::Bitcoin)
+            .map(ToString::to_string)
+            .as_deref(),
+        Some("bc1qdmc3qnfvxcae97003x2dh7kqz3h7uzp0u67078")
+    );
+    assert_eq!(
+        restored_wallet
+            .backup(&restored.master_password, None)
+            .unwrap()
+            .mnemonic
+            .expose_secret(),
+        "fiber jazz upper cruel betray fence series suit habit ski crowd project"
+    );
+
+    // An existing wallet is never overwritten.
+    assert!(matches!(
+        EncryptedWallet::import_backup(
+            &bundle_path,
+            &backup_password,
+            Some(&restored_name),
+            None,
+            None
+        ),
+        Err(WalletError::WalletExists(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_backup_wallet_name_is_not_a_path() {
+    assert_eq!(plain_name("savings").unwrap(), "savings");
+    for name in [
+        "",
+        "..",
+        "../savings",
+        "wallets/savings",
+        "..\\savings",
+        "/tmp/savings",
+    ] {
+        assert!(
+            matches!(plain_name(name), Err(WalletError::InvalidBackup(_))),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn test_wallet_header() {
+    let dir = std::env::temp_dir().join(format!("cwu-header-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("header").to_string_lossy().to_string();
+    let master_password = EncryptedWallet::create(12, "English", &wallet_name, None, None).unwrap();
+
+    let header = EncryptedWallet::header(&wallet_name).unwrap();
+    assert_eq!(header.name(), wallet_name);
+    assert_eq!(header.version(), 1);
+    assert_eq!(header.networks(), &[Network::Tron, Network::Bitcoin]);
+    assert!(header.created_at() > 0);
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    assert_eq!(
+        header.address(Network::Tron),
+        wallet.addresses().get(&Network::Tron)
+    );
+
+    let wallets = EncryptedWallet::list(&dir).unwrap();
+    assert_eq!(wallets, vec![(wallet_name.clone(), Some(header))]);
+
+    // Swapping the address in the plaintext header is detected on unlock.
+    let file_name = format!("{wallet_name}.cwu.json");
+    let mut file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&file_name).unwrap()).unwrap();
+    file["header"]["addresses"]["Tron"] = "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX".into();
+    std::fs::write(&file_name, file.to_string()).unwrap();
+    assert_eq!(
+        EncryptedWallet::header(&wallet_name)
+            .unwrap()
+            .address(Network::Tron),
+        NetworkAddress::parse(Network::Tron, "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX")
+            .ok()
+            .as_ref()
+    );
+    assert!(EncryptedWallet::open(&wallet_name, &master_password, None).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_legacy_wallet_has_no_header() {
+    assert!(matches!(
+        EncryptedWallet::header("src/tests/test1"),
+        Err(WalletError::NoHeader(_))
+    ));
+}
+
+#[test]
+fn test_duress_password_opens_decoy() {
+    let dir = std::env::temp_dir().join(format!("cwu-duress-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("duress").to_string_lossy().to_string();
+    let file_name = format!("{wallet_name}.cwu.json");
+    let master_password = EncryptedWallet::create(12, "English", &wallet_name, None, None).unwrap();
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    let file_size = std::fs::metadata(&file_name).unwrap().len();
+
+    assert!(matches!(
+        EncryptedWallet::add_decoy(
+            &wallet_name,
+            &master_password,
+            None,
+            Some(master_password.clone())
+        ),
+        Err(WalletError::SameDuressPassword)
+    ));
+    let duress_password =
+        EncryptedWallet::add_decoy(&wallet_name, &master_password, None, None).unwrap();
+    // Same format: the filler slot was replaced by the decoy.
+    let file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&file_name).unwrap()).unwrap();
+    assert_eq!(file["slots"].as_array().unwrap().len(), 2);
+    assert!(
+        std::fs::metadata(&file_name)
+            .unwrap()
+            .len()
+            .abs_diff(file_size)
+            < 64
+    );
+
+    let real = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    assert_eq!(real.addresses(), wallet.addresses());
+    let decoy = EncryptedWallet::open(&wallet_name, &duress_password, None).unwrap();
+    assert_eq!(decoy.name(), wallet.name());
+    assert_ne!(decoy.addresses(), wallet.addresses());
+    assert!(decoy.backup(&duress_password, None).is_ok());
+
+    // The header shows the decoy only.
+    let header = EncryptedWallet::header(&wallet_name).unwrap();
+    assert_eq!(
+        header.address(Network::Tron),
+        decoy.addresses().get(&Network::Tron)
+    );
+
+    assert!(matches!(
+        EncryptedWallet::add_decoy(&wallet_name, &SecretString::from("wrong"), None, None),
+        Err(WalletError::WrongPassword)
+    ));
+    assert!(EncryptedWallet::open(&wallet_name, &SecretString::from("wrong"), None).is_err());
+
+    // Nothing tells the decoy from a wallet, so the duress password sets up a decoy of the
+    // decoy, dropping the wallet it hid.
+    let second_duress_password =
+        EncryptedWallet::add_decoy(&wallet_name, &duress_password, None, None).unwrap();
+    assert!(matches!(
+        EncryptedWallet::open(&wallet_name, &master_password, None),
+        Err(WalletError::WrongPassword)
+    ));
+    assert_eq!(
+        EncryptedWallet::open(&wallet_name, &duress_password, None)
+            .unwrap()
+            .addresses(),
+        decoy.addresses()
+    );
+    assert!(EncryptedWallet::open(&wallet_name, &second_duress_password, None).is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_decoy_looks_like_a_wallet() {
+    let dir = std::env::temp_dir().join(format!("cwu-decoy-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("decoy").to_string_lossy().to_string();
+    let master_password = EncryptedWallet::create(24, "French", &wallet_name, None, None).unwrap();
+    let duress_password =
+        EncryptedWallet::add_decoy(&wallet_name, &master_password, None, None).unwrap();
+
+    let wallet_file = WalletFile::read(format!("{wallet_name}.cwu.json")).unwrap();
+    let slot = |password: &SecretString| -> serde_json::Value {
+        serde_json::from_str(wallet_file.decrypt(password, None).unwrap().expose_secret()).unwrap()
+    };
+    let keys = |value: &serde_json::Value| -> Vec<String> {
+        value.as_object().unwrap().keys().cloned().collect()
+    };
+    let (wallet, decoy) = (slot(&master_password), slot(&duress_password));
+    assert_eq!(keys(&decoy), keys(&wallet));
+    assert_eq!(keys(&decoy["key_pairs"]), keys(&wallet["key_pairs"]));
+    assert_eq!(keys(&decoy["addresses"]), keys(&wallet["addresses"]));
+
+    // Generated the same way: same words, same passphrase, same kind of password.
+    let phrase = |password: &SecretString| {
+        EncryptedWallet::open(&wallet_name, password, None)
+            .unwrap()
+            .backup(password, None)
+            .unwrap()
+    };
+    let (wallet, decoy) = (phrase(&master_password), phrase(&duress_password));
+    let words = |phrase: &RecoveryPhrase| Mnemonic::parse(phrase.mnemonic.expose_secret()).unwrap();
+    assert_eq!(words(&decoy).word_count(), words(&wallet).word_count());
+    assert_eq!(words(&decoy).language(), words(&wallet).language());
+    assert_eq!(decoy.passphrase.len(), wallet.passphrase.len());
+    assert_eq!(duress_password.len(), master_password.len());
+    assert!(
+        duress_password
+            .expose_secret()
+            .ends_with(decoy.passphrase.expose_secret())
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_import_key() {
+    let dir = std::env::temp_dir().join(format!("cwu-import-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("import").to_string_lossy().to_string();
+    let master_password = EncryptedWallet::create(12, "English", &wallet_name, None, None).unwrap();
+    let private_key =
+        SecretString::from("0xb51cde5cc2875fb879d49dda436dbeda1c26f4941df9bf47849dcf73841b6b9e");
+    let tron_address =
+        NetworkAddress::parse(Network::Tron, "TMTpzDaQrCVsE1efSyCnsENcbBj2oUTjyX").unwrap();
+
+    assert!(matches!(
+        EncryptedWallet::import_key(
+            &wallet_name,
+            &master_password,
+            None,
+            Network::Tron,
+            &SecretString::from("not a key"),
+        ),
+        Err(WalletError::InvalidPrivateKey)
+    ));
+    let address = EncryptedWallet::import_key(
+        &wallet_name,
+        &master_password,
+        None,
+        Network::Tron,
+        &private_key,
+    )
+    .unwrap();
+    assert_eq!(address, tron_address);
+
+    // Without a decoy, the header follows the imported key.
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    assert!(wallet.is_imported(Network::Tron));
+    assert!(!wallet.is_imported(Network::Bitcoin));
+    let keys = wallet.unlock(&master_password, None).unwrap();
+    assert!(matches!(
+        wallet.account_xpub(&keys, Network::Tron),
+        Err(WalletError::ImportedKey(Network::Tron))
+    ));
+    assert_eq!(wallet.addresses().get(&Network::Tron), Some(&tron_address));
+    assert_eq!(
+        EncryptedWallet::header(&wallet_name)
+            .unwrap()
+            .address(Network::Tron),
+        Some(&tron_address)
+    );
+
+    // Imported keys can't be derived from the mnemonic, the backup bundle carries them.
+    let bundle_path = dir.join("import.cwu-backup");
+    let backup_password = SecretString::from("correct-horse-battery-staple-orbit");
+    wallet
+        .export_backup(&master_password, None, &backup_password, &bundle_path)
+        .unwrap();
+    let restored_name = dir.join("restored").to_string_lossy().to_string();
+    let restored = EncryptedWallet::import_backup(
+        &bundle_path,
+        &backup_password,
+        Some(&restored_name),
+        None,
+        None,
+    )
+    .unwrap();
+    let restored_wallet =
+        EncryptedWallet::open(&restored_name, &restored.master_password, None).unwrap();
+    assert_eq!(restored_wallet.addresses(), wallet.addresses());
+    assert!(restored_wallet.is_imported(Network::Tron));
+
+    // With a decoy, the header keeps describing it.
+    let duress_password =
+        EncryptedWallet::add_decoy(&wallet_name, &master_password, None, None).unwrap();
+    let header = EncryptedWallet::header(&wallet_name).unwrap();
+    EncryptedWallet::import_key(
+        &wallet_name,
+        &master_password,
+        None,
+        Network::Ethereum,
+        &private_key,
+    )
+    .unwrap();
+    assert_eq!(EncryptedWallet::header(&wallet_name).unwrap(), header);
+    // Nor do the slot sizes tell the wallet from the decoy.
+    let file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(format!("{wallet_name}.cwu.json")).unwrap())
+            .unwrap();
+    let slots = file["slots"].as_array().unwrap();
+    assert_eq!(
+        slots[0]["ciphertext_b64"].as_str().unwrap().len(),
+        slots[1]["ciphertext_b64"].as_str().unwrap().len()
+    );
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    assert!(wallet.is_imported(Network::Ethereum));
+    assert!(EncryptedWallet::open(&wallet_name, &duress_password, None).is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_audit_log_detects_tampering() {
+    let dir = std::env::temp_dir().join(format!("cwu-audit-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("audit").to_string_lossy().to_string();
+    let master_password = EncryptedWallet::create(12, "English", &wallet_name, None, None).unwrap();
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    let address = wallet.addresses()[&Network::Tron].clone();
+
+    assert!(wallet.verify_audit_log().unwrap().entries().is_empty());
+    wallet.audit(AuditEvent::new(AuditOperation::Open)).unwrap();
+    wallet
+        .audit(AuditEvent::new(AuditOperation::Backup).with_address(&address))
+        .unwrap();
+    wallet
+        .audit(
+            AuditEvent::new(AuditOperation::Send)
+                .with_address(&address)
+                .with_tx_id("abcd"),
+        )
+        .unwrap();
+
+    let report = wallet.verify_audit_log().unwrap();
+    assert!(report.is_intact());
+    let operations: Vec<_> = report
+        .entries()
+        .iter()
+        .map(|entry| entry.event().operation())
+        .collect();
+    assert_eq!(
+        operations,
+        [
+            AuditOperation::Open,
+            AuditOperation::Backup,
+            AuditOperation::Send
+        ]
+    );
+    assert_eq!(report.entries()[2].event().tx_id(), Some("abcd"));
+
+    let log_path = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.to_string_lossy().ends_with(".cwu-audit.jsonl"))
+        .unwrap();
+    let original = std::fs::read_to_string(&log_path).unwrap();
+    // Nothing but the sequence and the chain is readable without the wallet.
+    assert!(!original.contains(&address.to_string()));
+    let lines: Vec<&str> = original.lines().collect();
+
+    // Editing an entry.
+    let mut line: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+    let entry = line["entry"].as_str().unwrap().to_string();
+    let swapped = if entry.ends_with('A') { 'B' } else { 'A' };
+    line["entry"] = format!("{}{swapped}", &entry[..entry.len() - 1]).into();
+    let edited = [lines[0], &line.to_string(), lines[2]].join("\n");
+    std::fs::write(&log_path, edited).unwrap();
+    let report = wallet.verify_audit_log().unwrap();
+    assert!(
+        report
+            .problems()
+            .contains(&AuditProblem::Tampered { line: 2 })
+    );
+    assert!(
+        report
+            .problems()
+            .contains(&AuditProblem::BrokenChain { line: 3 })
+    );
+
+    // Deleting an entry.
+    std::fs::write(&log_path, [lines[0], lines[2]].join("\n")).unwrap();
+    let report = wallet.verify_audit_log().unwrap();
+    assert!(!report.is_intact());
+    assert!(
+        report
+            .problems()
+            .contains(&AuditProblem::OutOfSequence { line: 2, seq: 2 })
+    );
+
+    // The original log is intact again.
+    std::fs::write(&log_path, &original).unwrap();
+    assert!(wallet.verify_audit_log().unwrap().is_intact());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cache_is_private_to_the_wallet() {
+    let dir = std::env::temp_dir().join(format!("cwu-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wallet_name = dir.join("cache").to_string_lossy().to_string();
+    let master_password = EncryptedWallet::create(12, "English", &wallet_name, None, None).unwrap();
+    let wallet = EncryptedWallet::open(&wallet_name, &master_password, None).unwrap();
+    let address = wallet.addresses()[&Network::Tron].to_string();
+    let name = format!("{address}.history");
+
+    assert!(wallet.read_cache(&name).is_none());
+    wallet.write_cache(&name, "first").unwrap();
+    wallet.write_cache(&name, &address).unwrap();
+    assert_eq!(wallet.read_cache(&name).unwrap().expose_secret(), address);
+
+    // Neither the file names nor the contents tell the address.
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with(".cwu-cache"))
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert!(!files[0].to_string_lossy().contains(&address));
+    assert!(
+        !std::fs::read_to_string(&files[0])
+            .unwrap()
+            .contains(&address)
+    );
+
+    // A decoy doesn't see the entries of the wallet.
+    let duress_password =
+        EncryptedWallet::add_decoy(&wallet_name, &master_password, None, None).unwrap();
+    let decoy = EncryptedWallet::open(&wallet_name, &duress_password, None).unwrap();
+    assert!(decoy.read_cache(&name).is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
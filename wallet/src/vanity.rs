@@ -0,0 +1,446 @@
+//! # Vanity Addresses
+//!
+//! A vanity search tries keys until one has an address matching a prefix and/or a suffix,
+//! e.g. a Tron address starting with `TCWU`. Keys are either random, or the successive
+//! indices `m/44'/coin'/0'/0/i` of a wallet mnemonic. Either way, an imported key is kept
+//! in backup bundles, not derived again when the mnemonic is restored. The search runs on several threads and reports its progress periodically.
+//!
+//! Every extra character makes the search about 58 (Tron base58) or 16 (Ethereum hex)
+//! times longer: check [`VanityPattern::expected_attempts`] before starting. Patterns that
+//! would take days are rejected.
+
+use crate::{EncryptedWallet, Result, WalletError, WalletKeys, network::key_pair_from_secret_key};
+use bip39::Mnemonic;
+use cwu_core_address::NetworkAddress;
+use cwu_model::Network;
+use cwu_security_utils::SecretString;
+use k256::SecretKey;
+use rand::RngCore;
+use std::{
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tiny_hderive::{bip32::ExtendedPrivKey, bip44::ChildNumber};
+use zeroize::Zeroizing;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// The second character of a Tron address is between these two, the first being `T`.
+const TRON_SECOND_CHARACTERS: &str = "9ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// How often the progress is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// How many attempts a thread makes between updates of the shared counter.
+const ATTEMPTS_BATCH: u64 = 64;
+/// Indices of the last, non-hardened, level of a derivation path.
+const MAX_INDEX: u32 = (1 << 31) - 1;
+/// Patterns needing more keys on average are rejected: over a day at a million keys per
+/// second.
+const MAX_EXPECTED_ATTEMPTS: f64 = 1e11;
+
+/// What a vanity address must look like.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VanityPattern {
+    network: Network,
+    prefix: String,
+    suffix: String,
+}
+
+impl VanityPattern {
+    /// A pattern for Tron base58 addresses (case-sensitive, the prefix starts with `T`), or
+    /// Ethereum hex addresses (case-insensitive, with or without `0x`).
+    pub fn new(network: Network, prefix: &str, suffix: &str) -> Result<Self> {
+        let invalid = |reason: String| Err(WalletError::InvalidVanityPattern(reason));
+        if prefix.is_empty() && suffix.is_empty() {
+            return invalid("the prefix and the suffix are both empty".to_string());
+        }
+        let (prefix, suffix) = match network {
+            Network::Tron => {
+                let pattern = format!("{prefix}{suffix}");
+                if let Some(c) = pattern.chars().find(|&c| !BASE58_ALPHABET.contains(c)) {
+                    return invalid(format!("'{c}' never appears in a Tron address"));
+                }
+                let mut chars = prefix.chars();
+                if chars.next().is_some_and(|c| c != 'T') {
+                    return invalid("Tron addresses start with 'T'".to_string());
+                }
+                if chars
+                    .next()
+                    .is_some_and(|c| !TRON_SECOND_CHARACTERS.contains(c))
+                {
+                    return invalid(format!(
+                        "the second character of a Tron address is one of {TRON_SECOND_CHARACTERS}"
+                    ));
+                }
+                (prefix.to_string(), suffix.to_string())
+            }
+            Network::Ethereum => {
+                let prefix = prefix
+                    .strip_prefix("0x")
+                    .unwrap_or(prefix)
+                    .to_ascii_lowercase();
+                let suffix = suffix.to_ascii_lowercase();
+                let pattern = format!("{prefix}{suffix}");
+                if let Some(c) = pattern.chars().find(|c| !c.is_ascii_hexdigit()) {
+                    return invalid(format!("'{c}' never appears in an Ethereum address"));
+                }
+                (prefix, suffix)
+            }
+            Network::Bitcoin => {
+                return invalid("vanity addresses are only supported on Tron and Ethereum".into());
+            }
+        };
+
+        let pattern = Self {
+            network,
+            prefix,
+            suffix,
+        };
+        if pattern.expected_attempts() > MAX_EXPECTED_ATTEMPTS {
+            return invalid(format!(
+                "about {:.0} keys to try, shorten the prefix or the suffix",
+                pattern.expected_attempts()
+            ));
+        }
+        Ok(pattern)
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// The average number of keys to try before finding a match.
+    pub fn expected_attempts(&self) -> f64 {
+        match self.network {
+            Network::Tron => {
+                // The leading `T` always matches, and the second character has fewer options.
+                let second = match self.prefix.len() {
+                    0 | 1 => 1.0,
+                    _ => TRON_SECOND_CHARACTERS.len() as f64,
+                };
+                let others = self.prefix.len().saturating_sub(2) + self.suffix.len();
+                second * 58f64.powi(others as i32)
+            }
+            _ => 16f64.powi((self.prefix.len() + self.suffix.len()) as i32),
+        }
+    }
+
+    fn matches(&self, address: &str) -> bool {
+        match self.network {
+            Network::Ethereum => {
+                let hex = address.trim_start_matches("0x").to_ascii_lowercase();
+                hex.starts_with(&self.prefix) && hex.ends_with(&self.suffix)
+            }
+            _ => address.starts_with(&self.prefix) && address.ends_with(&self.suffix),
+        }
+    }
+
+    /// Searches for a key matching the pattern on `threads` threads.
+    ///
+    /// `on_progress` is called periodically, the search stops and returns `None` when it
+    /// returns `false`, or when the mnemonic indices are exhausted.
+    ///
+    /// The search is CPU-bound and blocks until it ends.
+    pub fn search(
+        &self,
+        source: &VanitySource,
+        threads: usize,
+        mut on_progress: impl FnMut(&VanityProgress) -> bool,
+    ) -> Result<Option<VanityMatch>> {
+        let parent = match source {
+            VanitySource::RandomKeys => None,
+            VanitySource::MnemonicIndices { seed } => {
+                if self.expected_attempts() > MAX_INDEX as f64 {
+                    return Err(WalletError::InvalidVanityPattern(format!(
+                        "about {:.0} keys to try, more than the {MAX_INDEX} indices of the \
+                         mnemonic",
+                        self.expected_attempts()
+                    )));
+                }
+                let coin_type = match self.network {
+                    Network::Tron => 195,
+                    _ => 60,
+                };
+                let path = format!("m/44'/{coin_type}'/0'/0");
+                Some(ExtendedPrivKey::derive(seed.as_slice(), path.as_str()).expect("Valid path"))
+            }
+        };
+
+        let threads = threads.max(1);
+        let stop = AtomicBool::new(false);
+        let attempts = AtomicU64::new(0);
+        let running = AtomicUsize::new(threads);
+        let result = Mutex::new(None);
+        let start = Instant::now();
+
+        thread::scope(|scope| {
+            for worker in 0..threads {
+                let (parent, stop, attempts, running, result) =
+                    (&parent, &stop, &attempts, &running, &result);
+                scope.spawn(move || {
+                    let found = self.work(parent.as_ref(), worker, threads, stop, attempts);
+                    if let Some(found) = found.transpose() {
+                        stop.store(true, Ordering::Relaxed);
+                        let mut result = result.lock().unwrap_or_else(|e| e.into_inner());
+                        result.get_or_insert(found);
+                    }
+                    running.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+
+            let mut last_report = Instant::now();
+            while !stop.load(Ordering::Relaxed) && running.load(Ordering::Relaxed) > 0 {
+                thread::sleep(PROGRESS_INTERVAL / 5);
+                if last_report.elapsed() < PROGRESS_INTERVAL {
+                    continue;
+                }
+                last_report = Instant::now();
+                let progress = VanityProgress {
+                    attempts: attempts.load(Ordering::Relaxed),
+                    elapsed: start.elapsed(),
+                    expected_attempts: self.expected_attempts(),
+                };
+                if !on_progress(&progress) {
+                    stop.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+
+        let result = result.into_inner().unwrap_or_else(|e| e.into_inner());
+        result
+            .transpose()
+            .map(|found| found.map(|found| found.with_attempts(attempts.into_inner())))
+    }
+
+    /// The loop of a worker thread, until a match is found or the search is stopped.
+    fn work(
+        &self,
+        parent: Option<&ExtendedPrivKey>,
+        worker: usize,
+        threads: usize,
+        stop: &AtomicBool,
+        attempts: &AtomicU64,
+    ) -> Result<Option<VanityMatch>> {
+        let mut rng = rand::rng();
+        let mut index = worker as u64;
+        let mut batch = 0;
+        while !stop.load(Ordering::Relaxed) {
+            let (secret_key, found_index) = match parent {
+                None => {
+                    let mut bytes = Zeroizing::new([0u8; 32]);
+                    rng.fill_bytes(bytes.as_mut_slice());
+                    // Out of range keys are astronomically unlikely, just skip them.
+                    let Ok(secret_key) = SecretKey::from_slice(bytes.as_slice()) else {
+                        continue;
+                    };
+                    (secret_key, None)
+                }
+                Some(parent) => {
+                    if index > MAX_INDEX as u64 {
+                        break;
+                    }
+                    let child_index = index as u32;
+                    index += threads as u64;
+                    let Ok(child) = parent.child(ChildNumber::non_hardened_from_u32(child_index))
+                    else {
+                        continue;
+                    };
+                    let secret = Zeroizing::new(child.secret());
+                    (SecretKey::from_slice(secret.as_slice())?, Some(child_index))
+                }
+            };
+
+            let key_pair = key_pair_from_secret_key(self.network, &secret_key)?;
+            batch += 1;
+            if batch == ATTEMPTS_BATCH {
+                attempts.fetch_add(batch, Ordering::Relaxed);
+                batch = 0;
+            }
+            if self.matches(key_pair.address()) {
+                attempts.fetch_add(batch, Ordering::Relaxed);
+                return Ok(Some(VanityMatch {
+                    address: key_pair.network_address(self.network)?,
+                    private_key: SecretString::from(key_pair.private_key()),
+                    index: found_index,
+                    attempts: 0,
+                }));
+            }
+        }
+        attempts.fetch_add(batch, Ordering::Relaxed);
+        Ok(None)
+    }
+}
+
+/// Where the keys of a vanity search come from.
+pub enum VanitySource {
+    RandomKeys,
+    /// The indices of the derivation path of a mnemonic.
+    MnemonicIndices {
+        seed: Zeroizing<[u8; 64]>,
+    },
+}
+
+impl VanitySource {
+    /// The indices of the mnemonic of an unlocked wallet.
+    pub fn wallet_indices(wallet: &EncryptedWallet, keys: &WalletKeys) -> Result<Self> {
+        let mnemonic = keys.decrypt(wallet.mnemonic())?;
+        let mnemonic = Mnemonic::from_str(mnemonic.expose_secret())?;
+        let passphrase = keys.decrypt(wallet.passphrase())?;
+        Ok(VanitySource::MnemonicIndices {
+            seed: Zeroizing::new(mnemonic.to_seed(passphrase.expose_secret())),
+        })
+    }
+}
+
+/// A snapshot of a running vanity search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VanityProgress {
+    attempts: u64,
+    elapsed: Duration,
+    expected_attempts: f64,
+}
+
+impl VanityProgress {
+    pub fn attempts(&self) -> u64 {
+        self.attempts
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Attempts per second.
+    pub fn rate(&self) -> f64 {
+        self.attempts as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// The chance to have found a match by now.
+    pub fn probability(&self) -> f64 {
+        1.0 - (-(self.attempts as f64) / self.expected_attempts).exp()
+    }
+
+    /// The time until a match is found on average, from the current rate.
+    pub fn expected_remaining(&self) -> Option<Duration> {
+        let rate = self.rate();
+        let remaining = (self.expected_attempts - self.attempts as f64).max(0.0);
+        (rate > 0.0).then(|| Duration::from_secs_f64(remaining / rate))
+    }
+}
+
+/// A key whose address matches a [`VanityPattern`].
+#[derive(Debug)]
+pub struct VanityMatch {
+    address: NetworkAddress,
+    /// Hex encoded, as expected by [`EncryptedWallet::import_key`].
+    private_key: SecretString,
+    index: Option<u32>,
+    attempts: u64,
+}
+
+impl VanityMatch {
+    pub fn address(&self) -> &NetworkAddress {
+        &self.address
+    }
+
+    pub fn private_key(&self) -> &SecretString {
+        &self.private_key
+    }
+
+    /// The index of the key in the mnemonic derivation path, for mnemonic searches.
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    /// The number of keys tried by all threads.
+    pub fn attempts(&self) -> u64 {
+        self.attempts
+    }
+
+    fn with_attempts(mut self, attempts: u64) -> Self {
+        self.attempts = attempts;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vanity_pattern() {
+        assert!(VanityPattern::new(Network::Tron, "", "").is_err());
+        assert!(VanityPattern::new(Network::Tron, "CWU", "").is_err());
+        assert!(VanityPattern::new(Network::Tron, "T0", "").is_err());
+        assert!(VanityPattern::new(Network::Tron, "Tc", "").is_err());
+        assert!(VanityPattern::new(Network::Ethereum, "0xcafg", "").is_err());
+        assert!(VanityPattern::new(Network::Bitcoin, "1", "").is_err());
+        assert!(VanityPattern::new(Network::Ethereum, "", "0123456789abcdef").is_err());
+
+        let pattern = VanityPattern::new(Network::Tron, "TCWU", "").unwrap();
+        assert_eq!(pattern.expected_attempts(), 25.0 * 58.0 * 58.0);
+        assert!(pattern.matches("TCWUj5Py5WbP4AxYFz8XgjM3zj8cJrKG4x"));
+        assert!(!pattern.matches("TCWuj5Py5WbP4AxYFz8XgjM3zj8cJrKG4x"));
+
+        let pattern = VanityPattern::new(Network::Ethereum, "0xCAFE", "00").unwrap();
+        assert_eq!(pattern.expected_attempts(), 16f64.powi(6));
+        assert!(pattern.matches("0xCaFe5aAeb6053F3E94C9b9A09f33669435E7Ef00"));
+    }
+
+    #[test]
+    fn test_vanity_search() {
+        let pattern = VanityPattern::new(Network::Tron, "", "a").unwrap();
+        let found = pattern
+            .search(&VanitySource::RandomKeys, 2, |_| true)
+            .unwrap()
+            .unwrap();
+        assert!(found.address().to_string().ends_with('a'));
+        assert!(found.attempts() > 0);
+        assert_eq!(found.index(), None);
+
+        let secret_key =
+            SecretKey::from_slice(&hex::decode(found.private_key().expose_secret()).unwrap())
+                .unwrap();
+        let key_pair = key_pair_from_secret_key(Network::Tron, &secret_key).unwrap();
+        assert_eq!(key_pair.address(), found.address().to_string());
+    }
+
+    #[test]
+    fn test_vanity_search_over_mnemonic_indices() {
+        let mnemonic =
+            Mnemonic::from_str("test test test test test test test test test test test junk")
+                .unwrap();
+        let source = VanitySource::MnemonicIndices {
+            seed: Zeroizing::new(mnemonic.to_seed("")),
+        };
+        // Index 0 is TWer2Ygk5TEheHp3TPuYeqxmB6SsGZmaL6.
+        let pattern = VanityPattern::new(Network::Tron, "TWer", "").unwrap();
+        let found = pattern.search(&source, 3, |_| true).unwrap().unwrap();
+        assert_eq!(found.index(), Some(0));
+        assert_eq!(
+            found.address().to_string(),
+            "TWer2Ygk5TEheHp3TPuYeqxmB6SsGZmaL6"
+        );
+
+        // The indices run out long before this is found.
+        let pattern = VanityPattern::new(Network::Tron, "TWer2Yg", "").unwrap();
+        assert!(matches!(
+            pattern.search(&source, 1, |_| true),
+            Err(WalletError::InvalidVanityPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_vanity_search_can_be_cancelled() {
+        let pattern = VanityPattern::new(Network::Ethereum, "", "012345678").unwrap();
+        let found = pattern
+            .search(&VanitySource::RandomKeys, 1, |progress| {
+                progress.attempts() == 0
+            })
+            .unwrap();
+        assert!(found.is_none());
+    }
+}
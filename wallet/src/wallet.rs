@@ -1,14 +1,28 @@
 use crate::{
-    Result, WalletError, key_pair::KeyPair, language::Lang,
-    network::get_tron_key_pair_from_mnemonic,
+    Result, WalletError,
+    file::{KeyfileRequirement, Slot, WalletFile},
+    header::WalletHeader,
+    key_pair::KeyPair,
+    language::Lang,
+    network::{
+        BitcoinDerivation, get_bitcoin_key_pair_from_mnemonic, get_tron_key_pair_from_mnemonic,
+    },
 };
-use bip39::Mnemonic;
+use bip39::{Language, Mnemonic};
+use cwu_core_address::{BitcoinNetwork, NetworkAddress, network_addresses};
 use cwu_model::Network;
-use cwu_security_utils::EncryptedPayload;
+use cwu_security_utils::{
+    EncryptedPayload, Keyfile, PasswordError, SecretString, decrypt_with_keyfile,
+    encrypt_with_keyfile, estimate_password_strength,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
-use std::{collections::HashMap, fs, path::Path, str::FromStr};
-use zeroize::Zeroize;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::Path,
+    str::FromStr,
+};
 
 #[derive(Debug)]
 pub enum EncryptedWallet {
@@ -16,45 +30,131 @@ pub enum EncryptedWallet {
 }
 
 impl EncryptedWallet {
-    pub fn create(word_count: i32, language: &str, wallet_name: &str) -> Result<String> {
-        create(word_count, language, wallet_name)
+    /// Creates a new wallet and returns its master password.
+    ///
+    /// When `master_password` is `None`, a random one is generated. A user-chosen
+    /// password is rejected if it is too weak.
+    ///
+    /// With a `keyfile`, the wallet can only be opened with both the master password and
+    /// the same keyfile.
+    pub fn create(
+        word_count: i32,
+        language: &str,
+        wallet_name: &str,
+        master_password: Option<SecretString>,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<SecretString> {
+        create(word_count, language, wallet_name, master_password, keyfile)
     }
 
-    pub fn open(wallet_name: &str, mut master_password: String) -> Result<EncryptedWallet> {
-        let encrypted_payload = EncryptedPayload::from_file(wallet_file_name(wallet_name))?;
-        let encrypted_wallet = cwu_security_utils::decrypt(&encrypted_payload, &master_password)?;
-        master_password.zeroize();
+    pub fn open(
+        wallet_name: &str,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<EncryptedWallet> {
+        let wallet_file = WalletFile::read(wallet_file_name(wallet_name))?;
+        let keyfile_requirement = wallet_file.keyfile_requirement();
+        keyfile_requirement.check(keyfile)?;
+
+        let encrypted_wallet = wallet_file.decrypt(master_password, keyfile)?;
+        let encrypted_wallet = encrypted_wallet.expose_secret();
 
-        let version_container: VersionOnly = serde_json::from_str(&encrypted_wallet)?;
-        match version_container.version {
+        let version_container: VersionOnly = serde_json::from_str(encrypted_wallet)?;
+        let wallet = match version_container.version {
             1 => {
-                let v1: EncryptedWalletV1 = serde_json::from_str(&encrypted_wallet)?;
-                Ok(EncryptedWallet::Current(v1))
+                let mut v1: EncryptedWalletV1 = serde_json::from_str(encrypted_wallet)?;
+                v1.keyfile_requirement = keyfile_requirement;
+                EncryptedWallet::Current(v1)
             }
-            _ => Err(WalletError::UnsupportedVersion(version_container.version)),
+            _ => return Err(WalletError::UnsupportedVersion(version_container.version)),
+        };
+
+        // The header is authenticated, but it must also describe this very wallet.
+        if wallet_file.header_describes_payload()
+            && let Some(header) = wallet_file.header()
+            && (header.name() != wallet.name()
+                || header.version() != version_container.version
+                || header
+                    .addresses()
+                    .iter()
+                    .ne(wallet.sorted_addresses().iter()))
+        {
+            return Err(WalletError::HeaderMismatch);
+        }
+        Ok(wallet)
+    }
+
+    /// Reads the plaintext header of a wallet, without the master password.
+    ///
+    /// The header is only verified when the wallet is opened.
+    pub fn header(wallet_name: &str) -> Result<WalletHeader> {
+        let wallet_file = WalletFile::read(wallet_file_name(wallet_name))?;
+        wallet_file
+            .header()
+            .cloned()
+            .ok_or_else(|| WalletError::NoHeader(wallet_name.to_string()))
+    }
+
+    /// Lists the names of the wallets in `dir`, together with their headers if available.
+    pub fn list(dir: impl AsRef<Path>) -> Result<Vec<(String, Option<WalletHeader>)>> {
+        let mut wallets = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path
+                .to_str()
+                .and_then(|path| path.strip_suffix(WALLET_FILE_NAME_SUFFIX))
+            else {
+                continue;
+            };
+            let header = WalletFile::read(&path)
+                .ok()
+                .and_then(|file| file.header().cloned());
+            wallets.push((name.trim_start_matches("./").to_string(), header));
         }
+        wallets.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(wallets)
+    }
+
+    /// Whether the wallet needs a keyfile in addition to the master password.
+    ///
+    /// Only the plaintext envelope is read, so this can be asked before prompting for secrets.
+    pub fn requires_keyfile(wallet_name: &str) -> Result<bool> {
+        let wallet_file = WalletFile::read(wallet_file_name(wallet_name))?;
+        Ok(wallet_file.keyfile_requirement().is_required())
     }
 
     pub(crate) fn key_pair(
         &self,
-        mut master_password: String,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
         network: Network,
     ) -> Result<KeyPair> {
+        self.keyfile_requirement().check(keyfile)?;
         let encrypted_key_pair = self
             .key_pairs()
             .get(&network)
             .ok_or(WalletError::NotFoundKeyPair(network))?;
-        let mut key_pair_str = cwu_security_utils::decrypt(encrypted_key_pair, &master_password)?;
-        master_password.zeroize();
-        let key_pair = serde_json::from_str(&key_pair_str)?;
-        key_pair_str.zeroize();
+        let key_pair_str = decrypt_with_keyfile(encrypted_key_pair, master_password, keyfile)?;
+        let key_pair = serde_json::from_str(key_pair_str.expose_secret())?;
         Ok(key_pair)
     }
 
-    pub fn backup(&self, mut master_password: String) -> Result<String> {
-        let mnemonic = cwu_security_utils::decrypt(self.mnemonic(), &master_password)?;
-        master_password.zeroize();
-        Ok(mnemonic)
+    /// The mnemonic and the passphrase it is salted with, both needed to recover the keys.
+    pub fn backup(
+        &self,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<RecoveryPhrase> {
+        self.keyfile_requirement().check(keyfile)?;
+        Ok(RecoveryPhrase {
+            mnemonic: decrypt_with_keyfile(self.mnemonic(), master_password, keyfile)?,
+            passphrase: decrypt_with_keyfile(self.passphrase(), master_password, keyfile)?,
+        })
+    }
+
+    /// Whether secrets of this (opened) wallet can only be decrypted with a keyfile.
+    pub fn uses_keyfile(&self) -> bool {
+        self.keyfile_requirement().is_required()
     }
 
     pub fn name(&self) -> &str {
@@ -63,12 +163,27 @@ impl EncryptedWallet {
         }
     }
 
-    pub fn addresses(&self) -> &HashMap<Network, String> {
+    pub fn addresses(&self) -> &HashMap<Network, NetworkAddress> {
         match self {
             EncryptedWallet::Current(w) => &w.addresses,
         }
     }
 
+    /// Whether the key pair of `network` was imported, so it can't be recovered from the
+    /// mnemonic.
+    pub fn is_imported(&self, network: Network) -> bool {
+        match self {
+            EncryptedWallet::Current(w) => w.imported.contains(&network),
+        }
+    }
+
+    fn sorted_addresses(&self) -> BTreeMap<Network, NetworkAddress> {
+        self.addresses()
+            .iter()
+            .map(|(network, address)| (*network, address.clone()))
+            .collect()
+    }
+
     pub fn key_pairs(&self) -> &HashMap<Network, EncryptedPayload> {
         match self {
             EncryptedWallet::Current(w) => &w.key_pairs,
@@ -80,6 +195,26 @@ impl EncryptedWallet {
             EncryptedWallet::Current(w) => &w.mnemonic,
         }
     }
+
+    pub(crate) fn passphrase(&self) -> &EncryptedPayload {
+        match self {
+            EncryptedWallet::Current(w) => &w.passphrase,
+        }
+    }
+
+    pub(crate) fn keyfile_requirement(&self) -> &KeyfileRequirement {
+        match self {
+            EncryptedWallet::Current(w) => &w.keyfile_requirement,
+        }
+    }
+}
+
+/// What recovers the keys derived from the mnemonic: the words, and the BIP39 passphrase
+/// they are salted with, which the wallet generates.
+#[derive(Debug)]
+pub struct RecoveryPhrase {
+    pub mnemonic: SecretString,
+    pub passphrase: SecretString,
 }
 
 /// Lightweight Version Container .
@@ -94,8 +229,15 @@ pub struct EncryptedWalletV1 {
     mnemonic: EncryptedPayload,
     passphrase: EncryptedPayload,
     key_pairs: HashMap<Network, EncryptedPayload>,
-    addresses: HashMap<Network, String>,
+    #[serde(with = "network_addresses")]
+    addresses: HashMap<Network, NetworkAddress>,
     version: u32,
+    /// Networks whose key pair was imported rather than derived from the mnemonic.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(crate) imported: BTreeSet<Network>,
+    /// Taken from the file envelope when the wallet is opened.
+    #[serde(skip)]
+    keyfile_requirement: KeyfileRequirement,
 }
 
 impl EncryptedWalletV1 {
@@ -104,7 +246,7 @@ impl EncryptedWalletV1 {
         mnemonic: EncryptedPayload,
         passphrase: EncryptedPayload,
         key_pairs: HashMap<Network, EncryptedPayload>,
-        addresses: HashMap<Network, String>,
+        addresses: HashMap<Network, NetworkAddress>,
     ) -> Self {
         Self {
             name: wallet_name.to_string(),
@@ -113,76 +255,227 @@ impl EncryptedWalletV1 {
             key_pairs,
             addresses,
             version: 1,
+            imported: BTreeSet::new(),
+            keyfile_requirement: KeyfileRequirement::None,
+        }
+    }
+
+    /// Derives the key pairs and encrypts every secret of the wallet.
+    pub(crate) fn encrypt(
+        wallet_name: &str,
+        mnemonic: &Mnemonic,
+        passphrase: SecretString,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<Self> {
+        let mut key_pairs = HashMap::new();
+        let mut addresses = HashMap::new();
+        for (network, key_pair) in derive_key_pairs(mnemonic, &passphrase)? {
+            let address = key_pair.network_address(network)?;
+            let key_pair_str = key_pair.serialize()?;
+            let key_pair_encrypted =
+                encrypt_with_keyfile(key_pair_str.expose_secret(), master_password, keyfile)?;
+            key_pairs.insert(network, key_pair_encrypted);
+            addresses.insert(network, address);
+        }
+
+        let passphrase_encrypted =
+            encrypt_with_keyfile(passphrase.expose_secret(), master_password, keyfile)?;
+        drop(passphrase);
+
+        let mnemonic_str = SecretString::from(mnemonic.to_string());
+        let mnemonic_encrypted =
+            encrypt_with_keyfile(mnemonic_str.expose_secret(), master_password, keyfile)?;
+        drop(mnemonic_str);
+
+        Ok(EncryptedWalletV1::new(
+            wallet_name,
+            mnemonic_encrypted,
+            passphrase_encrypted,
+            key_pairs,
+            addresses,
+        ))
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The mnemonic of the wallet.
+    pub(crate) fn mnemonic(
+        &self,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<Mnemonic> {
+        let words = decrypt_with_keyfile(&self.mnemonic, master_password, keyfile)?;
+        Ok(Mnemonic::parse(words.expose_secret())?)
+    }
+
+    /// Replaces the key pair of `network`, derived or imported before, with an imported one.
+    pub(crate) fn import_key_pair(
+        &mut self,
+        network: Network,
+        key_pair: &KeyPair,
+        master_password: &SecretString,
+        keyfile: Option<&Keyfile>,
+    ) -> Result<NetworkAddress> {
+        let address = key_pair.network_address(network)?;
+        let key_pair_str = key_pair.serialize()?;
+        let key_pair_encrypted =
+            encrypt_with_keyfile(key_pair_str.expose_secret(), master_password, keyfile)?;
+        self.key_pairs.insert(network, key_pair_encrypted);
+        self.addresses.insert(network, address.clone());
+        self.imported.insert(network);
+        Ok(address)
+    }
+
+    pub(crate) fn sorted_addresses(&self) -> BTreeMap<Network, NetworkAddress> {
+        self.addresses
+            .iter()
+            .map(|(network, address)| (*network, address.clone()))
+            .collect()
+    }
+
+    /// The plaintext header describing this wallet.
+    pub(crate) fn header(&self, created_at: Option<u64>) -> WalletHeader {
+        let header = WalletHeader::new(&self.name, self.version, self.sorted_addresses());
+        match created_at {
+            Some(created_at) => header.with_created_at(created_at),
+            None => header,
         }
     }
 }
 
 const PASSWORD_LENGTH: usize = 8;
 
-fn create(word_count: i32, language: &str, wallet_name: &str) -> Result<String> {
+/// Generates a mnemonic, and the BIP39 passphrase salting it.
+pub(crate) fn generate_mnemonic(
+    language: Language,
+    word_count: usize,
+) -> Result<(Mnemonic, SecretString)> {
+    let mnemonic = Mnemonic::generate_in(language, word_count)?;
+    let passphrase = cwu_security_utils::generate_secure_password(PASSWORD_LENGTH)?;
+    Ok((mnemonic, passphrase))
+}
+
+fn create(
+    word_count: i32,
+    language: &str,
+    wallet_name: &str,
+    master_password: Option<SecretString>,
+    keyfile: Option<&Keyfile>,
+) -> Result<SecretString> {
     let language = Lang::from_str(language)?.lang;
-    let mnemonic = Mnemonic::generate_in(language, word_count as usize)?;
+    let (mnemonic, passphrase) = generate_mnemonic(language, word_count as usize)?;
+
+    write_wallet(wallet_name, &mnemonic, passphrase, master_password, keyfile)
+}
+
+/// Encrypts the wallet secrets and saves the wallet file, returning the master password.
+///
+/// Used both for new wallets and for wallets restored from a backup bundle. An existing
+/// wallet is never overwritten.
+pub(crate) fn write_wallet(
+    wallet_name: &str,
+    mnemonic: &Mnemonic,
+    passphrase: SecretString,
+    master_password: Option<SecretString>,
+    keyfile: Option<&Keyfile>,
+) -> Result<SecretString> {
+    let master_password = new_password(master_password, &passphrase)?;
 
-    let mut wallet_password = cwu_security_utils::generate_secure_password(PASSWORD_LENGTH)?;
-    let mut passphrase = cwu_security_utils::generate_secure_password(PASSWORD_LENGTH)?;
-    let master_password = format!("{wallet_password}{passphrase}");
-    wallet_password.zeroize();
+    let wallet =
+        EncryptedWalletV1::encrypt(wallet_name, mnemonic, passphrase, &master_password, keyfile)?;
+    let wallet_json_string = SecretString::from(to_string_pretty(&wallet)?);
+    let wallet_file = WalletFile::new(
+        wallet.header(None),
+        keyfile,
+        &[Slot {
+            plaintext: &wallet_json_string,
+            password: &master_password,
+        }],
+    )?;
+
+    // save wallet to file, never over an existing one
+    wallet_file
+        .write_new(wallet_file_name(wallet_name))
+        .map_err(|e| match e {
+            WalletError::IoError(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                WalletError::WalletExists(wallet_name.to_string())
+            }
+            e => e,
+        })?;
 
-    let mut key_pairs = HashMap::new();
-    let mut addresses = HashMap::new();
+    Ok(master_password)
+}
+
+/// The password of a new wallet: the chosen one if it is strong enough, or a random one
+/// ending in the BIP39 `passphrase` of the wallet.
+pub(crate) fn new_password(
+    chosen: Option<SecretString>,
+    passphrase: &SecretString,
+) -> Result<SecretString> {
+    match chosen {
+        Some(password) => {
+            ensure_strong(&password)?;
+            Ok(password)
+        }
+        None => {
+            let wallet_password = cwu_security_utils::generate_secure_password(PASSWORD_LENGTH)?;
+            Ok(concat_secrets(&wallet_password, passphrase))
+        }
+    }
+}
+
+/// Rejects user-chosen passwords that are too weak to protect a wallet.
+pub(crate) fn ensure_strong(password: &SecretString) -> Result<()> {
+    let estimate = estimate_password_strength(password.expose_secret());
+    if !estimate.is_strong_enough() {
+        return Err(PasswordError::TooWeak(estimate.entropy_bits()).into());
+    }
+    Ok(())
+}
+
+/// Derives the key pair of every supported network from the mnemonic and passphrase.
+pub(crate) fn derive_key_pairs(
+    mnemonic: &Mnemonic,
+    passphrase: &SecretString,
+) -> Result<Vec<(Network, KeyPair)>> {
+    let mut key_pairs = Vec::new();
     for &network in Network::iter() {
         let mut key_pair = KeyPair::default();
         match network {
             Network::Ethereum => {}
             Network::Tron => {
-                key_pair = get_tron_key_pair_from_mnemonic(&mnemonic, &passphrase)?;
+                key_pair = get_tron_key_pair_from_mnemonic(mnemonic, passphrase.expose_secret())?;
+            }
+            Network::Bitcoin => {
+                key_pair = get_bitcoin_key_pair_from_mnemonic(
+                    mnemonic,
+                    passphrase.expose_secret(),
+                    BitcoinDerivation::Bip84,
+                    BitcoinNetwork::Mainnet,
+                )?;
             }
         }
         if key_pair.is_empty() {
             continue;
         }
-        let address = key_pair.address().to_string();
-        let mut key_pair_str = key_pair.serialize()?;
-        let key_pair_encrypted = cwu_security_utils::encrypt(&key_pair_str, &master_password)?;
-        key_pair.zeroize();
-        key_pair_str.zeroize();
-        key_pairs.insert(network, key_pair_encrypted);
-        addresses.insert(network, address);
-    }
-
-    let passphrase_encrypted = cwu_security_utils::encrypt(&passphrase, &master_password)?;
-    passphrase.zeroize();
-
-    let mut mnemonic_str = mnemonic.to_string();
-    let mnemonic_encrypted = cwu_security_utils::encrypt(&mnemonic_str, &master_password)?;
-    mnemonic_str.zeroize();
-    let wallet = EncryptedWalletV1::new(
-        wallet_name,
-        mnemonic_encrypted,
-        passphrase_encrypted,
-        key_pairs,
-        addresses,
-    );
-    let mut wallet_json_string = to_string_pretty(&wallet)?;
-    let wallet_encrypted = cwu_security_utils::encrypt(&wallet_json_string, &master_password)?;
-
-    // save wallet to file
-    write_to_file(
-        to_string_pretty(&wallet_encrypted)?,
-        Path::new(wallet_file_name(wallet_name).as_str()),
-    )?;
-    wallet_json_string.zeroize();
+        key_pairs.push((network, key_pair));
+    }
+    Ok(key_pairs)
+}
 
-    Ok(master_password)
+/// Joins two secrets into a new one without leaving intermediate copies behind.
+fn concat_secrets(first: &SecretString, second: &SecretString) -> SecretString {
+    let mut joined = String::with_capacity(first.len() + second.len());
+    joined.push_str(first.expose_secret());
+    joined.push_str(second.expose_secret());
+    SecretString::from(joined)
 }
 
 const WALLET_FILE_NAME_SUFFIX: &str = ".cwu.json";
 
-fn wallet_file_name(wallet_name: &str) -> String {
+pub(crate) fn wallet_file_name(wallet_name: &str) -> String {
     format!("{wallet_name}{WALLET_FILE_NAME_SUFFIX}")
 }
-
-fn write_to_file(data: String, path: impl AsRef<Path>) -> Result<()> {
-    fs::write(path, data)?;
-    Ok(())
-}
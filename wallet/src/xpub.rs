@@ -0,0 +1,295 @@
+//! # Extended Public Keys
+//!
+//! An account level extended public key (BIP32 xpub, at `m/purpose'/coin'/0'`) derives every
+//! receiving address of the account without any private key. Wallets export one per network,
+//! and a [`WatchOnlyWallet`] imports it to derive the addresses `account/0/i`, e.g. customer
+//! deposit addresses on a web server. Index 0 is the address stored in the signing wallet.
+//!
+//! Bitcoin keys use the SLIP-132 version bytes telling the address type: `xpub` for BIP44,
+//! `ypub` for BIP49 and `zpub` for BIP84 (`tpub`, `upub` and `vpub` on testnet). BIP86 has
+//! no dedicated version, Taproot accounts are `xpub`s set up with
+//! [`WatchOnlyWallet::with_address_type`]. Tron and Ethereum accounts are always `xpub`s.
+
+use crate::{
+    BitcoinDerivation, EncryptedWallet, Result, WalletError, WalletKeys, network::AddressEncoder,
+};
+use bip32::{ChildNumber, DerivationPath, ExtendedKey, Prefix, XPrv, XPub};
+use bip39::Mnemonic;
+use cwu_core_address::{BitcoinAddressType, BitcoinFormat, BitcoinNetwork, NetworkAddress};
+use cwu_model::Network;
+use k256::PublicKey;
+use std::{fmt, str::FromStr};
+use zeroize::Zeroizing;
+
+/// The depth of account level keys, `m/purpose'/coin'/account'`.
+const ACCOUNT_DEPTH: u8 = 3;
+
+const XPUB: Prefix = Prefix::XPUB;
+const YPUB: Prefix = Prefix::YPUB;
+const ZPUB: Prefix = Prefix::ZPUB;
+const TPUB: Prefix = Prefix::TPUB;
+const UPUB: Prefix = Prefix::from_parts_unchecked("upub", 0x044a5262);
+const VPUB: Prefix = Prefix::from_parts_unchecked("vpub", 0x045f1cf6);
+
+/// A BIP32 extended public key and its SLIP-132 version.
+///
+/// Derivation is left to the `bip32` crate, only the version is handled here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedPublicKey {
+    version: Prefix,
+    key: XPub,
+}
+
+impl ExtendedPublicKey {
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from(self.key.public_key())
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.key.attrs().depth
+    }
+
+    /// The non-hardened child `index`, hardened children need the private key.
+    pub fn child(&self, index: u32) -> Result<Self> {
+        let child_number = ChildNumber::new(index, false).map_err(|_| {
+            WalletError::InvalidExtendedKey(format!(
+                "child {index} is hardened, it can't be derived from a public key"
+            ))
+        })?;
+        let key = self.key.derive_child(child_number).map_err(|_| {
+            WalletError::InvalidExtendedKey(format!("child {index} is invalid, use the next one"))
+        })?;
+        Ok(Self {
+            version: self.version,
+            key,
+        })
+    }
+}
+
+impl fmt::Display for ExtendedPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key.to_string(self.version))
+    }
+}
+
+impl FromStr for ExtendedPublicKey {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| WalletError::InvalidExtendedKey(reason.to_string());
+        let extended_key =
+            ExtendedKey::from_str(s.trim()).map_err(|_| invalid("bad base58 encoding"))?;
+        let version = extended_key.prefix;
+        if ![XPUB, YPUB, ZPUB, TPUB, UPUB, VPUB].contains(&version) {
+            return Err(invalid("not an extended public key"));
+        }
+        let key = XPub::try_from(extended_key).map_err(|_| invalid("invalid public key"))?;
+        Ok(Self { version, key })
+    }
+}
+
+/// The SLIP-132 version of an account.
+fn version(encoder: &AddressEncoder) -> Prefix {
+    match encoder {
+        AddressEncoder::Bitcoin(format) => match (format.network, format.address_type) {
+            (BitcoinNetwork::Mainnet, BitcoinAddressType::P2shP2wpkh) => YPUB,
+            (BitcoinNetwork::Mainnet, BitcoinAddressType::P2wpkh) => ZPUB,
+            (BitcoinNetwork::Mainnet, _) => XPUB,
+            (BitcoinNetwork::Testnet, BitcoinAddressType::P2shP2wpkh) => UPUB,
+            (BitcoinNetwork::Testnet, BitcoinAddressType::P2wpkh) => VPUB,
+            (BitcoinNetwork::Testnet, _) => TPUB,
+        },
+        _ => XPUB,
+    }
+}
+
+impl EncryptedWallet {
+    /// The account level extended public key of `network`, `m/44'/coin'/0'`, or BIP84 for
+    /// Bitcoin.
+    pub fn account_xpub(&self, keys: &WalletKeys, network: Network) -> Result<ExtendedPublicKey> {
+        match network {
+            Network::Ethereum => self.xpub(keys, "m/44'/60'/0'", &AddressEncoder::Ethereum),
+            Network::Tron => self.xpub(keys, "m/44'/195'/0'", &AddressEncoder::Tron),
+            Network::Bitcoin => {
+                self.bitcoin_account_xpub(keys, BitcoinDerivation::Bip84, BitcoinNetwork::Mainnet)
+            }
+        }
+    }
+
+    /// The account level extended public key of a Bitcoin derivation scheme.
+    pub fn bitcoin_account_xpub(
+        &self,
+        keys: &WalletKeys,
+        derivation: BitcoinDerivation,
+        network: BitcoinNetwork,
+    ) -> Result<ExtendedPublicKey> {
+        self.xpub(
+            keys,
+            &derivation.account_path(network),
+            &derivation.encoder(network),
+        )
+    }
+
+    fn xpub(
+        &self,
+        keys: &WalletKeys,
+        account_path: &str,
+        encoder: &AddressEncoder,
+    ) -> Result<ExtendedPublicKey> {
+        // The address of an imported key isn't part of the account.
+        if self.is_imported(encoder.network()) {
+            return Err(WalletError::ImportedKey(encoder.network()));
+        }
+        let mnemonic = keys.decrypt(self.mnemonic())?;
+        let mnemonic = Mnemonic::from_str(mnemonic.expose_secret())?;
+        let passphrase = keys.decrypt(self.passphrase())?;
+        let seed = Zeroizing::new(mnemonic.to_seed(passphrase.expose_secret()));
+
+        let path = DerivationPath::from_str(account_path).expect("Valid path");
+        let key = XPrv::derive_from_path(seed.as_slice(), &path)
+            .map_err(|e| WalletError::InvalidExtendedKey(e.to_string()))?;
+        Ok(ExtendedPublicKey {
+            version: version(encoder),
+            key: key.public_key(),
+        })
+    }
+}
+
+/// Derives the receiving addresses of an account from its extended public key, without any
+/// private key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOnlyWallet {
+    account: ExtendedPublicKey,
+    encoder: AddressEncoder,
+}
+
+impl WatchOnlyWallet {
+    /// A watch-only wallet for an account level extended public key of `network`.
+    ///
+    /// Bitcoin addresses get the type of the version bytes: P2PKH for `xpub`/`tpub`, nested
+    /// SegWit for `ypub`/`upub` and native SegWit for `zpub`/`vpub`.
+    pub fn new(network: Network, xpub: &str) -> Result<Self> {
+        let account = ExtendedPublicKey::from_str(xpub)?;
+        if account.depth() != ACCOUNT_DEPTH {
+            return Err(WalletError::InvalidExtendedKey(format!(
+                "expected an account level key (depth {ACCOUNT_DEPTH}), found depth {}",
+                account.depth()
+            )));
+        }
+        if network != Network::Bitcoin && account.version != XPUB {
+            return Err(WalletError::InvalidExtendedKey(format!(
+                "{network} accounts are exported as xpub, found {}",
+                account.version
+            )));
+        }
+        let encoder = match network {
+            Network::Ethereum => AddressEncoder::Ethereum,
+            Network::Tron => AddressEncoder::Tron,
+            Network::Bitcoin => {
+                let (address_type, network) = match account.version {
+                    YPUB => (BitcoinAddressType::P2shP2wpkh, BitcoinNetwork::Mainnet),
+                    ZPUB => (BitcoinAddressType::P2wpkh, BitcoinNetwork::Mainnet),
+                    TPUB => (BitcoinAddressType::P2pkh, BitcoinNetwork::Testnet),
+                    UPUB => (BitcoinAddressType::P2shP2wpkh, BitcoinNetwork::Testnet),
+                    VPUB => (BitcoinAddressType::P2wpkh, BitcoinNetwork::Testnet),
+                    _ => (BitcoinAddressType::P2pkh, BitcoinNetwork::Mainnet),
+                };
+                AddressEncoder::Bitcoin(BitcoinFormat::new(address_type, network))
+            }
+        };
+        Ok(Self { account, encoder })
+    }
+
+    /// Overrides the Bitcoin address type, e.g. Taproot for a BIP86 `xpub`.
+    pub fn with_address_type(mut self, address_type: BitcoinAddressType) -> Self {
+        if let AddressEncoder::Bitcoin(format) = &mut self.encoder {
+            format.address_type = address_type;
+        }
+        self
+    }
+
+    pub fn network(&self) -> Network {
+        self.encoder.network()
+    }
+
+    pub fn account(&self) -> &ExtendedPublicKey {
+        &self.account
+    }
+
+    /// The receiving address `index`, i.e. `account/0/index`.
+    pub fn address(&self, index: u32) -> Result<NetworkAddress> {
+        let key = self.account.child(0)?.child(index)?;
+        self.encoder.address(&key.public_key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn account(path: &str, version: Prefix) -> ExtendedPublicKey {
+        let seed = Mnemonic::from_str(MNEMONIC).unwrap().to_seed("");
+        let path = DerivationPath::from_str(path).unwrap();
+        ExtendedPublicKey {
+            version,
+            key: XPrv::derive_from_path(seed, &path).unwrap().public_key(),
+        }
+    }
+
+    /// The account keys and addresses of BIP84 and BIP49.
+    #[test]
+    fn test_account_xpub() {
+        let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+        assert_eq!(account("m/84'/0'/0'", ZPUB).to_string(), zpub);
+        assert_eq!(ExtendedPublicKey::from_str(zpub).unwrap().to_string(), zpub);
+
+        let wallet = WatchOnlyWallet::new(Network::Bitcoin, zpub).unwrap();
+        assert_eq!(
+            wallet.address(0).unwrap().to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+        assert_eq!(
+            wallet.address(1).unwrap().to_string(),
+            "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g"
+        );
+
+        let wallet =
+            WatchOnlyWallet::new(Network::Bitcoin, &account("m/49'/1'/0'", UPUB).to_string())
+                .unwrap();
+        assert_eq!(
+            wallet.address(0).unwrap().to_string(),
+            "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2"
+        );
+    }
+
+    #[test]
+    fn test_watch_only_wallet() {
+        let xpub = account("m/86'/0'/0'", XPUB).to_string();
+        let wallet = WatchOnlyWallet::new(Network::Bitcoin, &xpub)
+            .unwrap()
+            .with_address_type(BitcoinAddressType::P2tr);
+        assert_eq!(
+            wallet.address(0).unwrap().to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+
+        // Only account level keys are accepted.
+        let xpub = account("m/44'/195'/0'", XPUB).child(0).unwrap().to_string();
+        assert!(matches!(
+            WatchOnlyWallet::new(Network::Tron, &xpub),
+            Err(WalletError::InvalidExtendedKey(_))
+        ));
+        assert!(WatchOnlyWallet::new(Network::Tron, "xpub").is_err());
+        assert!(account("m/44'/195'/0'", XPUB).child(1 << 31).is_err());
+
+        // Tron and Ethereum accounts are xpubs, other versions tell a Bitcoin account.
+        let zpub = account("m/44'/195'/0'", ZPUB).to_string();
+        assert!(matches!(
+            WatchOnlyWallet::new(Network::Tron, &zpub),
+            Err(WalletError::InvalidExtendedKey(_))
+        ));
+        assert!(WatchOnlyWallet::new(Network::Ethereum, &zpub).is_err());
+    }
+}